@@ -0,0 +1,348 @@
+app-title = PurpleAir GUI
+
+## Status
+
+status-fetching = Sensordaten werden abgerufen...
+status-failed = Die Sensordaten konnten nicht abgerufen werden ({ $error }). Neuer Versuch...
+announcement = Die Luftqualität ist jetzt { $category }. PM2,5-AQI { $aqi } (vorher { $previous })
+notification-summary = Die Luftqualität ist jetzt { $category }
+notification-body = PM2,5-AQI { $aqi } (vorher { $previous })
+webhook-summary-aqi = PM2,5-AQI { $aqi } ({ $category })
+webhook-summary-humidity = { $value } % Luftfeuchtigkeit
+
+## AQI-Kategorien und Kacheln
+
+category-good = Gut
+category-moderate = Mäßig
+category-usg = Ungesund für empfindliche Gruppen
+    .short = Ungesund (empfindlich)
+category-unhealthy = Ungesund
+category-very-unhealthy = Sehr ungesund
+category-hazardous = Gefährlich
+
+aqi-channel-a = Kanal A PM2,5-AQI
+    .spoken = PM2,5-AQI von Kanal A
+aqi-channel-b = Kanal B PM2,5-AQI
+    .spoken = PM2,5-AQI von Kanal B
+aqi-pm10-channel-a = Kanal A PM10-AQI
+    .spoken = PM10-AQI von Kanal A
+aqi-pm10-channel-b = Kanal B PM10-AQI
+    .spoken = PM10-AQI von Kanal B
+concentrations-channel-a = Kanal A µg/m³
+    .spoken = Konzentrationen von Kanal A
+concentrations-channel-b = Kanal B µg/m³
+    .spoken = Konzentrationen von Kanal B
+concentration-atm = ATM
+concentration-cf-1 = CF=1
+pm1-0 = PM1,0
+pm2-5 = PM2,5
+pm10 = PM10
+concentration-spoken = { $size }: { $atm } ATM, { $cf_1 } CF=1, Mikrogramm pro Kubikmeter
+aqi-tile-spoken = { $channel }, { $aqi }, { $category }
+aqi-tile-description = Luftqualitätskategorie { $level } von { $count }
+category-level = Stufe { $level } von { $count }
+
+## Temperatur und Luftfeuchtigkeit
+
+temperature-value = { $value } { $unit ->
+    [celsius] °C
+   *[fahrenheit] °F
+}
+temperature-spoken = Temperatur, { $value } { $unit ->
+    [celsius] Grad Celsius
+   *[fahrenheit] Grad Fahrenheit
+}, unkorrigiert
+    .description = Im Sensorgehäuse gemessen, das meist einige Grad wärmer ist als die Luft
+dew-point-spoken = Taupunkt, { $value } { $unit ->
+    [celsius] Grad Celsius
+   *[fahrenheit] Grad Fahrenheit
+}
+    .description = Die Temperatur, bei der die Luft mit Wasserdampf gesättigt wäre
+humidity-value = { $value } %
+humidity-spoken = Relative Luftfeuchtigkeit, { $value } Prozent, unkorrigiert
+    .description = Im Sensorgehäuse gemessen, das meist trockener ist als die Luft
+icon-thermometer = Thermometer
+    .description = Temperatursymbol
+icon-water-drop = Wassertropfen
+    .description = Taupunktsymbol
+icon-percent = Prozentzeichen
+    .description = Luftfeuchtigkeitssymbol
+heat-index = Hitzeindex
+heat-index-spoken = Hitzeindex, { $value } { $unit ->
+    [celsius] Grad Celsius
+   *[fahrenheit] Grad Fahrenheit
+}
+    .description = Wie heiß es sich mit der Luftfeuchtigkeit anfühlt, aus den unkorrigierten Messwerten
+humidex = Humidex
+humidex-spoken = Humidex, { $value }
+    .description = Das kanadische Maß dafür, wie heiß es sich mit der Luftfeuchtigkeit anfühlt, aus den unkorrigierten Messwerten
+absolute-humidity = Absolute Feuchte
+absolute-humidity-value = { $value } g/m³
+absolute-humidity-spoken = Absolute Feuchte, { $value } Gramm Wasser pro Kubikmeter
+    .description = Der Wasserdampf in der Luft, aus den unkorrigierten Messwerten
+comfort-zone = Behaglichkeit
+comfort-zone-spoken = Behaglichkeit, { $value }
+    .description = Verglichen mit dem üblichen Innenraumbereich von 20 bis 26 °C und 30 bis 60 Prozent Luftfeuchtigkeit
+comfort-cold = Zu kalt
+comfort-dry = Zu trocken
+comfort-comfortable = Behaglich
+comfort-humid = Zu feucht
+comfort-hot = Zu warm
+
+## Aktuelle Seite und Diagramm
+
+reading-averaged = 2-Minuten-Mittel
+reading-live = LIVE
+reading-summary = { $kind } ({ $samples ->
+    [one] { $samples } Messung
+   *[other] { $samples } Messungen
+})
+reading-summary-updated = { $kind } ({ $samples ->
+    [one] { $samples } Messung
+   *[other] { $samples } Messungen
+}, aktualisiert um { $time })
+reading-summary-mean = { $kind } ({ $samples ->
+    [one] { $samples } Messung
+   *[other] { $samples } Messungen
+}, aktualisiert um { $time }, mittlerer AQI { $mean })
+live-mode = Live-Modus: { $on ->
+    [yes] An
+   *[no] Aus
+}
+recording = Aufnahme: { $on ->
+    [yes] An
+   *[no] Aus
+}
+chart-title = PM2,5-AQI, { $summary }
+chart-empty = Noch keine Messwerte.
+chart-max = max. { $aqi }
+
+## Diagnose
+
+diagnostics-title = Diagnose
+field = { $label }: { $value }
+diagnostics-sensor = Sensor
+diagnostics-firmware = Firmware-Version
+diagnostics-hardware = Hardware-Version
+diagnostics-devices = Geräte
+diagnostics-uptime = Laufzeit
+diagnostics-wifi = WLAN-Signal
+diagnostics-memory = Freier Speicher
+diagnostics-uploads = Uploads
+diagnostics-source = Quelle
+diagnostics-readings = Messwerte
+diagnostics-last-error = Letzter Fehler
+diagnostics-webhooks = Webhook-Zustellungen
+delivery-delivered = { $time } { $kind } an { $url }: zugestellt (HTTP { $status })
+delivery-failed = { $time } { $kind } an { $url }: nach { $attempts } Versuchen fehlgeschlagen ({ $error })
+payload-alert = Warnung
+payload-summary = Zusammenfassung
+uptime = { $days } T. { $hours } Std. { $minutes } Min.
+memory-value = { $bytes } Bytes
+uploads-value = { $succeeded } von { $sent } erfolgreich
+readings-value = { $received } empfangen, { $failed } fehlgeschlagen
+diagnostics-channels = Kanäle
+diagnostics-maintenance = Wartung
+drift-trend = Verhältnis B/A { $ratio }, ändert sich um { $change } pro Woche in den letzten { $weeks } Wochen
+drift-no-trend = Noch nicht genug Verlauf, um die Kanäle zu vergleichen ({ $days } von { $needed } Tagen)
+drift-drift = Kanal { $channel } misst { $percent } % weniger als der andere Kanal als früher, sein Laser lässt möglicherweise nach.
+drift-no-counts = Kanal { $channel } zählt keine Partikel, der andere Kanal schon.
+drift-saturated = Kanal { $channel } hängt bei seinem Höchstwert fest, der andere Kanal nicht.
+channel-a = A
+channel-b = B
+maintenance-clean = Den Lufteinlass mit Druckluft ausblasen und in ein paar Tagen erneut prüfen
+maintenance-replace = Den Partikelzähler austauschen
+
+## Aktualität
+
+duration = { $unit ->
+    [seconds] { $count ->
+        [one] { $count } Sekunde
+       *[other] { $count } Sekunden
+    }
+    [minutes] { $count ->
+        [one] { $count } Minute
+       *[other] { $count } Minuten
+    }
+   *[hours] { $count ->
+        [one] { $count } Stunde
+       *[other] { $count } Stunden
+    }
+}
+updated-ago = Aktualisiert vor { $duration }
+stale-warning = Seit { $duration } keine neuen Daten, die angezeigten Werte sind möglicherweise veraltet.
+clock-skew = Die Uhr des Sensors geht gegenüber diesem Computer { $duration } { $direction ->
+    [ahead] vor
+   *[behind] nach
+}.
+clock-offset = { $duration } { $direction ->
+    [ahead] vor
+   *[behind] nach
+}
+diagnostics-sensor-time = Sensorzeit
+
+## Karte
+
+map-title = Sensorkarte
+map-no-locations = Noch hat kein Sensor seinen Standort gemeldet.
+map-marker = { $sensor }: { $aqi }
+map-marker-spoken = { $sensor }, { $place ->
+    [inside] innen
+   *[other] außen
+}, PM2,5-AQI { $aqi }, { $category }
+
+## Innen und außen
+
+comparison-title = Innen und außen
+comparison-no-pair = Füge in den Einstellungen einen Innen- und einen Außensensor hinzu, um sie zu vergleichen. Jeder Sensor meldet, ob er drinnen oder draußen steht.
+comparison-waiting = Warte auf Messwerte beider Sensoren, die nur wenige Minuten auseinander liegen.
+comparison-indoor = Innen
+comparison-outdoor = Außen
+comparison-reading = { $sensor }: PM2,5 { $pm2_5 } µg/m³, AQI { $aqi }
+comparison-ratio = Verhältnis innen/außen
+comparison-ratio-unknown = zu wenig PM2,5 draußen für einen Vergleich
+comparison-keep-closed = Fenster geschlossen halten
+    .description = Die Luft draußen ist schlechter als drinnen.
+comparison-ventilate = Lüften ist unbedenklich
+    .description = Die Luft draußen ist gut oder sauberer als drinnen.
+comparison-lag = Die Werte drinnen folgen Spitzen draußen nach etwa { $duration }.
+comparison-lag-immediate = Die Werte drinnen folgen Spitzen draußen innerhalb weniger Minuten.
+comparison-lag-unknown = Die Werte drinnen folgen denen draußen noch nicht deutlich genug, um zu sagen, wie schnell Außenluft hereinkommt.
+comparison-chart = Verhältnis von PM2,5 innen zu außen, bis { $max }. Unter 1 hält das Gebäude Partikel ab, über 1 entstehen sie drinnen.
+
+## Berichte
+
+reports-title = Berichte
+report-title = { $period ->
+    [week] Wochenbericht
+   *[day] Tagesbericht
+} für { $sensor }, { $dates }
+report-week = { $first } – { $last }
+report-period-day = Täglich
+report-period-week = Wöchentlich
+report-format-html = HTML
+report-format-markdown = Markdown
+report-earlier = Früher
+report-later = Später
+report-export = Als { $format } exportieren
+report-exported = Gespeichert unter { $path }
+report-export-failed = Der Bericht konnte nicht gespeichert werden: { $error }
+report-loading = Bericht wird geladen...
+report-no-sensor = Es wurde noch kein Sensor gelesen.
+report-no-readings = Für diesen Sensor wurden in diesem Zeitraum keine Messwerte aufgezeichnet.
+report-aqi = PM2,5-AQI
+report-aqi-value = min. { $min }, Mittel { $mean }, max. { $max }
+report-peak = Spitze
+report-peak-value = { $aqi } um { $time }
+report-peak-value-week = { $aqi } am { $date } um { $time }
+report-temperature = Temperatur
+report-range = { $min } bis { $max }
+report-readings = Messwerte
+report-readings-value = { $count } über { $hours }
+report-restarts = Neustarts des Sensors
+report-uploads = Uploads
+report-uploads-value = { $succeeded } von { $sent } erfolgreich ({ $percent } %)
+report-no-uploads = keine gesendet
+report-hours = { $hours } h
+report-category-hours = Zeit je Kategorie
+report-generated = Erstellt am { $date } um { $time }.
+
+## Kalender
+
+calendar-heading = Kalender
+calendar-title = Täglicher AQI von { $sensor }
+calendar-loading = Wird geladen...
+calendar-day-spoken = { $date }, { $statistic }: AQI { $aqi }, { $category }
+calendar-day-empty = { $date }: keine Messwerte
+calendar-day-summary = Mittlerer AQI { $mean }, höchster { $max }
+calendar-no-data = Keine Messwerte
+calendar-close-day = Schließen
+daily-mean = Tagesmittel
+daily-max = Tageshöchstwert
+
+## Ereignisse
+
+event-smoke = Rauch
+event-dust = Staub
+event-cooking = Kochen
+event-description = { $kind } von { $start } bis { $end }, PM2,5 bis zu { $peak } µg/m³
+event-description-ongoing = { $kind } seit { $start }, PM2,5 bis zu { $peak } µg/m³
+event-ongoing = { $kind } erkannt, PM2,5 bis zu { $peak } µg/m³
+
+## Navigation
+
+nav-current = Aktuell
+nav-chart = Diagramm
+nav-diagnostics = Diagnose
+nav-map = Karte
+nav-comparison = Innen/außen
+nav-reports = Berichte
+nav-calendar = Kalender
+nav-find-sensors = Sensoren suchen
+nav-settings = Einstellungen
+nav-back = Zurück
+keyboard-shortcut = Tastenkürzel: { $keys }
+
+## Suche
+
+discovery-title = Sensoren im lokalen Netzwerk
+discovery-scanning = Suche läuft...
+discovery-none = Keine Sensoren gefunden.
+discovery-selected = { $sensor } (ausgewählt)
+discovery-sensor = { $id } - { $geo } ({ $place }) unter { $url }
+discovery-scan-again = Erneut suchen
+
+## Einstellungen
+
+settings-title = Einstellungen
+settings-saved-to = Gespeichert in { $path }
+settings-no-directory = Es wurde kein Konfigurationsverzeichnis gefunden, Änderungen gelten nur bis zum Schließen der App.
+settings-read-error = Die Konfigurationsdatei konnte nicht gelesen werden, Änderungen werden erst nach ihrer Korrektur gespeichert: { $error }
+settings-sensors = Sensoren
+settings-active-sensor = { $sensor } (aktiv)
+settings-remove-sensor = Entfernen
+settings-add-sensor = Über „Sensoren suchen“ hinzufügen
+setting-language = Sprache
+setting-source = Datenquelle
+setting-poll-interval = Abfrageintervall
+poll-interval-value = { $seconds } s
+setting-temperature = Temperatur
+setting-comfort = Komfortwerte
+setting-aqi-scheme = AQI-Schema
+setting-aqi-display = AQI für
+setting-concentrations = Konzentrationen
+setting-theme = Design
+setting-aqi-colors = AQI-Farben
+aqi-colors-epa = EPA
+aqi-colors-colorblind = Farbenblind-sicher
+setting-alerts = Warnungen
+setting-alert-from = Warnen ab
+setting-stale-after = Ausgrauen nach
+setting-night-mode = Nachtmodus
+setting-at-night = Nachts
+setting-daily-report = Tagesbericht
+night-dim = Dimmen
+night-blank = Aus
+on = An
+off = Aus
+
+source-local = Lokal
+source-cloud = Cloud
+source-replay = Wiedergabe
+source-simulator = Simulator
+unit-fahrenheit = Fahrenheit
+unit-celsius = Celsius
+scheme-us-epa = US EPA
+scheme-us-epa-corrected = US EPA (korrigiert)
+scheme-lrapa = LRAPA
+scheme-aqandu = AQ&U
+aqi-display-pm2-5 = PM2,5
+aqi-display-pm10 = PM10
+aqi-display-both = PM2,5 und PM10
+theme-dark = Dunkel
+theme-light = Hell
+theme-high-contrast = Hoher Kontrast
+theme-custom = Eigenes
+night-off = Aus
+night-schedule = Zeitplan
+night-sun = Sonnenuntergang bis -aufgang
@@ -0,0 +1,348 @@
+app-title = PurpleAir GUI
+
+## Status
+
+status-fetching = Fetching the sensor data...
+status-failed = Failed to fetch the sensor data ({ $error }). Retrying...
+announcement = Air quality is now { $category }. PM2.5 AQI { $aqi } (was { $previous })
+notification-summary = Air quality is now { $category }
+notification-body = PM2.5 AQI { $aqi } (was { $previous })
+webhook-summary-aqi = PM2.5 AQI { $aqi } ({ $category })
+webhook-summary-humidity = { $value }% humidity
+
+## AQI categories and tiles
+
+category-good = Good
+category-moderate = Moderate
+category-usg = Unhealthy for Sensitive Groups
+    .short = Unhealthy (Sensitive)
+category-unhealthy = Unhealthy
+category-very-unhealthy = Very Unhealthy
+category-hazardous = Hazardous
+
+aqi-channel-a = Ch A PM2.5 AQI
+    .spoken = Channel A PM2.5 AQI
+aqi-channel-b = Ch B PM2.5 AQI
+    .spoken = Channel B PM2.5 AQI
+aqi-pm10-channel-a = Ch A PM10 AQI
+    .spoken = Channel A PM10 AQI
+aqi-pm10-channel-b = Ch B PM10 AQI
+    .spoken = Channel B PM10 AQI
+concentrations-channel-a = Ch A µg/m³
+    .spoken = Channel A concentrations
+concentrations-channel-b = Ch B µg/m³
+    .spoken = Channel B concentrations
+concentration-atm = ATM
+concentration-cf-1 = CF=1
+pm1-0 = PM1.0
+pm2-5 = PM2.5
+pm10 = PM10
+concentration-spoken = { $size }: { $atm } ATM, { $cf_1 } CF=1, micrograms per cubic meter
+aqi-tile-spoken = { $channel }, { $aqi }, { $category }
+aqi-tile-description = Air quality category { $level } of { $count }
+category-level = Level { $level } of { $count }
+
+## Temperature and humidity
+
+temperature-value = { $value } { $unit ->
+    [celsius] °C
+   *[fahrenheit] °F
+}
+temperature-spoken = Temperature, { $value } { $unit ->
+    [celsius] degrees Celsius
+   *[fahrenheit] degrees Fahrenheit
+}, uncorrected
+    .description = Measured inside the sensor housing, which usually reads several degrees warmer than the air
+dew-point-spoken = Dew point, { $value } { $unit ->
+    [celsius] degrees Celsius
+   *[fahrenheit] degrees Fahrenheit
+}
+    .description = The temperature at which the air would be saturated with water vapor
+humidity-value = { $value }%
+humidity-spoken = Relative humidity, { $value } percent, uncorrected
+    .description = Measured inside the sensor housing, which usually reads drier than the air
+icon-thermometer = Thermometer
+    .description = Temperature icon
+icon-water-drop = Water drop
+    .description = Dew point icon
+icon-percent = Percent sign
+    .description = Humidity icon
+heat-index = Heat Index
+heat-index-spoken = Heat index, { $value } { $unit ->
+    [celsius] degrees Celsius
+   *[fahrenheit] degrees Fahrenheit
+}
+    .description = How hot it feels with the humidity, from the uncorrected readings
+humidex = Humidex
+humidex-spoken = Humidex, { $value }
+    .description = The Canadian measure of how hot it feels with the humidity, from the uncorrected readings
+absolute-humidity = Absolute Humidity
+absolute-humidity-value = { $value } g/m³
+absolute-humidity-spoken = Absolute humidity, { $value } grams of water per cubic meter
+    .description = The water vapor in the air, from the uncorrected readings
+comfort-zone = Comfort
+comfort-zone-spoken = Comfort, { $value }
+    .description = Compared with the usual indoor range of 68 to 79 °F and 30 to 60 percent humidity
+comfort-cold = Too Cold
+comfort-dry = Too Dry
+comfort-comfortable = Comfortable
+comfort-humid = Too Humid
+comfort-hot = Too Hot
+
+## Current page and chart
+
+reading-averaged = 2-min average
+reading-live = LIVE
+reading-summary = { $kind } ({ $samples ->
+    [one] { $samples } sample
+   *[other] { $samples } samples
+})
+reading-summary-updated = { $kind } ({ $samples ->
+    [one] { $samples } sample
+   *[other] { $samples } samples
+}, updated { $time })
+reading-summary-mean = { $kind } ({ $samples ->
+    [one] { $samples } sample
+   *[other] { $samples } samples
+}, updated { $time }, mean AQI { $mean })
+live-mode = Live Mode: { $on ->
+    [yes] On
+   *[no] Off
+}
+recording = Recording: { $on ->
+    [yes] On
+   *[no] Off
+}
+chart-title = PM2.5 AQI, { $summary }
+chart-empty = No readings yet.
+chart-max = max { $aqi }
+
+## Diagnostics
+
+diagnostics-title = Diagnostics
+field = { $label }: { $value }
+diagnostics-sensor = Sensor
+diagnostics-firmware = Firmware Version
+diagnostics-hardware = Hardware Version
+diagnostics-devices = Devices
+diagnostics-uptime = Uptime
+diagnostics-wifi = Wi-Fi Signal
+diagnostics-memory = Free Memory
+diagnostics-uploads = Uploads
+diagnostics-source = Source
+diagnostics-readings = Readings
+diagnostics-last-error = Last Error
+diagnostics-webhooks = Webhook Deliveries
+delivery-delivered = { $time } { $kind } to { $url }: delivered (HTTP { $status })
+delivery-failed = { $time } { $kind } to { $url }: failed after { $attempts } attempts ({ $error })
+payload-alert = Alert
+payload-summary = Summary
+uptime = { $days }d { $hours }h { $minutes }m
+memory-value = { $bytes } bytes
+uploads-value = { $succeeded } of { $sent } succeeded
+readings-value = { $received } received, { $failed } failed
+diagnostics-channels = Channels
+diagnostics-maintenance = Maintenance
+drift-trend = B/A ratio { $ratio }, changing by { $change } a week over the last { $weeks } weeks
+drift-no-trend = Not enough history to compare the channels yet ({ $days } of { $needed } days)
+drift-drift = Channel { $channel } reads { $percent } % less than the other channel than it used to, its laser may be wearing out.
+drift-no-counts = Channel { $channel } counts no particles while the other channel does.
+drift-saturated = Channel { $channel } is stuck at its highest count while the other channel is not.
+channel-a = A
+channel-b = B
+maintenance-clean = Blow out the air inlet with compressed air and check again in a few days
+maintenance-replace = Replace the particle counter
+
+## Freshness
+
+duration = { $unit ->
+    [seconds] { $count ->
+        [one] { $count } second
+       *[other] { $count } seconds
+    }
+    [minutes] { $count ->
+        [one] { $count } minute
+       *[other] { $count } minutes
+    }
+   *[hours] { $count ->
+        [one] { $count } hour
+       *[other] { $count } hours
+    }
+}
+updated-ago = Updated { $duration } ago
+stale-warning = No new data for { $duration }, the values shown may be out of date.
+clock-skew = The sensor's clock is { $duration } { $direction ->
+    [ahead] ahead of
+   *[behind] behind
+} this computer.
+clock-offset = { $duration } { $direction ->
+    [ahead] ahead
+   *[behind] behind
+}
+diagnostics-sensor-time = Sensor Time
+
+## Map
+
+map-title = Sensor Map
+map-no-locations = None of the sensors has reported its location yet.
+map-marker = { $sensor }: { $aqi }
+map-marker-spoken = { $sensor }, { $place ->
+    [inside] indoors
+   *[other] outdoors
+}, PM2.5 AQI { $aqi }, { $category }
+
+## Indoor and outdoor
+
+comparison-title = Indoor vs. Outdoor
+comparison-no-pair = Add an indoor and an outdoor sensor in the settings to compare them. Each sensor reports whether it is placed inside or outside.
+comparison-waiting = Waiting for readings from both sensors taken within a few minutes of each other.
+comparison-indoor = Indoor
+comparison-outdoor = Outdoor
+comparison-reading = { $sensor }: PM2.5 { $pm2_5 } µg/m³, AQI { $aqi }
+comparison-ratio = Indoor/Outdoor Ratio
+comparison-ratio-unknown = too little outdoor PM2.5 to compare
+comparison-keep-closed = Keep windows closed
+    .description = The outdoor air is worse than the indoor air.
+comparison-ventilate = Safe to ventilate
+    .description = The outdoor air is good, or cleaner than the indoor air.
+comparison-lag = Indoor levels follow outdoor peaks after about { $duration }.
+comparison-lag-immediate = Indoor levels follow outdoor peaks within minutes.
+comparison-lag-unknown = Indoor levels do not follow the outdoor ones closely enough yet to tell how fast outdoor air gets in.
+comparison-chart = Indoor/outdoor PM2.5 ratio, up to { $max }. Below 1 the building keeps particles out, above 1 they come from inside.
+
+## Reports
+
+reports-title = Reports
+report-title = { $period ->
+    [week] Weekly
+   *[day] Daily
+} report for { $sensor }, { $dates }
+report-week = { $first } – { $last }
+report-period-day = Daily
+report-period-week = Weekly
+report-format-html = HTML
+report-format-markdown = Markdown
+report-earlier = Earlier
+report-later = Later
+report-export = Export { $format }
+report-exported = Saved to { $path }
+report-export-failed = Could not save the report: { $error }
+report-loading = Loading the report...
+report-no-sensor = No sensor has been read yet.
+report-no-readings = No readings were recorded for this sensor in this period.
+report-aqi = PM2.5 AQI
+report-aqi-value = min { $min }, mean { $mean }, max { $max }
+report-peak = Peak
+report-peak-value = { $aqi } at { $time }
+report-peak-value-week = { $aqi } on { $date } at { $time }
+report-temperature = Temperature
+report-range = { $min } to { $max }
+report-readings = Readings
+report-readings-value = { $count } covering { $hours }
+report-restarts = Sensor Restarts
+report-uploads = Uploads
+report-uploads-value = { $succeeded } of { $sent } succeeded ({ $percent } %)
+report-no-uploads = none sent
+report-hours = { $hours } h
+report-category-hours = Time per Category
+report-generated = Generated on { $date } at { $time }.
+
+## Calendar
+
+calendar-heading = Calendar
+calendar-title = Daily AQI of { $sensor }
+calendar-loading = Loading...
+calendar-day-spoken = { $date }, { $statistic }: AQI { $aqi }, { $category }
+calendar-day-empty = { $date }: no readings
+calendar-day-summary = Mean AQI { $mean }, highest { $max }
+calendar-no-data = No readings
+calendar-close-day = Close
+daily-mean = Daily Mean
+daily-max = Daily Max
+
+## Events
+
+event-smoke = Smoke
+event-dust = Dust
+event-cooking = Cooking
+event-description = { $kind } from { $start } to { $end }, PM2.5 up to { $peak } µg/m³
+event-description-ongoing = { $kind } since { $start }, PM2.5 up to { $peak } µg/m³
+event-ongoing = { $kind } detected, PM2.5 up to { $peak } µg/m³
+
+## Navigation
+
+nav-current = Current
+nav-chart = Chart
+nav-diagnostics = Diagnostics
+nav-map = Map
+nav-comparison = Indoor/Outdoor
+nav-reports = Reports
+nav-calendar = Calendar
+nav-find-sensors = Find Sensors
+nav-settings = Settings
+nav-back = Back
+keyboard-shortcut = Keyboard shortcut: { $keys }
+
+## Discovery
+
+discovery-title = Sensors on the local network
+discovery-scanning = Scanning...
+discovery-none = No sensors found.
+discovery-selected = { $sensor } (selected)
+discovery-sensor = { $id } - { $geo } ({ $place }) at { $url }
+discovery-scan-again = Scan Again
+
+## Settings
+
+settings-title = Settings
+settings-saved-to = Saved to { $path }
+settings-no-directory = No config directory was found, changes are kept until the app is closed.
+settings-read-error = The config file could not be read, changes are not saved until it is fixed: { $error }
+settings-sensors = Sensors
+settings-active-sensor = { $sensor } (active)
+settings-remove-sensor = Remove
+settings-add-sensor = Add from Find Sensors
+setting-language = Language
+setting-source = Data Source
+setting-poll-interval = Poll Interval
+poll-interval-value = { $seconds } s
+setting-temperature = Temperature
+setting-comfort = Comfort Metrics
+setting-aqi-scheme = AQI Scheme
+setting-aqi-display = AQI For
+setting-concentrations = Concentrations
+setting-theme = Theme
+setting-aqi-colors = AQI Colors
+aqi-colors-epa = EPA
+aqi-colors-colorblind = Colorblind Safe
+setting-alerts = Alerts
+setting-alert-from = Alert From
+setting-stale-after = Grey Out After
+setting-night-mode = Night Mode
+setting-at-night = At Night
+setting-daily-report = Daily Report
+night-dim = Dim
+night-blank = Blank
+on = On
+off = Off
+
+source-local = Local
+source-cloud = Cloud
+source-replay = Replay
+source-simulator = Simulator
+unit-fahrenheit = Fahrenheit
+unit-celsius = Celsius
+scheme-us-epa = US EPA
+scheme-us-epa-corrected = US EPA (corrected)
+scheme-lrapa = LRAPA
+scheme-aqandu = AQ&U
+aqi-display-pm2-5 = PM2.5
+aqi-display-pm10 = PM10
+aqi-display-both = PM2.5 and PM10
+theme-dark = Dark
+theme-light = Light
+theme-high-contrast = High Contrast
+theme-custom = Custom
+night-off = Off
+night-schedule = Schedule
+night-sun = Sunset to Sunrise
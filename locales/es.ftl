@@ -0,0 +1,348 @@
+app-title = PurpleAir GUI
+
+## Estado
+
+status-fetching = Obteniendo los datos del sensor...
+status-failed = No se pudieron obtener los datos del sensor ({ $error }). Reintentando...
+announcement = La calidad del aire ahora es { $category }. AQI PM2,5 { $aqi } (antes { $previous })
+notification-summary = La calidad del aire ahora es { $category }
+notification-body = AQI PM2,5 { $aqi } (antes { $previous })
+webhook-summary-aqi = AQI PM2,5 { $aqi } ({ $category })
+webhook-summary-humidity = { $value } % de humedad
+
+## Categorías y mosaicos del AQI
+
+category-good = Buena
+category-moderate = Moderada
+category-usg = Dañina para grupos sensibles
+    .short = Dañina (sensibles)
+category-unhealthy = Dañina
+category-very-unhealthy = Muy dañina
+category-hazardous = Peligrosa
+
+aqi-channel-a = AQI PM2,5 canal A
+    .spoken = AQI de PM2,5 del canal A
+aqi-channel-b = AQI PM2,5 canal B
+    .spoken = AQI de PM2,5 del canal B
+aqi-pm10-channel-a = AQI PM10 canal A
+    .spoken = AQI de PM10 del canal A
+aqi-pm10-channel-b = AQI PM10 canal B
+    .spoken = AQI de PM10 del canal B
+concentrations-channel-a = Canal A µg/m³
+    .spoken = Concentraciones del canal A
+concentrations-channel-b = Canal B µg/m³
+    .spoken = Concentraciones del canal B
+concentration-atm = ATM
+concentration-cf-1 = CF=1
+pm1-0 = PM1,0
+pm2-5 = PM2,5
+pm10 = PM10
+concentration-spoken = { $size }: { $atm } ATM, { $cf_1 } CF=1, microgramos por metro cúbico
+aqi-tile-spoken = { $channel }, { $aqi }, { $category }
+aqi-tile-description = Categoría de calidad del aire { $level } de { $count }
+category-level = Nivel { $level } de { $count }
+
+## Temperatura y humedad
+
+temperature-value = { $value } { $unit ->
+    [celsius] °C
+   *[fahrenheit] °F
+}
+temperature-spoken = Temperatura, { $value } { $unit ->
+    [celsius] grados Celsius
+   *[fahrenheit] grados Fahrenheit
+}, sin corregir
+    .description = Medida dentro de la carcasa del sensor, que suele marcar varios grados más que el aire
+dew-point-spoken = Punto de rocío, { $value } { $unit ->
+    [celsius] grados Celsius
+   *[fahrenheit] grados Fahrenheit
+}
+    .description = La temperatura a la que el aire quedaría saturado de vapor de agua
+humidity-value = { $value } %
+humidity-spoken = Humedad relativa, { $value } por ciento, sin corregir
+    .description = Medida dentro de la carcasa del sensor, que suele marcar menos humedad que el aire
+icon-thermometer = Termómetro
+    .description = Icono de temperatura
+icon-water-drop = Gota de agua
+    .description = Icono de punto de rocío
+icon-percent = Signo de porcentaje
+    .description = Icono de humedad
+heat-index = Índice de calor
+heat-index-spoken = Índice de calor, { $value } { $unit ->
+    [celsius] grados Celsius
+   *[fahrenheit] grados Fahrenheit
+}
+    .description = La sensación térmica con la humedad, a partir de las lecturas sin corregir
+humidex = Humidex
+humidex-spoken = Humidex, { $value }
+    .description = La medida canadiense de la sensación térmica con la humedad, a partir de las lecturas sin corregir
+absolute-humidity = Humedad absoluta
+absolute-humidity-value = { $value } g/m³
+absolute-humidity-spoken = Humedad absoluta, { $value } gramos de agua por metro cúbico
+    .description = El vapor de agua en el aire, a partir de las lecturas sin corregir
+comfort-zone = Confort
+comfort-zone-spoken = Confort, { $value }
+    .description = Comparado con el rango habitual en interiores de 20 a 26 °C y 30 a 60 por ciento de humedad
+comfort-cold = Demasiado frío
+comfort-dry = Demasiado seco
+comfort-comfortable = Confortable
+comfort-humid = Demasiado húmedo
+comfort-hot = Demasiado caluroso
+
+## Página actual y gráfico
+
+reading-averaged = media de 2 min
+reading-live = EN VIVO
+reading-summary = { $kind } ({ $samples ->
+    [one] { $samples } muestra
+   *[other] { $samples } muestras
+})
+reading-summary-updated = { $kind } ({ $samples ->
+    [one] { $samples } muestra
+   *[other] { $samples } muestras
+}, actualizado a las { $time })
+reading-summary-mean = { $kind } ({ $samples ->
+    [one] { $samples } muestra
+   *[other] { $samples } muestras
+}, actualizado a las { $time }, AQI medio { $mean })
+live-mode = Modo en vivo: { $on ->
+    [yes] Sí
+   *[no] No
+}
+recording = Grabación: { $on ->
+    [yes] Sí
+   *[no] No
+}
+chart-title = AQI PM2,5, { $summary }
+chart-empty = Todavía no hay lecturas.
+chart-max = máx. { $aqi }
+
+## Diagnóstico
+
+diagnostics-title = Diagnóstico
+field = { $label }: { $value }
+diagnostics-sensor = Sensor
+diagnostics-firmware = Versión del firmware
+diagnostics-hardware = Versión del hardware
+diagnostics-devices = Dispositivos
+diagnostics-uptime = Tiempo en marcha
+diagnostics-wifi = Señal Wi-Fi
+diagnostics-memory = Memoria libre
+diagnostics-uploads = Envíos
+diagnostics-source = Fuente
+diagnostics-readings = Lecturas
+diagnostics-last-error = Último error
+diagnostics-webhooks = Entregas de webhooks
+delivery-delivered = { $time } { $kind } a { $url }: entregado (HTTP { $status })
+delivery-failed = { $time } { $kind } a { $url }: falló tras { $attempts } intentos ({ $error })
+payload-alert = Alerta
+payload-summary = Resumen
+uptime = { $days } d { $hours } h { $minutes } min
+memory-value = { $bytes } bytes
+uploads-value = { $succeeded } de { $sent } correctos
+readings-value = { $received } recibidas, { $failed } fallidas
+diagnostics-channels = Canales
+diagnostics-maintenance = Mantenimiento
+drift-trend = Relación B/A { $ratio }, cambia { $change } por semana en las últimas { $weeks } semanas
+drift-no-trend = Aún no hay historial suficiente para comparar los canales ({ $days } de { $needed } días)
+drift-drift = El canal { $channel } mide un { $percent } % menos que el otro canal que antes, puede que su láser se esté desgastando.
+drift-no-counts = El canal { $channel } no cuenta partículas mientras que el otro canal sí.
+drift-saturated = El canal { $channel } está atascado en su recuento máximo mientras que el otro canal no.
+channel-a = A
+channel-b = B
+maintenance-clean = Limpie la entrada de aire con aire comprimido y vuelva a comprobarlo en unos días
+maintenance-replace = Sustituya el contador de partículas
+
+## Actualidad de los datos
+
+duration = { $unit ->
+    [seconds] { $count ->
+        [one] { $count } segundo
+       *[other] { $count } segundos
+    }
+    [minutes] { $count ->
+        [one] { $count } minuto
+       *[other] { $count } minutos
+    }
+   *[hours] { $count ->
+        [one] { $count } hora
+       *[other] { $count } horas
+    }
+}
+updated-ago = Actualizado hace { $duration }
+stale-warning = Sin datos nuevos desde hace { $duration }, los valores mostrados pueden estar desactualizados.
+clock-skew = El reloj del sensor va { $duration } { $direction ->
+    [ahead] adelantado
+   *[behind] atrasado
+} respecto a este equipo.
+clock-offset = { $duration } { $direction ->
+    [ahead] adelantado
+   *[behind] atrasado
+}
+diagnostics-sensor-time = Hora del sensor
+
+## Mapa
+
+map-title = Mapa de sensores
+map-no-locations = Ningún sensor ha informado todavía de su ubicación.
+map-marker = { $sensor }: { $aqi }
+map-marker-spoken = { $sensor }, { $place ->
+    [inside] interior
+   *[other] exterior
+}, AQI de PM2,5 { $aqi }, { $category }
+
+## Interior y exterior
+
+comparison-title = Interior frente a exterior
+comparison-no-pair = Añade un sensor de interior y uno de exterior en los ajustes para compararlos. Cada sensor informa de si está dentro o fuera.
+comparison-waiting = Esperando lecturas de ambos sensores tomadas con pocos minutos de diferencia.
+comparison-indoor = Interior
+comparison-outdoor = Exterior
+comparison-reading = { $sensor }: PM2,5 { $pm2_5 } µg/m³, AQI { $aqi }
+comparison-ratio = Relación interior/exterior
+comparison-ratio-unknown = muy poco PM2,5 exterior para comparar
+comparison-keep-closed = Mantén las ventanas cerradas
+    .description = El aire exterior es peor que el interior.
+comparison-ventilate = Se puede ventilar
+    .description = El aire exterior es bueno, o más limpio que el interior.
+comparison-lag = Los niveles interiores siguen a los picos exteriores tras unos { $duration }.
+comparison-lag-immediate = Los niveles interiores siguen a los picos exteriores en pocos minutos.
+comparison-lag-unknown = Los niveles interiores todavía no siguen a los exteriores lo bastante como para saber con qué rapidez entra el aire de fuera.
+comparison-chart = Relación de PM2,5 interior/exterior, hasta { $max }. Por debajo de 1 el edificio retiene las partículas, por encima de 1 se generan dentro.
+
+## Informes
+
+reports-title = Informes
+report-title = Informe { $period ->
+    [week] semanal
+   *[day] diario
+} de { $sensor }, { $dates }
+report-week = { $first } – { $last }
+report-period-day = Diario
+report-period-week = Semanal
+report-format-html = HTML
+report-format-markdown = Markdown
+report-earlier = Anterior
+report-later = Siguiente
+report-export = Exportar { $format }
+report-exported = Guardado en { $path }
+report-export-failed = No se pudo guardar el informe: { $error }
+report-loading = Cargando el informe...
+report-no-sensor = Todavía no se ha leído ningún sensor.
+report-no-readings = No se registraron lecturas de este sensor en este periodo.
+report-aqi = AQI de PM2,5
+report-aqi-value = mín. { $min }, media { $mean }, máx. { $max }
+report-peak = Pico
+report-peak-value = { $aqi } a las { $time }
+report-peak-value-week = { $aqi } el { $date } a las { $time }
+report-temperature = Temperatura
+report-range = de { $min } a { $max }
+report-readings = Lecturas
+report-readings-value = { $count } que cubren { $hours }
+report-restarts = Reinicios del sensor
+report-uploads = Envíos
+report-uploads-value = { $succeeded } de { $sent } correctos ({ $percent } %)
+report-no-uploads = ninguno enviado
+report-hours = { $hours } h
+report-category-hours = Tiempo por categoría
+report-generated = Generado el { $date } a las { $time }.
+
+## Calendario
+
+calendar-heading = Calendario
+calendar-title = AQI diario de { $sensor }
+calendar-loading = Cargando...
+calendar-day-spoken = { $date }, { $statistic }: AQI { $aqi }, { $category }
+calendar-day-empty = { $date }: sin lecturas
+calendar-day-summary = AQI medio { $mean }, máximo { $max }
+calendar-no-data = Sin lecturas
+calendar-close-day = Cerrar
+daily-mean = Media diaria
+daily-max = Máximo diario
+
+## Eventos
+
+event-smoke = Humo
+event-dust = Polvo
+event-cooking = Cocina
+event-description = { $kind } de { $start } a { $end }, PM2,5 de hasta { $peak } µg/m³
+event-description-ongoing = { $kind } desde las { $start }, PM2,5 de hasta { $peak } µg/m³
+event-ongoing = Evento detectado: { $kind }, PM2,5 de hasta { $peak } µg/m³
+
+## Navegación
+
+nav-current = Actual
+nav-chart = Gráfico
+nav-diagnostics = Diagnóstico
+nav-map = Mapa
+nav-comparison = Interior/exterior
+nav-reports = Informes
+nav-calendar = Calendario
+nav-find-sensors = Buscar sensores
+nav-settings = Ajustes
+nav-back = Volver
+keyboard-shortcut = Atajo de teclado: { $keys }
+
+## Búsqueda
+
+discovery-title = Sensores en la red local
+discovery-scanning = Buscando...
+discovery-none = No se encontraron sensores.
+discovery-selected = { $sensor } (seleccionado)
+discovery-sensor = { $id } - { $geo } ({ $place }) en { $url }
+discovery-scan-again = Buscar de nuevo
+
+## Ajustes
+
+settings-title = Ajustes
+settings-saved-to = Se guarda en { $path }
+settings-no-directory = No se encontró un directorio de configuración, los cambios se mantienen hasta cerrar la aplicación.
+settings-read-error = No se pudo leer el archivo de configuración, los cambios no se guardan hasta corregirlo: { $error }
+settings-sensors = Sensores
+settings-active-sensor = { $sensor } (activo)
+settings-remove-sensor = Quitar
+settings-add-sensor = Añadir desde Buscar sensores
+setting-language = Idioma
+setting-source = Fuente de datos
+setting-poll-interval = Intervalo de consulta
+poll-interval-value = { $seconds } s
+setting-temperature = Temperatura
+setting-comfort = Métricas de confort
+setting-aqi-scheme = Esquema de AQI
+setting-aqi-display = AQI de
+setting-concentrations = Concentraciones
+setting-theme = Tema
+setting-aqi-colors = Colores del AQI
+aqi-colors-epa = EPA
+aqi-colors-colorblind = Aptos para daltónicos
+setting-alerts = Alertas
+setting-alert-from = Alertar desde
+setting-stale-after = Atenuar tras
+setting-night-mode = Modo nocturno
+setting-at-night = De noche
+setting-daily-report = Informe diario
+night-dim = Atenuar
+night-blank = Apagar
+on = Sí
+off = No
+
+source-local = Local
+source-cloud = Nube
+source-replay = Reproducción
+source-simulator = Simulador
+unit-fahrenheit = Fahrenheit
+unit-celsius = Celsius
+scheme-us-epa = EPA de EE. UU.
+scheme-us-epa-corrected = EPA de EE. UU. (corregido)
+scheme-lrapa = LRAPA
+scheme-aqandu = AQ&U
+aqi-display-pm2-5 = PM2,5
+aqi-display-pm10 = PM10
+aqi-display-both = PM2,5 y PM10
+theme-dark = Oscuro
+theme-light = Claro
+theme-high-contrast = Alto contraste
+theme-custom = Personalizado
+night-off = Desactivado
+night-schedule = Horario
+night-sun = Del atardecer al amanecer
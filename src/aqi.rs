@@ -0,0 +1,258 @@
+use crate::sensor_data::LocalSensorData;
+use serde::{Deserialize, Serialize};
+
+/// The US EPA AQI categories.
+/// https://www.airnow.gov/aqi/aqi-basics/
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AqiCategory {
+    Good,
+    Moderate,
+    UnhealthyForSensitiveGroups,
+    Unhealthy,
+    VeryUnhealthy,
+    Hazardous,
+}
+
+impl AqiCategory {
+    pub const ALL: [AqiCategory; 6] = [
+        AqiCategory::Good,
+        AqiCategory::Moderate,
+        AqiCategory::UnhealthyForSensitiveGroups,
+        AqiCategory::Unhealthy,
+        AqiCategory::VeryUnhealthy,
+        AqiCategory::Hazardous,
+    ];
+
+    pub fn from_aqi(aqi: f64) -> AqiCategory {
+        // The AQI is reported as an integer, so round before comparing against the breakpoints.
+        match aqi.round() as u64 {
+            0..=50 => AqiCategory::Good,
+            51..=100 => AqiCategory::Moderate,
+            101..=150 => AqiCategory::UnhealthyForSensitiveGroups,
+            151..=200 => AqiCategory::Unhealthy,
+            201..=300 => AqiCategory::VeryUnhealthy,
+            _ => AqiCategory::Hazardous,
+        }
+    }
+
+    /// 1 for Good up to 6 for Hazardous.
+    pub fn level(&self) -> usize {
+        *self as usize + 1
+    }
+
+    /// The official EPA color as red, green and blue.
+    pub fn rgb8(&self) -> [u8; 3] {
+        match self {
+            AqiCategory::Good => [0, 228, 0],
+            AqiCategory::Moderate => [255, 255, 0],
+            AqiCategory::UnhealthyForSensitiveGroups => [255, 126, 0],
+            AqiCategory::Unhealthy => [255, 0, 0],
+            AqiCategory::VeryUnhealthy => [143, 63, 151],
+            AqiCategory::Hazardous => [126, 0, 35],
+        }
+    }
+
+    /// The official EPA color, in the same `rgb(r,g,b)` format the sensor uses for `p25aqic`.
+    pub fn rgb(&self) -> &'static str {
+        match self {
+            AqiCategory::Good => "rgb(0,228,0)",
+            AqiCategory::Moderate => "rgb(255,255,0)",
+            AqiCategory::UnhealthyForSensitiveGroups => "rgb(255,126,0)",
+            AqiCategory::Unhealthy => "rgb(255,0,0)",
+            AqiCategory::VeryUnhealthy => "rgb(143,63,151)",
+            AqiCategory::Hazardous => "rgb(126,0,35)",
+        }
+    }
+}
+
+/// The US EPA PM2.5 breakpoints as revised in 2024: (concentration low, concentration high, AQI low, AQI high).
+const PM2_5_BREAKPOINTS: [(f64, f64, f64, f64); 6] = [
+    (0.0, 9.0, 0.0, 50.0),
+    (9.1, 35.4, 51.0, 100.0),
+    (35.5, 55.4, 101.0, 150.0),
+    (55.5, 125.4, 151.0, 200.0),
+    (125.5, 225.4, 201.0, 300.0),
+    (225.5, 325.4, 301.0, 500.0),
+];
+
+/// Converts a PM2.5 concentration in µg/m³ to the US EPA AQI.
+pub fn pm2_5_aqi(concentration: f64) -> f64 {
+    // The EPA truncates PM2.5 concentrations to one decimal place before looking up the breakpoint.
+    let concentration = (concentration.max(0.0) * 10.0).floor() / 10.0;
+
+    let (c_low, c_high, i_low, i_high) = PM2_5_BREAKPOINTS
+        .iter()
+        .copied()
+        .find(|(_, c_high, _, _)| concentration <= *c_high)
+        .unwrap_or(PM2_5_BREAKPOINTS[PM2_5_BREAKPOINTS.len() - 1]);
+
+    ((i_high - i_low) / (c_high - c_low) * (concentration - c_low) + i_low).round()
+}
+
+/// The US EPA PM10 breakpoints as revised in 2024, in the same layout as `PM2_5_BREAKPOINTS`.
+const PM10_BREAKPOINTS: [(f64, f64, f64, f64); 6] = [
+    (0.0, 54.0, 0.0, 50.0),
+    (55.0, 154.0, 51.0, 100.0),
+    (155.0, 254.0, 101.0, 150.0),
+    (255.0, 354.0, 151.0, 200.0),
+    (355.0, 424.0, 201.0, 300.0),
+    (425.0, 604.0, 301.0, 500.0),
+];
+
+/// Converts a PM10 concentration in µg/m³ to the US EPA AQI.
+pub fn pm10_aqi(concentration: f64) -> f64 {
+    // The EPA truncates PM10 concentrations to whole numbers before looking up the breakpoint.
+    let concentration = concentration.max(0.0).floor();
+
+    let (c_low, c_high, i_low, i_high) = PM10_BREAKPOINTS
+        .iter()
+        .copied()
+        .find(|(_, c_high, _, _)| concentration <= *c_high)
+        .unwrap_or(PM10_BREAKPOINTS[PM10_BREAKPOINTS.len() - 1]);
+
+    ((i_high - i_low) / (c_high - c_low) * (concentration - c_low) + i_low).round()
+}
+
+/// Which pollutants the AQI tiles are shown for.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AqiDisplay {
+    #[default]
+    Pm2_5,
+    Pm10,
+    /// PM2.5 and PM10 side by side.
+    Both,
+}
+
+impl AqiDisplay {
+    pub const ALL: [AqiDisplay; 3] = [AqiDisplay::Pm2_5, AqiDisplay::Pm10, AqiDisplay::Both];
+
+    pub fn shows_pm2_5(&self) -> bool {
+        *self != AqiDisplay::Pm10
+    }
+
+    pub fn shows_pm10(&self) -> bool {
+        *self != AqiDisplay::Pm2_5
+    }
+}
+
+/// How the PM2.5 AQI is computed from the raw sensor values.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AqiScheme {
+    /// The AQI the sensor reports itself, from the uncorrected `pm2_5_atm` concentration.
+    #[default]
+    UsEpa,
+
+    /// The EPA's nationwide correction for PurpleAir sensors, which accounts for humidity.
+    /// https://www.epa.gov/air-sensor-toolbox/technical-approaches-sensor-data-airnow-fire-and-smoke-map
+    UsEpaCorrected,
+
+    /// The Lane Regional Air Protection Agency correction, tuned for wood smoke.
+    Lrapa,
+
+    /// The University of Utah AQ&U correction.
+    AqAndU,
+}
+
+impl AqiScheme {
+    pub const ALL: [AqiScheme; 4] = [AqiScheme::UsEpa, AqiScheme::UsEpaCorrected, AqiScheme::Lrapa, AqiScheme::AqAndU];
+
+    /// The corrected PM2.5 concentration for a channel's `pm2_5_cf_1` value.
+    fn correct(&self, cf_1: f64, humidity: Option<f64>) -> Option<f64> {
+        let corrected = match self {
+            AqiScheme::UsEpa => return None,
+            AqiScheme::UsEpaCorrected => 0.524 * cf_1 - 0.0862 * humidity? + 5.75,
+            AqiScheme::Lrapa => 0.5 * cf_1 - 0.66,
+            AqiScheme::AqAndU => 0.778 * cf_1 + 2.65,
+        };
+        Some(corrected.max(0.0))
+    }
+
+    /// The PM2.5 AQI of a channel's `pm2_5_cf_1` value in this scheme, `None` where the sensor's own AQI stands,
+    /// i.e. for the US EPA scheme or without the scheme's inputs.
+    pub fn corrected_aqi(&self, cf_1: Option<f64>, humidity: Option<u64>) -> Option<f64> {
+        self.correct(cf_1?, humidity.map(|humidity| humidity as f64)).map(pm2_5_aqi)
+    }
+
+    /// Replaces the AQI values and colors of both channels with the ones computed by this scheme.
+    /// The sensor's own values are kept where the scheme lacks an input, e.g. the humidity.
+    pub fn apply(&self, sensor_data: &mut LocalSensorData) {
+        if let Some(aqi) = self.corrected_aqi(sensor_data.pm2_5_cf_1, sensor_data.current_humidity) {
+            sensor_data.pm2_5_aqi = Some(aqi);
+            sensor_data.p25aqic = Some(AqiCategory::from_aqi(aqi).rgb().to_string());
+        }
+
+        if let Some(aqi) = self.corrected_aqi(sensor_data.pm2_5_cf_1_b, sensor_data.current_humidity) {
+            sensor_data.pm2_5_aqi_b = Some(aqi);
+            sensor_data.p25aqic_b = Some(AqiCategory::from_aqi(aqi).rgb().to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pm10_breakpoint_edges() {
+        let edges = [
+            (0.0, 0.0),
+            (54.0, 50.0),
+            (55.0, 51.0),
+            (154.0, 100.0),
+            (155.0, 101.0),
+            (254.0, 150.0),
+            (255.0, 151.0),
+            (354.0, 200.0),
+            (355.0, 201.0),
+            (424.0, 300.0),
+            (425.0, 301.0),
+            (604.0, 500.0),
+        ];
+        for (concentration, aqi) in edges {
+            assert_eq!(pm10_aqi(concentration), aqi, "{} µg/m³", concentration);
+        }
+    }
+
+    #[test]
+    fn pm10_is_truncated_before_the_lookup() {
+        assert_eq!(pm10_aqi(54.9), 50.0);
+        assert_eq!(pm10_aqi(154.99), 100.0);
+        assert_eq!(pm10_aqi(-3.0), 0.0);
+    }
+
+    #[test]
+    fn pm10_above_the_table_follows_the_last_segment() {
+        assert_eq!(pm10_aqi(605.0), 501.0);
+        assert_eq!(pm10_aqi(700.0), 607.0);
+    }
+
+    #[test]
+    fn the_sensors_own_aqi_stands_for_us_epa() {
+        assert_eq!(AqiScheme::UsEpa.corrected_aqi(Some(20.0), Some(50)), None);
+    }
+
+    #[test]
+    fn corrections_need_their_inputs() {
+        for scheme in [AqiScheme::UsEpaCorrected, AqiScheme::Lrapa, AqiScheme::AqAndU] {
+            assert_eq!(scheme.corrected_aqi(None, Some(50)), None);
+        }
+        assert_eq!(AqiScheme::UsEpaCorrected.corrected_aqi(Some(20.0), None), None);
+        assert!(AqiScheme::Lrapa.corrected_aqi(Some(20.0), None).is_some());
+    }
+
+    #[test]
+    fn corrected_aqi_per_scheme() {
+        // 0.524 * 20 - 0.0862 * 50 + 5.75 = 11.92 µg/m³
+        assert_eq!(AqiScheme::UsEpaCorrected.corrected_aqi(Some(20.0), Some(50)), Some(56.0));
+        // 0.5 * 20 - 0.66 = 9.34 µg/m³
+        assert_eq!(AqiScheme::Lrapa.corrected_aqi(Some(20.0), Some(50)), Some(51.0));
+        // 0.778 * 20 + 2.65 = 18.21 µg/m³
+        assert_eq!(AqiScheme::AqAndU.corrected_aqi(Some(20.0), Some(50)), Some(68.0));
+    }
+
+    #[test]
+    fn corrections_never_go_below_zero() {
+        assert_eq!(AqiScheme::Lrapa.corrected_aqi(Some(1.0), None), Some(0.0));
+        assert_eq!(AqiScheme::UsEpaCorrected.corrected_aqi(Some(0.0), Some(100)), Some(0.0));
+    }
+}
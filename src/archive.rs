@@ -0,0 +1,190 @@
+use crate::aqi::AqiScheme;
+use crate::sensor_data::LocalSensorData;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Holds the sensor's id in its directory, because the directory name cannot be turned back into it.
+const SENSOR_ID_FILE: &str = "sensor-id";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ArchiveSettings {
+    /// Keep every reading of the configured sensors on disk, for the reports.
+    pub enabled: bool,
+
+    /// The readings are kept here as `{sensor id}/{yyyy-mm-dd}.jsonl`, one file per sensor and local day.
+    /// Defaults to the platform's data directory.
+    pub directory: Option<PathBuf>,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        ArchiveSettings {
+            enabled: true,
+            directory: None,
+        }
+    }
+}
+
+impl ArchiveSettings {
+    pub fn directory(&self) -> Option<PathBuf> {
+        self.directory.clone().or_else(|| dirs::data_dir().map(|data_dir| data_dir.join("purple-air-gui").join("history")))
+    }
+}
+
+/// The part of a reading the reports need. Much smaller than the sensor's full response, so a year of readings
+/// every 10 seconds stays in the hundreds of megabytes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchivedReading {
+    pub received_at: DateTime<Utc>,
+    /// Channel A PM2.5 AQI as the sensor reports it, see `aqi_in` for the other schemes.
+    pub aqi: Option<f64>,
+    /// Channel A PM2.5 in µg/m³.
+    pub pm2_5: Option<f64>,
+    /// Channel A PM2.5 CF=1 in µg/m³, which the corrected AQI schemes start from.
+    pub pm2_5_cf_1: Option<f64>,
+    /// Channel B PM2.5 in µg/m³, and the 0.3 µm counts of both channels, for telling when a channel wears out.
+    pub pm2_5_b: Option<f64>,
+    pub p_0_3_um: Option<f64>,
+    pub p_0_3_um_b: Option<f64>,
+    pub temperature_f: Option<u64>,
+    pub humidity: Option<u64>,
+    /// See `LocalSensorData::uptime`.
+    pub uptime: u64,
+    pub http_success: u64,
+    pub http_sends: u64,
+}
+
+impl ArchivedReading {
+    /// Keeps the sensor's own values, so `sensor_data` must not have an AQI scheme applied to it.
+    pub fn new(sensor_data: &LocalSensorData, received_at: DateTime<Utc>) -> Self {
+        ArchivedReading {
+            received_at,
+            aqi: sensor_data.pm2_5_aqi,
+            pm2_5: sensor_data.pm2_5_atm,
+            pm2_5_cf_1: sensor_data.pm2_5_cf_1,
+            pm2_5_b: sensor_data.pm2_5_atm_b,
+            p_0_3_um: sensor_data.p_0_3_um,
+            p_0_3_um_b: sensor_data.p_0_3_um_b,
+            temperature_f: sensor_data.current_temp_f,
+            humidity: sensor_data.current_humidity,
+            uptime: sensor_data.uptime,
+            http_success: sensor_data.http_success,
+            http_sends: sensor_data.http_sends,
+        }
+    }
+
+    /// The channel A PM2.5 AQI in `scheme`. Readings archived without their CF=1 concentration keep their AQI.
+    pub fn aqi_in(&self, scheme: AqiScheme) -> Option<f64> {
+        scheme.corrected_aqi(self.pm2_5_cf_1, self.humidity).or(self.aqi)
+    }
+}
+
+/// The directory of a sensor's files. Sensor ids are MAC addresses, whose colons are not allowed in Windows paths.
+pub(crate) fn sensor_directory(directory: &Path, sensor_id: &str) -> PathBuf {
+    directory.join(sensor_id.replace(':', "-"))
+}
+
+fn day_file(directory: &Path, sensor_id: &str, date: NaiveDate) -> PathBuf {
+    sensor_directory(directory, sensor_id).join(format!("{}.jsonl", date.format("%Y-%m-%d")))
+}
+
+/// Appends a reading of `sensor_id` to the file of the local day it was received on.
+pub async fn append(directory: PathBuf, sensor_id: String, reading: ArchivedReading) -> std::io::Result<()> {
+    let path = day_file(&directory, sensor_id.as_str(), reading.received_at.with_timezone(&Local).date_naive());
+    let sensor_directory = sensor_directory(&directory, sensor_id.as_str());
+    tokio::fs::create_dir_all(&sensor_directory).await?;
+    let id_file = sensor_directory.join(SENSOR_ID_FILE);
+    if !tokio::fs::try_exists(&id_file).await? {
+        tokio::fs::write(&id_file, sensor_id.as_str()).await?;
+    }
+
+    let mut line = serde_json::to_string(&reading)?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(&path).await?;
+    file.write_all(line.as_bytes()).await
+}
+
+/// The readings of `sensor_id` received on the local days from `first` to `last`, oldest first.
+/// Days without a file are skipped, and so are lines that do not parse, e.g. one cut short by a crash.
+pub async fn load(directory: PathBuf, sensor_id: String, first: NaiveDate, last: NaiveDate) -> Vec<ArchivedReading> {
+    let mut readings = Vec::new();
+    for date in first.iter_days().take_while(|date| *date <= last) {
+        let Ok(contents) = tokio::fs::read_to_string(day_file(&directory, sensor_id.as_str(), date)).await else {
+            continue;
+        };
+        readings.extend(contents.lines().filter_map(|line| serde_json::from_str::<ArchivedReading>(line).ok()));
+    }
+    readings.sort_by_key(|reading| reading.received_at);
+    readings
+}
+
+/// The ids of the sensors that have readings in the archive.
+pub async fn sensors(directory: PathBuf) -> Vec<String> {
+    let mut sensors = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(&directory).await else {
+        return sensors;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if !entry.file_type().await.is_ok_and(|file_type| file_type.is_dir()) {
+            continue;
+        }
+        // Directories archived before the id was stored in them all belong to MAC addresses.
+        let sensor_id = match tokio::fs::read_to_string(entry.path().join(SENSOR_ID_FILE)).await {
+            Ok(sensor_id) => sensor_id,
+            Err(_) => entry.file_name().to_string_lossy().replace('-', ":"),
+        };
+        sensors.push(sensor_id);
+    }
+    sensors.sort();
+    sensors
+}
+
+/// The local days `sensor_id` has readings for, oldest first.
+pub async fn days(directory: PathBuf, sensor_id: String) -> Vec<NaiveDate> {
+    let mut days = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(sensor_directory(&directory, sensor_id.as_str())).await else {
+        return days;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(date) = name.strip_suffix(".jsonl").and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()) {
+            days.push(date);
+        }
+    }
+    days.sort();
+    days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn reading(received_at: DateTime<Utc>) -> ArchivedReading {
+        let sensor_data: LocalSensorData = serde_json::from_str(include_str!("../fixtures/pa-ii.json")).unwrap();
+        ArchivedReading::new(&sensor_data, received_at)
+    }
+
+    #[tokio::test]
+    async fn sensor_ids_survive_the_directory_names() {
+        let directory = std::env::temp_dir().join(format!("purple-air-archive-{}", std::process::id()));
+        let time = Utc.with_ymd_and_hms(2025, 6, 29, 12, 0, 0).unwrap();
+        for sensor_id in ["84:f3:eb:7b:c8:ee", "simulator-42", "cloud-1234"] {
+            append(directory.clone(), sensor_id.to_string(), reading(time)).await.unwrap();
+            append(directory.clone(), sensor_id.to_string(), reading(time)).await.unwrap();
+        }
+        // Archived before the ids were stored.
+        std::fs::create_dir_all(directory.join("aa-bb-cc-dd-ee-ff")).unwrap();
+
+        let sensors = sensors(directory.clone()).await;
+        let date = time.with_timezone(&Local).date_naive();
+        let readings = load(directory.clone(), "simulator-42".to_string(), date, date).await;
+        std::fs::remove_dir_all(directory).unwrap();
+
+        assert_eq!(sensors, ["84:f3:eb:7b:c8:ee", "aa:bb:cc:dd:ee:ff", "cloud-1234", "simulator-42"]);
+        assert_eq!(readings.len(), 2);
+    }
+}
@@ -0,0 +1,207 @@
+//! A stand-in for a PurpleAir sensor on the local network, for trying out the fetch and retry paths.
+//!
+//! Serves `/json` (and `/json?live=true`) from fixture files or the simulator and can inject
+//! latency, HTTP 500s, truncated bodies and malformed JSON:
+//!
+//! ```sh
+//! cargo run --bin mock_sensor -- --port 8080 --simulate --seed 7 --latency-ms 500 --error-rate 0.1
+//! cargo run --bin mock_sensor -- --fixture fixtures/pa-ii.json --malformed-rate 0.2
+//! ```
+//!
+//! Then point `device_url` at `http://127.0.0.1:8080/json`.
+
+use purple_air_gui::data_source::{SimulatorSettings, SimulatorSource};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const USAGE: &str = "usage: mock_sensor [--port PORT] [--fixture FILE]... [--simulate] [--seed SEED]
+                   [--latency-ms MS] [--error-rate P] [--truncate-rate P] [--malformed-rate P]";
+
+struct Options {
+    port: u16,
+    fixtures: Vec<PathBuf>,
+    simulate: bool,
+    seed: u64,
+    latency: Duration,
+    error_rate: f64,
+    truncate_rate: f64,
+    malformed_rate: f64,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            port: 8080,
+            fixtures: Vec::new(),
+            simulate: false,
+            seed: 0,
+            latency: Duration::ZERO,
+            error_rate: 0.0,
+            truncate_rate: 0.0,
+            malformed_rate: 0.0,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            let invalid = |error: &dyn std::fmt::Display| format!("invalid value for {}: {}", arg, error);
+
+            match arg.as_str() {
+                "--port" => options.port = value()?.parse().map_err(|error| invalid(&error))?,
+                "--fixture" => options.fixtures.push(PathBuf::from(value()?)),
+                "--simulate" => options.simulate = true,
+                "--seed" => options.seed = value()?.parse().map_err(|error| invalid(&error))?,
+                "--latency-ms" => options.latency = Duration::from_millis(value()?.parse().map_err(|error| invalid(&error))?),
+                "--error-rate" => options.error_rate = value()?.parse().map_err(|error| invalid(&error))?,
+                "--truncate-rate" => options.truncate_rate = value()?.parse().map_err(|error| invalid(&error))?,
+                "--malformed-rate" => options.malformed_rate = value()?.parse().map_err(|error| invalid(&error))?,
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+
+        if options.fixtures.is_empty() && !options.simulate {
+            options.fixtures.push(PathBuf::from("fixtures/pa-ii.json"));
+        }
+
+        Ok(options)
+    }
+}
+
+/// Produces the response bodies and decides which faults to inject.
+struct MockSensor {
+    fixtures: Vec<String>,
+    next_fixture: usize,
+    simulator: Option<SimulatorSource>,
+    rng: StdRng,
+}
+
+impl MockSensor {
+    /// The next body to serve, or `None` if the simulator dropped off the network.
+    fn next_body(&mut self) -> Option<String> {
+        if let Some(simulator) = &mut self.simulator {
+            let reading = simulator.generate().ok()?;
+            return serde_json::to_string(&reading.sensor_data).ok();
+        }
+
+        let body = self.fixtures[self.next_fixture].clone();
+        self.next_fixture = (self.next_fixture + 1) % self.fixtures.len();
+        Some(body)
+    }
+
+    fn roll(&mut self, chance: f64) -> bool {
+        self.rng.random_bool(chance.clamp(0.0, 1.0))
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str, content_length: usize) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, content_length, body
+    )
+}
+
+fn handle(mut stream: TcpStream, options: &Options, sensor: &Mutex<MockSensor>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // Skip the headers, the mock only cares about the path.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    println!("{}", request_line.trim_end());
+
+    if path != "/json" && !path.starts_with("/json?") {
+        return respond(&mut stream, "404 Not Found", "", 0);
+    }
+
+    std::thread::sleep(options.latency);
+
+    let mut sensor = sensor.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if sensor.roll(options.error_rate) {
+        return respond(&mut stream, "500 Internal Server Error", "", 0);
+    }
+
+    let Some(mut body) = sensor.next_body() else {
+        // Like a sensor that is off the Wi-Fi: the connection closes without an answer.
+        return Ok(());
+    };
+
+    if sensor.roll(options.malformed_rate) {
+        body = body.replacen(':', ";", 1);
+    }
+
+    if sensor.roll(options.truncate_rate) {
+        // Promise the full body but close the connection halfway through it.
+        let full_length = body.len();
+        let half = body.char_indices().map(|(index, _)| index).find(|index| *index >= full_length / 2).unwrap_or(full_length);
+        return respond(&mut stream, "200 OK", &body[..half], full_length);
+    }
+
+    respond(&mut stream, "200 OK", body.as_str(), body.len())
+}
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut fixtures = Vec::new();
+    for path in &options.fixtures {
+        match std::fs::read_to_string(path) {
+            Ok(fixture) => fixtures.push(fixture),
+            Err(error) => {
+                eprintln!("Failed to read the fixture {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let simulator = options.simulate.then(|| {
+        SimulatorSource::new(SimulatorSettings {
+            seed: options.seed,
+            ..SimulatorSettings::default()
+        })
+    });
+
+    let sensor = Arc::new(Mutex::new(MockSensor {
+        fixtures,
+        next_fixture: 0,
+        simulator,
+        rng: StdRng::seed_from_u64(options.seed),
+    }));
+
+    let listener = match TcpListener::bind(("0.0.0.0", options.port)) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Failed to listen on port {}: {}", options.port, error);
+            std::process::exit(1);
+        }
+    };
+    // With `--port 0` the system picks a free port, print the one it picked.
+    let port = listener.local_addr().map(|address| address.port()).unwrap_or(options.port);
+    println!("Serving a mock sensor on http://127.0.0.1:{}/json", port);
+
+    let options = Arc::new(options);
+    for stream in listener.incoming().flatten() {
+        let options = options.clone();
+        let sensor = sensor.clone();
+        std::thread::spawn(move || {
+            if let Err(error) = handle(stream, &options, &sensor) {
+                eprintln!("{}", error);
+            }
+        });
+    }
+}
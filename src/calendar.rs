@@ -0,0 +1,154 @@
+use crate::aqi::AqiScheme;
+use crate::archive::{self, ArchivedReading};
+use crate::report::{Report, ReportPeriod};
+use chrono::{Local, NaiveDate, TimeDelta, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// The calendar shows this many weeks, a year ending with the current one.
+pub const CALENDAR_WEEKS: usize = 53;
+/// The day chart splits the day into bins this many minutes long.
+pub const DAY_BIN_MINUTES: u32 = 15;
+/// The daily figures of past days are cached in this file in the sensor's archive directory, so the calendar does not
+/// read a year of readings every time it is shown. They are cached per AQI scheme.
+const DAILY_CACHE_FILE: &str = "daily.json";
+
+/// Which daily figure the calendar colors the days by.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DailyStatistic {
+    #[default]
+    Mean,
+    Max,
+}
+
+impl DailyStatistic {
+    pub const ALL: [DailyStatistic; 2] = [DailyStatistic::Mean, DailyStatistic::Max];
+}
+
+/// The PM2.5 AQI of a day, time-weighted the same way as in the reports.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct DailyAqi {
+    pub mean: f64,
+    pub max: f64,
+}
+
+impl DailyAqi {
+    pub fn get(&self, statistic: DailyStatistic) -> f64 {
+        match statistic {
+            DailyStatistic::Mean => self.mean,
+            DailyStatistic::Max => self.max,
+        }
+    }
+}
+
+/// The daily AQI of one sensor over the weeks the calendar shows.
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    pub sensor_id: String,
+    pub days: BTreeMap<NaiveDate, DailyAqi>,
+}
+
+impl Calendar {
+    /// The weeks shown, oldest first, each from Monday to Sunday. Days after `today` are left out.
+    pub fn weeks(today: NaiveDate) -> Vec<Vec<NaiveDate>> {
+        let (first, _) = ReportPeriod::Week.span(today - TimeDelta::weeks(CALENDAR_WEEKS as i64 - 1));
+        (0..CALENDAR_WEEKS)
+            .map(|week| {
+                let monday = first + TimeDelta::weeks(week as i64);
+                monday.iter_days().take(7).filter(|date| *date <= today).collect()
+            })
+            .collect()
+    }
+}
+
+/// Works out the daily AQI of `sensor_id` in `scheme` for the calendar ending with the week of `today`.
+/// Past days come from the cache when they can, today is always worked out again since it is not over yet.
+pub async fn load(directory: PathBuf, sensor_id: String, today: NaiveDate, scheme: AqiScheme) -> Calendar {
+    let cache = archive::sensor_directory(&directory, sensor_id.as_str()).join(DAILY_CACHE_FILE);
+    let mut schemes: HashMap<AqiScheme, BTreeMap<NaiveDate, DailyAqi>> = match tokio::fs::read_to_string(&cache).await {
+        Ok(contents) => serde_json::from_str(contents.as_str()).unwrap_or_default(),
+        Err(_) => HashMap::new(),
+    };
+    let cached = schemes.entry(scheme).or_default();
+
+    let first = Calendar::weeks(today).first().and_then(|week| week.first().copied()).unwrap_or(today);
+    let mut days = BTreeMap::new();
+    let mut cache_changed = false;
+    for date in archive::days(directory.clone(), sensor_id.clone()).await.into_iter().filter(|date| *date >= first && *date <= today) {
+        if date < today && let Some(daily) = cached.get(&date) {
+            days.insert(date, *daily);
+            continue;
+        }
+
+        let readings = archive::load(directory.clone(), sensor_id.clone(), date, date).await;
+        let Some(aqi) = Report::new(sensor_id.clone(), String::new(), ReportPeriod::Day, date, &readings, scheme).aqi else {
+            continue;
+        };
+        let daily = DailyAqi { mean: aqi.mean, max: aqi.max };
+        days.insert(date, daily);
+        if date < today {
+            cached.insert(date, daily);
+            cache_changed = true;
+        }
+    }
+
+    if cache_changed && let Ok(contents) = serde_json::to_string(&schemes) && let Err(error) = tokio::fs::write(&cache, contents).await {
+        eprintln!("Failed to cache the daily AQI in {}: {}", cache.display(), error);
+    }
+
+    Calendar { sensor_id, days }
+}
+
+/// The mean PM2.5 AQI in `scheme` of each `DAY_BIN_MINUTES` of a day's readings by local time, `None` for bins without
+/// readings.
+pub fn day_profile(readings: &[ArchivedReading], scheme: AqiScheme) -> Vec<Option<f64>> {
+    let count = (24 * 60 / DAY_BIN_MINUTES) as usize;
+    let mut sums = vec![(0.0, 0); count];
+    for reading in readings {
+        let Some(aqi) = reading.aqi_in(scheme) else {
+            continue;
+        };
+        let time = reading.received_at.with_timezone(&Local);
+        let index = ((time.hour() * 60 + time.minute()) / DAY_BIN_MINUTES) as usize;
+        sums[index.min(count - 1)].0 += aqi;
+        sums[index.min(count - 1)].1 += 1;
+    }
+    sums.into_iter().map(|(sum, count)| (count > 0).then(|| sum / count as f64)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensor_data::LocalSensorData;
+
+    #[tokio::test]
+    async fn the_scheme_is_applied_when_the_calendar_is_made() {
+        let directory = std::env::temp_dir().join(format!("purple-air-calendar-{}", std::process::id()));
+        let today = Local::now().date_naive();
+        let date = today - TimeDelta::days(10);
+        let noon = date.and_hms_opt(12, 0, 0).unwrap().and_local_timezone(Local).earliest().unwrap().to_utc();
+
+        let mut sensor_data: LocalSensorData = serde_json::from_str(include_str!("../fixtures/pa-ii.json")).unwrap();
+        sensor_data.pm2_5_aqi = Some(70.0);
+        sensor_data.pm2_5_cf_1 = Some(20.0);
+        sensor_data.current_humidity = Some(50);
+        let sensor_id = sensor_data.sensor_id.clone();
+        for minutes in 0..2 {
+            let reading = ArchivedReading::new(&sensor_data, noon + TimeDelta::minutes(minutes));
+            archive::append(directory.clone(), sensor_id.clone(), reading).await.unwrap();
+        }
+
+        let mean = |calendar: Calendar| calendar.days.get(&date).map(|daily| daily.mean);
+        let lrapa = crate::aqi::pm2_5_aqi(0.5 * 20.0 - 0.66);
+        assert_eq!(mean(load(directory.clone(), sensor_id.clone(), today, AqiScheme::UsEpa).await), Some(70.0));
+        assert_eq!(mean(load(directory.clone(), sensor_id.clone(), today, AqiScheme::Lrapa).await), Some(lrapa));
+        // Both come from the cache now, neither overwrote the other.
+        assert_eq!(mean(load(directory.clone(), sensor_id.clone(), today, AqiScheme::UsEpa).await), Some(70.0));
+        let cache = archive::sensor_directory(&directory, sensor_id.as_str()).join(DAILY_CACHE_FILE);
+        let cached: HashMap<AqiScheme, BTreeMap<NaiveDate, DailyAqi>> = serde_json::from_str(&std::fs::read_to_string(cache).unwrap()).unwrap();
+        assert_eq!(cached.len(), 2);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,291 @@
+use crate::aqi::{self, AqiCategory};
+use crate::comfort::dewpoint_f;
+use crate::sensor_data::{LocalSensorData, Status};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Only these fields are requested. The API charges points per field, so keep this list to what `LocalSensorData` can hold.
+const FIELDS: &str = "name,location_type,latitude,longitude,last_seen,firmware_version,hardware,uptime,rssi,memory,\
+humidity,temperature,pressure,\
+pm1.0_atm_a,pm2.5_atm_a,pm10.0_atm_a,pm1.0_cf_1_a,pm2.5_cf_1_a,pm10.0_cf_1_a,\
+0.3_um_count_a,0.5_um_count_a,1.0_um_count_a,2.5_um_count_a,5.0_um_count_a,10.0_um_count_a,\
+pm1.0_atm_b,pm2.5_atm_b,pm10.0_atm_b,pm1.0_cf_1_b,pm2.5_cf_1_b,pm10.0_cf_1_b,\
+0.3_um_count_b,0.5_um_count_b,1.0_um_count_b,2.5_um_count_b,5.0_um_count_b,10.0_um_count_b";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CloudSettings {
+    /// A PurpleAir API read key, see https://develop.purpleair.com/keys
+    pub api_key: String,
+
+    /// The index shown in the sensor's map.purpleair.com URL.
+    pub sensor_index: u64,
+
+    pub api_url: String,
+
+    /// Cached data is reused until it is this old. The cloud only receives new data from the sensor every 2 minutes
+    /// and every request costs API points.
+    pub refresh_minutes: u64,
+}
+
+impl Default for CloudSettings {
+    fn default() -> Self {
+        CloudSettings {
+            api_key: String::new(),
+            sensor_index: 0,
+            api_url: "https://api.purpleair.com/v1".to_string(),
+            refresh_minutes: 10,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CloudError {
+    MissingApiKey,
+    Request(reqwest::Error),
+    Status(u16, String),
+    Parse(serde_json::Error),
+}
+
+impl fmt::Display for CloudError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloudError::MissingApiKey => write!(f, "no PurpleAir API read key is configured"),
+            CloudError::Request(error) => write!(f, "request failed: {}", error),
+            CloudError::Status(status, body) => write!(f, "HTTP {}: {}", status, body),
+            CloudError::Parse(error) => write!(f, "invalid response: {}", error),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SensorResponse {
+    sensor: CloudSensor,
+}
+
+/// https://api.purpleair.com/#api-sensors-get-sensor-data
+#[derive(Deserialize)]
+struct CloudSensor {
+    sensor_index: u64,
+    name: Option<String>,
+    /// 0 is outside, 1 is inside
+    location_type: Option<u8>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    /// UNIX time stamp of the last time the cloud received data from the sensor
+    last_seen: Option<i64>,
+    firmware_version: Option<String>,
+    hardware: Option<String>,
+    uptime: Option<u64>,
+    rssi: Option<i64>,
+    memory: Option<u64>,
+    humidity: Option<f64>,
+    temperature: Option<f64>,
+    pressure: Option<f64>,
+
+    #[serde(rename = "pm1.0_atm_a")]
+    pm1_0_atm_a: Option<f64>,
+    #[serde(rename = "pm2.5_atm_a")]
+    pm2_5_atm_a: Option<f64>,
+    #[serde(rename = "pm10.0_atm_a")]
+    pm10_0_atm_a: Option<f64>,
+    #[serde(rename = "pm1.0_cf_1_a")]
+    pm1_0_cf_1_a: Option<f64>,
+    #[serde(rename = "pm2.5_cf_1_a")]
+    pm2_5_cf_1_a: Option<f64>,
+    #[serde(rename = "pm10.0_cf_1_a")]
+    pm10_0_cf_1_a: Option<f64>,
+    #[serde(rename = "0.3_um_count_a")]
+    um_count_0_3_a: Option<f64>,
+    #[serde(rename = "0.5_um_count_a")]
+    um_count_0_5_a: Option<f64>,
+    #[serde(rename = "1.0_um_count_a")]
+    um_count_1_0_a: Option<f64>,
+    #[serde(rename = "2.5_um_count_a")]
+    um_count_2_5_a: Option<f64>,
+    #[serde(rename = "5.0_um_count_a")]
+    um_count_5_0_a: Option<f64>,
+    #[serde(rename = "10.0_um_count_a")]
+    um_count_10_0_a: Option<f64>,
+
+    #[serde(rename = "pm1.0_atm_b")]
+    pm1_0_atm_b: Option<f64>,
+    #[serde(rename = "pm2.5_atm_b")]
+    pm2_5_atm_b: Option<f64>,
+    #[serde(rename = "pm10.0_atm_b")]
+    pm10_0_atm_b: Option<f64>,
+    #[serde(rename = "pm1.0_cf_1_b")]
+    pm1_0_cf_1_b: Option<f64>,
+    #[serde(rename = "pm2.5_cf_1_b")]
+    pm2_5_cf_1_b: Option<f64>,
+    #[serde(rename = "pm10.0_cf_1_b")]
+    pm10_0_cf_1_b: Option<f64>,
+    #[serde(rename = "0.3_um_count_b")]
+    um_count_0_3_b: Option<f64>,
+    #[serde(rename = "0.5_um_count_b")]
+    um_count_0_5_b: Option<f64>,
+    #[serde(rename = "1.0_um_count_b")]
+    um_count_1_0_b: Option<f64>,
+    #[serde(rename = "2.5_um_count_b")]
+    um_count_2_5_b: Option<f64>,
+    #[serde(rename = "5.0_um_count_b")]
+    um_count_5_0_b: Option<f64>,
+    #[serde(rename = "10.0_um_count_b")]
+    um_count_10_0_b: Option<f64>,
+}
+
+impl CloudSensor {
+    /// Maps the cloud fields onto the local JSON layout. Fields only the device itself knows,
+    /// such as heap statistics and the status of its uploads, are left empty.
+    fn into_sensor_data(self) -> LocalSensorData {
+        let hardware = self.hardware.unwrap_or_default();
+        let hardware_version = hardware.split('+').next().unwrap_or_default().to_string();
+        let place = if self.location_type == Some(1) { "inside" } else { "outside" };
+        let date_time = self.last_seen.and_then(|last_seen| DateTime::<Utc>::from_timestamp(last_seen, 0)).unwrap_or_else(Utc::now);
+
+        let pm2_5_aqi = self.pm2_5_atm_a.map(aqi::pm2_5_aqi);
+        let pm2_5_aqi_b = self.pm2_5_atm_b.map(aqi::pm2_5_aqi);
+        let color = |aqi: f64| AqiCategory::from_aqi(aqi).rgb().to_string();
+
+        LocalSensorData {
+            sensor_id: self.sensor_index.to_string(),
+            date_time,
+            geo: self.name.unwrap_or_default(),
+            mem: self.memory.unwrap_or_default(),
+            memfrag: 0,
+            memfb: 0,
+            memcs: 0,
+            id: 0,
+            lat: self.latitude.unwrap_or_default(),
+            lon: self.longitude.unwrap_or_default(),
+            logging_rate: 0,
+            place: place.to_string(),
+            version: self.firmware_version.unwrap_or_default(),
+            uptime: self.uptime.unwrap_or_default(),
+            rssi: self.rssi.unwrap_or_default(),
+            period: 120,
+            http_success: 0,
+            http_sends: 0,
+            hardware_version,
+            hardware_discovered: hardware,
+            wl_state: String::new(),
+            ssid: String::new(),
+            response: None,
+            response_date: None,
+            adc: 0.0,
+            current_temp_f: self.temperature.map(|temperature| temperature.round() as u64),
+            current_humidity: self.humidity.map(|humidity| humidity.round() as u64),
+            // The cloud does not report a dew point.
+            current_dewpoint_f: self.temperature.zip(self.humidity).map(|(temperature, humidity)| dewpoint_f(temperature, humidity).round() as u64),
+            pressure: self.pressure,
+            current_temp_f_680: None,
+            current_humidity_680: None,
+            current_dewpoint_f_680: None,
+            pressure_680: None,
+            gas_680: None,
+            p25aqic_b: pm2_5_aqi_b.map(color),
+            pm2_5_aqi_b,
+            pm1_0_cf_1_b: self.pm1_0_cf_1_b,
+            p_0_3_um_b: self.um_count_0_3_b,
+            pm2_5_cf_1_b: self.pm2_5_cf_1_b,
+            p_0_5_um_b: self.um_count_0_5_b,
+            pm10_0_cf_1_b: self.pm10_0_cf_1_b,
+            p_1_0_um_b: self.um_count_1_0_b,
+            pm1_0_atm_b: self.pm1_0_atm_b,
+            p_2_5_um_b: self.um_count_2_5_b,
+            pm2_5_atm_b: self.pm2_5_atm_b,
+            p_5_0_um_b: self.um_count_5_0_b,
+            pm10_0_atm_b: self.pm10_0_atm_b,
+            p_10_0_um_b: self.um_count_10_0_b,
+            p25aqic: pm2_5_aqi.map(color),
+            pm2_5_aqi,
+            pm1_0_cf_1: self.pm1_0_cf_1_a,
+            p_0_3_um: self.um_count_0_3_a,
+            pm2_5_cf_1: self.pm2_5_cf_1_a,
+            p_0_5_um: self.um_count_0_5_a,
+            pm10_0_cf_1: self.pm10_0_cf_1_a,
+            p_1_0_um: self.um_count_1_0_a,
+            pm1_0_atm: self.pm1_0_atm_a,
+            p_2_5_um: self.um_count_2_5_a,
+            pm2_5_atm: self.pm2_5_atm_a,
+            p_5_0_um: self.um_count_5_0_a,
+            pm10_0_atm: self.pm10_0_atm_a,
+            p_10_0_um: self.um_count_10_0_a,
+            status_ntp: Status::NotConfigured,
+            status_loc: Status::NotConfigured,
+            status_upd: Status::NotConfigured,
+            status_paa: Status::NotConfigured,
+            status_tsa: Status::NotConfigured,
+            status_tss_a: Status::NotConfigured,
+            status_for_processor_1: None,
+            status_tsb: Status::NotConfigured,
+            status_tss_b: Status::NotConfigured,
+            status_for_processor_2: None,
+        }
+    }
+}
+
+/// A client for the PurpleAir API that only asks for new data every `refresh_minutes` to save API points.
+pub struct CloudClient {
+    settings: CloudSettings,
+    client: reqwest::Client,
+    fetched_at: Option<Instant>,
+    /// When the cloud last heard from the sensor, as of the last response.
+    last_seen: Option<DateTime<Utc>>,
+}
+
+impl CloudClient {
+    pub fn new(settings: CloudSettings) -> Self {
+        CloudClient {
+            settings,
+            client: reqwest::Client::new(),
+            fetched_at: None,
+            last_seen: None,
+        }
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.settings.refresh_minutes * 60)
+    }
+
+    /// Requests the sensor from the API. Returns `None` without a request while the last response is fresh, and
+    /// when the cloud has not heard from the sensor since then, so the same reading is never returned twice.
+    pub async fn fetch(&mut self) -> Result<Option<LocalSensorData>, CloudError> {
+        if let Some(fetched_at) = self.fetched_at && fetched_at.elapsed() < self.refresh_interval() {
+            return Ok(None);
+        }
+
+        if self.settings.api_key.is_empty() {
+            return Err(CloudError::MissingApiKey);
+        }
+
+        let url = format!("{}/sensors/{}", self.settings.api_url.trim_end_matches('/'), self.settings.sensor_index);
+        let response = self
+            .client
+            .get(url.as_str())
+            .query(&[("fields", FIELDS)])
+            .header("X-API-Key", self.settings.api_key.as_str())
+            .timeout(Duration::from_secs(15))
+            .send()
+            .await
+            .map_err(CloudError::Request)?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(CloudError::Request)?;
+        if !status.is_success() {
+            return Err(CloudError::Status(status.as_u16(), body));
+        }
+
+        let response: SensorResponse = serde_json::from_str(body.as_str()).map_err(CloudError::Parse)?;
+        let sensor_data = response.sensor.into_sensor_data();
+        self.fetched_at = Some(Instant::now());
+        if self.last_seen == Some(sensor_data.date_time) {
+            return Ok(None);
+        }
+        self.last_seen = Some(sensor_data.date_time);
+
+        Ok(Some(sensor_data))
+    }
+}
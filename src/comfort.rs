@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TemperatureUnit {
+    /// The unit the sensor reports in.
+    #[default]
+    Fahrenheit,
+    Celsius,
+}
+
+impl TemperatureUnit {
+    pub const ALL: [TemperatureUnit; 2] = [TemperatureUnit::Fahrenheit, TemperatureUnit::Celsius];
+
+    /// A temperature given in Fahrenheit in this unit.
+    pub fn convert(&self, fahrenheit: f64) -> f64 {
+        match self {
+            TemperatureUnit::Fahrenheit => fahrenheit,
+            TemperatureUnit::Celsius => fahrenheit_to_celsius(fahrenheit),
+        }
+    }
+
+    /// Formats a temperature given in Fahrenheit, e.g. "77 °F" or "25 °C".
+    pub fn format(&self, fahrenheit: f64) -> String {
+        match self {
+            TemperatureUnit::Fahrenheit => format!("{} °F", fahrenheit.round()),
+            TemperatureUnit::Celsius => format!("{} °C", self.convert(fahrenheit).round()),
+        }
+    }
+}
+
+pub fn fahrenheit_to_celsius(fahrenheit: f64) -> f64 {
+    (fahrenheit - 32.0) * 5.0 / 9.0
+}
+
+pub fn celsius_to_fahrenheit(celsius: f64) -> f64 {
+    celsius * 9.0 / 5.0 + 32.0
+}
+
+/// The dew point in Fahrenheit using the Magnus approximation.
+pub fn dewpoint_f(temperature_f: f64, humidity: f64) -> f64 {
+    let temperature_c = fahrenheit_to_celsius(temperature_f);
+    let (b, c) = (17.62, 243.12);
+    let gamma = (humidity.max(1.0) / 100.0).ln() + b * temperature_c / (c + temperature_c);
+    celsius_to_fahrenheit(c * gamma / (b - gamma))
+}
+
+/// The NWS heat index in Fahrenheit: Steadman's simple formula, or the Rothfusz regression with its adjustments once
+/// that reaches 80 °F.
+/// https://www.wpc.ncep.noaa.gov/html/heatindex_equation.shtml
+pub fn heat_index_f(temperature_f: f64, humidity: f64) -> f64 {
+    let (t, rh) = (temperature_f, humidity);
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < 80.0 {
+        return simple;
+    }
+
+    let mut index = -42.379 + 2.04901523 * t + 10.14333127 * rh
+        - 0.22475541 * t * rh
+        - 0.00683783 * t * t
+        - 0.05481717 * rh * rh
+        + 0.00122874 * t * t * rh
+        + 0.00085282 * t * rh * rh
+        - 0.00000199 * t * t * rh * rh;
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
+        index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+        index += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+    }
+    index
+}
+
+/// The Canadian humidex, which has no unit but reads like a temperature in Celsius.
+/// https://climate.weather.gc.ca/glossary_e.html#humidex
+pub fn humidex(temperature_f: f64, humidity: f64) -> f64 {
+    let dewpoint_k = fahrenheit_to_celsius(dewpoint_f(temperature_f, humidity)) + 273.15;
+    let vapor_pressure = 6.11 * (5417.7530 * (1.0 / 273.16 - 1.0 / dewpoint_k)).exp();
+    fahrenheit_to_celsius(temperature_f) + 0.5555 * (vapor_pressure - 10.0)
+}
+
+/// The water vapor in the air in g/m³, from the Magnus saturation vapor pressure.
+pub fn absolute_humidity(temperature_f: f64, humidity: f64) -> f64 {
+    let temperature_c = fahrenheit_to_celsius(temperature_f);
+    let saturation = 6.112 * (17.67 * temperature_c / (temperature_c + 243.5)).exp();
+    saturation * humidity * 2.1674 / (273.15 + temperature_c)
+}
+
+/// Where the air sits relative to the usual indoor comfort range of 68–79 °F and 30–60 % relative humidity.
+/// Temperature goes first, a room that is too hot is not made comfortable by dry air.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComfortZone {
+    Cold,
+    Dry,
+    Comfortable,
+    Humid,
+    Hot,
+}
+
+impl ComfortZone {
+    pub fn new(temperature_f: f64, humidity: f64) -> ComfortZone {
+        match (temperature_f, humidity) {
+            (t, _) if t < 68.0 => ComfortZone::Cold,
+            (t, _) if t > 79.0 => ComfortZone::Hot,
+            (_, rh) if rh < 30.0 => ComfortZone::Dry,
+            (_, rh) if rh > 60.0 => ComfortZone::Humid,
+            _ => ComfortZone::Comfortable,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{} is not within {} of {}", actual, tolerance, expected);
+    }
+
+    #[test]
+    fn heat_index_matches_the_nws_table() {
+        assert_near(heat_index_f(90.0, 70.0), 106.0, 0.5);
+        assert_near(heat_index_f(96.0, 65.0), 121.0, 0.5);
+        assert_near(heat_index_f(80.0, 40.0), 80.0, 0.5);
+    }
+
+    #[test]
+    fn heat_index_adjusts_for_dry_heat() {
+        // The regression alone gives 94.75 °F.
+        assert_near(heat_index_f(100.0, 10.0), 94.12, 0.01);
+    }
+
+    #[test]
+    fn heat_index_adjusts_for_humid_warmth() {
+        // The regression alone gives 98.04 °F and 101.58 °F.
+        assert_near(heat_index_f(84.0, 90.0), 98.34, 0.01);
+        assert_near(heat_index_f(85.0, 90.0), 101.78, 0.01);
+    }
+
+    #[test]
+    fn heat_index_below_80_uses_the_simple_formula() {
+        assert_near(heat_index_f(70.0, 50.0), 69.05, 0.001);
+        assert_near(heat_index_f(60.0, 30.0), 0.5 * (60.0 + 61.0 + (60.0 - 68.0) * 1.2 + 30.0 * 0.094), 0.001);
+    }
+
+    #[test]
+    fn humidex_matches_the_eccc_table() {
+        for (temperature_c, humidity, expected) in [(25.0, 60.0, 30.0), (30.0, 70.0, 41.0), (35.0, 50.0, 45.0), (40.0, 40.0, 51.0)] {
+            assert_near(humidex(celsius_to_fahrenheit(temperature_c), humidity), expected, 0.5);
+        }
+    }
+
+    #[test]
+    fn absolute_humidity_at_room_temperature() {
+        assert_near(absolute_humidity(celsius_to_fahrenheit(25.0), 50.0), 11.5, 0.05);
+    }
+
+    #[test]
+    fn comfort_zone_boundaries() {
+        assert_eq!(ComfortZone::new(67.9, 45.0), ComfortZone::Cold);
+        assert_eq!(ComfortZone::new(68.0, 45.0), ComfortZone::Comfortable);
+        assert_eq!(ComfortZone::new(79.0, 45.0), ComfortZone::Comfortable);
+        assert_eq!(ComfortZone::new(79.1, 45.0), ComfortZone::Hot);
+        assert_eq!(ComfortZone::new(72.0, 29.9), ComfortZone::Dry);
+        assert_eq!(ComfortZone::new(72.0, 30.0), ComfortZone::Comfortable);
+        assert_eq!(ComfortZone::new(72.0, 60.0), ComfortZone::Comfortable);
+        assert_eq!(ComfortZone::new(72.0, 60.1), ComfortZone::Humid);
+        // The temperature decides first.
+        assert_eq!(ComfortZone::new(60.0, 80.0), ComfortZone::Cold);
+        assert_eq!(ComfortZone::new(85.0, 20.0), ComfortZone::Hot);
+    }
+}
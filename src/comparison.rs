@@ -0,0 +1,251 @@
+use crate::sensor_data::LocalSensorData;
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use std::collections::VecDeque;
+
+/// Readings are averaged into one point per this long, the sensors are read every few seconds.
+const POINT_INTERVAL: TimeDelta = TimeDelta::minutes(1);
+/// A day of points.
+const SERIES_CAPACITY: usize = 1440;
+/// An indoor reading is compared with the outdoor reading closest in time, if there is one at most this far away.
+const PAIRING_WINDOW: TimeDelta = TimeDelta::minutes(5);
+/// Outdoor concentrations below this make the ratio meaningless, 1 µg/m³ indoors would already be a ratio of 10.
+const MIN_OUTDOOR_PM2_5: f64 = 1.0;
+/// The lag is found by comparing averages over bins this long.
+const LAG_BIN: TimeDelta = TimeDelta::minutes(5);
+const MAX_LAG_BINS: usize = 36;
+/// Indoor levels only count as following the outdoor ones if the shifted series correlate at least this well.
+const MIN_LAG_CORRELATION: f64 = 0.5;
+
+/// A reading reduced to what the comparison needs.
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    pub time: DateTime<Utc>,
+    /// Channel A PM2.5 in µg/m³.
+    pub pm2_5: f64,
+    pub aqi: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recommendation {
+    KeepWindowsClosed,
+    SafeToVentilate,
+}
+
+impl Recommendation {
+    /// Outdoor air is let in when it is Good, or when it is cleaner than the indoor air and not unhealthy for anyone yet.
+    pub fn new(indoor: &Point, outdoor: &Point) -> Recommendation {
+        if outdoor.aqi <= 50.0 || (outdoor.pm2_5 < indoor.pm2_5 && outdoor.aqi <= 100.0) {
+            Recommendation::SafeToVentilate
+        } else {
+            Recommendation::KeepWindowsClosed
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Series {
+    url: String,
+    indoor: bool,
+    points: VecDeque<Point>,
+    /// How many readings the last point averages.
+    last_readings: u32,
+}
+
+/// The readings of every configured sensor, sorted into indoor and outdoor ones by the `place` they report.
+#[derive(Debug, Default)]
+pub struct IndoorOutdoor {
+    series: Vec<Series>,
+}
+
+impl IndoorOutdoor {
+    pub fn observe(&mut self, url: &str, sensor_data: &LocalSensorData, time: DateTime<Utc>) {
+        let (Some(pm2_5), Some(aqi)) = (sensor_data.pm2_5_atm, sensor_data.pm2_5_aqi) else {
+            return;
+        };
+        let indoor = sensor_data.place == "inside";
+
+        let index = match self.series.iter().position(|series| series.url == url) {
+            Some(index) => index,
+            None => {
+                self.series.push(Series { url: url.to_string(), indoor, points: VecDeque::new(), last_readings: 0 });
+                self.series.len() - 1
+            }
+        };
+        let series = &mut self.series[index];
+        // A sensor that was moved starts over.
+        if series.indoor != indoor {
+            series.indoor = indoor;
+            series.points.clear();
+        }
+
+        let time = time.duration_trunc(POINT_INTERVAL).unwrap_or(time);
+        if let Some(last) = series.points.back_mut() && last.time == time {
+            series.last_readings += 1;
+            let weight = 1.0 / series.last_readings as f64;
+            last.pm2_5 += (pm2_5 - last.pm2_5) * weight;
+            last.aqi += (aqi - last.aqi) * weight;
+            return;
+        }
+
+        if series.points.len() == SERIES_CAPACITY {
+            series.points.pop_front();
+        }
+        series.points.push_back(Point { time, pm2_5, aqi });
+        series.last_readings = 1;
+    }
+
+    /// Forgets the sensors that are no longer configured.
+    pub fn retain(&mut self, urls: &[&str]) {
+        self.series.retain(|series| urls.contains(&series.url.as_str()));
+    }
+
+    /// The first indoor and the first outdoor sensor that have readings.
+    pub fn pair(&self) -> Option<Comparison<'_>> {
+        let find = |indoor: bool| self.series.iter().find(|series| series.indoor == indoor && !series.points.is_empty());
+        let (indoor, outdoor) = (find(true)?, find(false)?);
+
+        Some(Comparison {
+            indoor_url: indoor.url.as_str(),
+            outdoor_url: outdoor.url.as_str(),
+            indoor: &indoor.points,
+            outdoor: &outdoor.points,
+        })
+    }
+}
+
+pub struct Comparison<'a> {
+    pub indoor_url: &'a str,
+    pub outdoor_url: &'a str,
+    pub indoor: &'a VecDeque<Point>,
+    pub outdoor: &'a VecDeque<Point>,
+}
+
+impl Comparison<'_> {
+    /// The outdoor reading closest in time to `time`.
+    fn outdoor_at(&self, time: DateTime<Utc>) -> Option<&Point> {
+        self.outdoor
+            .iter()
+            .filter(|point| (point.time - time).abs() <= PAIRING_WINDOW)
+            .min_by_key(|point| (point.time - time).abs())
+    }
+
+    /// The indoor/outdoor PM2.5 ratio at every indoor reading that has an outdoor reading close by.
+    /// Below 1 the building keeps particles out, above 1 they come from inside.
+    pub fn ratios(&self) -> Vec<(DateTime<Utc>, f64)> {
+        self.indoor
+            .iter()
+            .filter_map(|indoor| {
+                let outdoor = self.outdoor_at(indoor.time)?;
+                (outdoor.pm2_5 >= MIN_OUTDOOR_PM2_5).then(|| (indoor.time, indoor.pm2_5 / outdoor.pm2_5))
+            })
+            .collect()
+    }
+
+    /// The newest indoor reading and the outdoor reading closest to it.
+    pub fn latest(&self) -> Option<(&Point, &Point)> {
+        let indoor = self.indoor.back()?;
+        Some((indoor, self.outdoor_at(indoor.time)?))
+    }
+
+    pub fn recommendation(&self) -> Option<Recommendation> {
+        self.latest().map(|(indoor, outdoor)| Recommendation::new(indoor, outdoor))
+    }
+
+    /// How long indoor levels take to follow outdoor ones: the shift that best lines up the two series, up to 3 hours.
+    /// `None` until there is enough overlap, or if the indoor levels do not follow the outdoor ones at all.
+    pub fn lag(&self) -> Option<TimeDelta> {
+        let start = self.indoor.front()?.time.max(self.outdoor.front()?.time);
+        let end = self.indoor.back()?.time.min(self.outdoor.back()?.time);
+        if end <= start {
+            return None;
+        }
+
+        let indoor = bin(self.indoor, start, end);
+        let outdoor = bin(self.outdoor, start, end);
+        let (best_lag, best_correlation) = (0..=MAX_LAG_BINS.min(outdoor.len().saturating_sub(1)))
+            .filter_map(|lag| {
+                let pairs: Vec<(f64, f64)> = outdoor.iter().zip(indoor.iter().skip(lag))
+                    .filter_map(|(outdoor, indoor)| Some(((*outdoor)?, (*indoor)?)))
+                    .collect();
+                correlation(&pairs).map(|correlation| (lag, correlation))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        (best_correlation >= MIN_LAG_CORRELATION).then(|| LAG_BIN * best_lag as i32)
+    }
+}
+
+/// The mean PM2.5 of the points in each `LAG_BIN` from `start` to `end`, `None` for bins without points.
+fn bin(points: &VecDeque<Point>, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<Option<f64>> {
+    let count = ((end - start).num_seconds() / LAG_BIN.num_seconds()) as usize + 1;
+    let mut sums = vec![(0.0, 0); count];
+    for point in points.iter().filter(|point| point.time >= start && point.time <= end) {
+        let index = ((point.time - start).num_seconds() / LAG_BIN.num_seconds()) as usize;
+        sums[index].0 += point.pm2_5;
+        sums[index].1 += 1;
+    }
+    sums.into_iter().map(|(sum, count)| (count > 0).then(|| sum / count as f64)).collect()
+}
+
+/// The Pearson correlation of the pairs, `None` with too few pairs or no variation to correlate.
+fn correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 6 {
+        return None;
+    }
+
+    let count = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance: f64 = pairs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance_x: f64 = pairs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let variance_y: f64 = pairs.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    if variance_x <= f64::EPSILON || variance_y <= f64::EPSILON {
+        return None;
+    }
+
+    Some(covariance / (variance_x * variance_y).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sensor_data(place: &str, pm2_5: f64) -> LocalSensorData {
+        let mut sensor_data: LocalSensorData = serde_json::from_str(include_str!("../fixtures/pa-ii.json")).unwrap();
+        sensor_data.place = place.to_string();
+        sensor_data.pm2_5_atm = Some(pm2_5);
+        sensor_data.pm2_5_aqi = Some(crate::aqi::pm2_5_aqi(pm2_5));
+        sensor_data
+    }
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 29, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn readings_are_averaged_per_minute() {
+        let mut comparison = IndoorOutdoor::default();
+        for (seconds, pm2_5) in [(0, 2.0), (20, 4.0), (40, 6.0), (60, 10.0)] {
+            comparison.observe("http://outdoor/json", &sensor_data("outside", pm2_5), start() + TimeDelta::seconds(seconds));
+        }
+        comparison.observe("http://indoor/json", &sensor_data("inside", 1.0), start());
+
+        let pair = comparison.pair().unwrap();
+        let points: Vec<(DateTime<Utc>, f64)> = pair.outdoor.iter().map(|point| (point.time, point.pm2_5)).collect();
+        assert_eq!(points, [(start(), 4.0), (start() + TimeDelta::minutes(1), 10.0)]);
+    }
+
+    #[test]
+    fn a_day_of_fast_readings_fits() {
+        let mut comparison = IndoorOutdoor::default();
+        for tick in 0..24 * 60 * 12 {
+            comparison.observe("http://indoor/json", &sensor_data("inside", 5.0), start() + TimeDelta::seconds(tick * 5));
+        }
+        comparison.observe("http://outdoor/json", &sensor_data("outside", 5.0), start());
+
+        let indoor = comparison.pair().unwrap().indoor;
+        assert_eq!(indoor.len(), SERIES_CAPACITY);
+        assert_eq!(indoor.front().unwrap().time, start());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::cloud::{CloudClient, CloudSettings};
+use crate::data_source::{DataSource, Pacer, ReadingFuture, SourceError, SourceHealth, SourceMetadata};
+use crate::history::{Reading, ReadingKind};
+use crate::settings::DataSourceKind;
+use std::time::Duration;
+
+/// The client only requests new data every `refresh_minutes`, so polling it often costs no API points.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Reads the sensor through the PurpleAir API.
+pub struct CloudSource {
+    client: CloudClient,
+    location: String,
+    pacer: Pacer,
+    health: SourceHealth,
+}
+
+impl CloudSource {
+    pub fn new(settings: CloudSettings) -> Self {
+        CloudSource {
+            location: format!("{}/sensors/{}", settings.api_url, settings.sensor_index),
+            client: CloudClient::new(settings),
+            pacer: Pacer::new(POLL_INTERVAL),
+            health: SourceHealth::default(),
+        }
+    }
+}
+
+impl DataSource for CloudSource {
+    fn next_reading(&mut self) -> ReadingFuture<'_> {
+        Box::pin(async move {
+            loop {
+                self.pacer.wait().await;
+                let result = match self.client.fetch().await {
+                    Ok(Some(sensor_data)) => Ok(Reading {
+                        kind: ReadingKind::Averaged,
+                        sensor_data,
+                    }),
+                    // Nothing new yet, ask again at the next poll.
+                    Ok(None) => continue,
+                    Err(error) => Err(SourceError::Cloud(error)),
+                };
+                self.health.record(&result);
+                return result;
+            }
+        })
+    }
+
+    fn metadata(&self) -> SourceMetadata {
+        SourceMetadata {
+            kind: DataSourceKind::Cloud,
+            location: self.location.clone(),
+        }
+    }
+
+    fn health(&self) -> &SourceHealth {
+        &self.health
+    }
+}
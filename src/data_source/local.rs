@@ -0,0 +1,86 @@
+use crate::data_source::{DataSource, Pacer, ReadingFuture, RecordedResponse, SourceError, SourceHealth, SourceMetadata};
+use crate::history::{Reading, ReadingKind};
+use crate::settings::DataSourceKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const LIVE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reads the sensor's `/json` endpoint on the local network.
+pub struct LocalSource {
+    url: String,
+    kind: ReadingKind,
+    client: reqwest::Client,
+    pacer: Pacer,
+    health: SourceHealth,
+
+    /// Every raw response body is appended to this file, see `ReplaySource`.
+    recording: Option<PathBuf>,
+}
+
+impl LocalSource {
+    pub fn new(url: String, poll_interval: Duration, live_mode: bool, recording: Option<PathBuf>) -> Self {
+        let (kind, poll_interval) = if live_mode {
+            (ReadingKind::Live, LIVE_POLL_INTERVAL)
+        } else {
+            (ReadingKind::Averaged, poll_interval)
+        };
+
+        LocalSource {
+            url,
+            kind,
+            client: reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build().unwrap_or_default(),
+            pacer: Pacer::new(poll_interval),
+            health: SourceHealth::default(),
+            recording,
+        }
+    }
+
+    async fn fetch(&self) -> Result<Reading, SourceError> {
+        let response = self.client.get(self.kind.url(self.url.as_str())).send().await.map_err(SourceError::Request)?;
+        if !response.status().is_success() {
+            return Err(SourceError::Status(response.status()));
+        }
+        let json_text = response.text().await.map_err(SourceError::Request)?;
+
+        // Recorded before parsing so that responses the parser chokes on can be replayed too.
+        if let Some(path) = &self.recording {
+            let recorded = RecordedResponse {
+                received_at: chrono::Utc::now(),
+                kind: self.kind,
+                body: json_text.clone(),
+            };
+            if let Err(error) = recorded.append_to(path).await {
+                eprintln!("Failed to record the response to {}: {}", path.display(), error);
+            }
+        }
+
+        Ok(Reading {
+            kind: self.kind,
+            sensor_data: serde_json::from_str(json_text.as_str()).map_err(SourceError::Parse)?,
+        })
+    }
+}
+
+impl DataSource for LocalSource {
+    fn next_reading(&mut self) -> ReadingFuture<'_> {
+        Box::pin(async move {
+            self.pacer.wait().await;
+            let result = self.fetch().await;
+            self.health.record(&result);
+            result
+        })
+    }
+
+    fn metadata(&self) -> SourceMetadata {
+        SourceMetadata {
+            kind: DataSourceKind::Local,
+            location: self.kind.url(self.url.as_str()),
+        }
+    }
+
+    fn health(&self) -> &SourceHealth {
+        &self.health
+    }
+}
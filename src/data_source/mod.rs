@@ -0,0 +1,280 @@
+mod cloud;
+mod local;
+mod replay;
+mod simulator;
+
+pub use cloud::CloudSource;
+pub use local::LocalSource;
+pub use replay::{RecordedResponse, ReplaySettings, ReplaySource};
+pub use simulator::{SimulatorSettings, SimulatorSource};
+
+use crate::cloud::CloudError;
+use crate::history::Reading;
+use crate::settings::{DataSourceKind, Settings};
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+pub type ReadingFuture<'a> = Pin<Box<dyn Future<Output = Result<Reading, SourceError>> + Send + 'a>>;
+
+/// A backend that produces sensor readings, e.g. the sensor on the local network, the PurpleAir API,
+/// a recorded session or a simulator. The GUI and any other consumer read from every backend the same way.
+pub trait DataSource: Send {
+    /// Waits until the next reading is due and returns it. The first call returns as soon as possible.
+    fn next_reading(&mut self) -> ReadingFuture<'_>;
+
+    fn metadata(&self) -> SourceMetadata;
+
+    fn health(&self) -> &SourceHealth;
+}
+
+/// Describes where a source reads from.
+#[derive(Debug, Clone)]
+pub struct SourceMetadata {
+    pub kind: DataSourceKind,
+
+    /// The URL, file or other location the readings come from.
+    pub location: String,
+}
+
+#[derive(Debug)]
+pub enum SourceError {
+    Request(reqwest::Error),
+
+    /// The sensor answered with an error status instead of its readings.
+    Status(reqwest::StatusCode),
+
+    Parse(serde_json::Error),
+    Cloud(CloudError),
+    Io(std::io::Error),
+
+    /// The source could not produce a reading this time, e.g. a simulated Wi-Fi dropout.
+    Unavailable(String),
+
+    /// The source has no more readings, e.g. a replay reached the end of its recording.
+    Finished,
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Request(error) => write!(f, "request failed: {}", error),
+            SourceError::Status(status) => write!(f, "the sensor answered HTTP {}", status),
+            SourceError::Parse(error) => write!(f, "invalid sensor data: {}", error),
+            SourceError::Cloud(error) => write!(f, "PurpleAir API: {}", error),
+            SourceError::Io(error) => write!(f, "{}", error),
+            SourceError::Unavailable(reason) => write!(f, "{}", reason),
+            SourceError::Finished => write!(f, "no more readings"),
+        }
+    }
+}
+
+/// How well a source has been doing lately.
+#[derive(Debug, Clone, Default)]
+pub struct SourceHealth {
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+    pub total_readings: u64,
+    pub total_failures: u64,
+}
+
+impl SourceHealth {
+    pub fn record(&mut self, result: &Result<Reading, SourceError>) {
+        match result {
+            Ok(_) => {
+                self.last_success = Some(Utc::now());
+                self.consecutive_failures = 0;
+                self.total_readings += 1;
+            }
+            Err(error) => {
+                self.last_error = Some(error.to_string());
+                self.consecutive_failures += 1;
+                self.total_failures += 1;
+            }
+        }
+    }
+}
+
+/// Creates the source selected in the settings.
+pub fn from_settings(settings: &Settings) -> Box<dyn DataSource> {
+    match settings.source {
+        DataSourceKind::Local => Box::new(LocalSource::new(
+            settings.sensor().url.clone(),
+            Duration::from_secs(settings.poll_interval_seconds),
+            settings.live_mode,
+            settings.recording.clone(),
+        )),
+        DataSourceKind::Cloud => Box::new(CloudSource::new(settings.cloud.clone())),
+        DataSourceKind::Replay => Box::new(ReplaySource::new(settings.replay.clone())),
+        DataSourceKind::Simulator => Box::new(SimulatorSource::new(settings.simulator.clone())),
+    }
+}
+
+/// Spaces out readings. The first wait returns immediately, every later one sleeps for the interval.
+struct Pacer {
+    interval: Duration,
+    started: bool,
+}
+
+impl Pacer {
+    fn new(interval: Duration) -> Self {
+        Pacer {
+            interval,
+            started: false,
+        }
+    }
+
+    async fn wait(&mut self) {
+        if self.started {
+            tokio::time::sleep(self.interval).await;
+        }
+        self.started = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::ReadingKind;
+    use std::path::PathBuf;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::time::Instant;
+
+    fn replay_settings(speed: f64) -> Settings {
+        Settings {
+            source: DataSourceKind::Replay,
+            replay: ReplaySettings {
+                path: PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/replay.jsonl")),
+                speed,
+            },
+            ..Settings::default()
+        }
+    }
+
+    /// Answers a single request with `status` and the fixture as the body.
+    async fn answer_once(status: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/json", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await;
+
+            let body = include_str!("../../fixtures/pa-ii.json");
+            let response = format!("HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn local_source_reads_the_sensor() {
+        let mut source = LocalSource::new(answer_once("200 OK").await, Duration::from_secs(10), false, None);
+
+        let reading = source.next_reading().await.unwrap();
+        assert_eq!(reading.kind, ReadingKind::Averaged);
+        assert_eq!(source.health().total_readings, 1);
+    }
+
+    #[tokio::test]
+    async fn local_source_rejects_error_statuses() {
+        let mut source = LocalSource::new(answer_once("503 Service Unavailable").await, Duration::from_secs(10), false, None);
+
+        let result = source.next_reading().await;
+        assert!(matches!(result, Err(SourceError::Status(reqwest::StatusCode::SERVICE_UNAVAILABLE))));
+        assert_eq!(source.health().total_failures, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pacer_waits_from_the_second_reading() {
+        let mut pacer = Pacer::new(Duration::from_secs(5));
+
+        let started = Instant::now();
+        pacer.wait().await;
+        assert_eq!(started.elapsed(), Duration::ZERO);
+        pacer.wait().await;
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+        pacer.wait().await;
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn simulator_from_settings() {
+        let settings = Settings {
+            source: DataSourceKind::Simulator,
+            simulator: SimulatorSettings {
+                interval_seconds: 10,
+                time_scale: 6.0,
+                dropout_chance: 0.0,
+                ..SimulatorSettings::default()
+            },
+            ..Settings::default()
+        };
+        let mut source = from_settings(&settings);
+        assert_eq!(source.metadata().kind, DataSourceKind::Simulator);
+
+        let started = Instant::now();
+        let first = source.next_reading().await.unwrap();
+        assert_eq!(started.elapsed(), Duration::ZERO);
+        let second = source.next_reading().await.unwrap();
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+
+        assert_eq!(first.kind, ReadingKind::Averaged);
+        assert_eq!(second.sensor_data.date_time - first.sensor_data.date_time, chrono::Duration::seconds(60));
+        assert!(second.sensor_data.pm2_5_aqi.is_some());
+        assert_eq!(source.health().total_readings, 2);
+        assert_eq!(source.health().total_failures, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_from_settings() {
+        let mut source = from_settings(&replay_settings(1.0));
+        assert_eq!(source.metadata().kind, DataSourceKind::Replay);
+        assert!(source.metadata().location.ends_with("replay.jsonl"));
+
+        let started = Instant::now();
+        let aqis = [31.0, 35.0, 42.0];
+        for (index, aqi) in aqis.iter().enumerate() {
+            let reading = source.next_reading().await.unwrap();
+            assert_eq!(reading.kind, ReadingKind::Averaged);
+            assert_eq!(reading.sensor_data.pm2_5_aqi, Some(*aqi));
+            // The recording was made 10 seconds apart.
+            assert_eq!(started.elapsed(), Duration::from_secs(10 * index as u64));
+        }
+
+        assert!(matches!(source.next_reading().await, Err(SourceError::Finished)));
+        assert_eq!(source.health().total_readings, 3);
+        assert_eq!(source.health().total_failures, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_speed_shortens_the_gaps() {
+        let mut source = from_settings(&replay_settings(10.0));
+
+        let started = Instant::now();
+        for _ in 0..3 {
+            source.next_reading().await.unwrap();
+        }
+        assert_eq!(started.elapsed(), Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn replay_reports_a_missing_recording_once() {
+        let mut source = from_settings(&Settings {
+            source: DataSourceKind::Replay,
+            replay: ReplaySettings {
+                path: PathBuf::from("does-not-exist.jsonl"),
+                speed: 1.0,
+            },
+            ..Settings::default()
+        });
+
+        assert!(matches!(source.next_reading().await, Err(SourceError::Io(_))));
+        assert!(matches!(source.next_reading().await, Err(SourceError::Finished)));
+    }
+}
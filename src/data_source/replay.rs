@@ -0,0 +1,125 @@
+use crate::data_source::{DataSource, ReadingFuture, SourceError, SourceHealth, SourceMetadata};
+use crate::history::{Reading, ReadingKind};
+use crate::settings::DataSourceKind;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ReplaySettings {
+    pub path: PathBuf,
+
+    /// How many times faster than real time the recording is played back.
+    pub speed: f64,
+}
+
+impl Default for ReplaySettings {
+    fn default() -> Self {
+        ReplaySettings {
+            path: PathBuf::from("recording.jsonl"),
+            speed: 1.0,
+        }
+    }
+}
+
+/// One raw `/json` response body, one per line in a recording file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedResponse {
+    pub received_at: DateTime<Utc>,
+    pub kind: ReadingKind,
+    pub body: String,
+}
+
+impl RecordedResponse {
+    /// Appends the response to a recording file as one line.
+    pub async fn append_to(&self, path: &Path) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(self)?;
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(path).await?;
+        file.write_all(line.as_bytes()).await
+    }
+}
+
+/// Plays back a recording file with the same spacing the responses were originally received with,
+/// or with the spacing shortened by the replay speed.
+pub struct ReplaySource {
+    path: PathBuf,
+    speed: f64,
+    responses: Option<VecDeque<RecordedResponse>>,
+    previous: Option<DateTime<Utc>>,
+    health: SourceHealth,
+}
+
+impl ReplaySource {
+    pub fn new(settings: ReplaySettings) -> Self {
+        ReplaySource {
+            path: settings.path,
+            speed: settings.speed,
+            responses: None,
+            previous: None,
+            health: SourceHealth::default(),
+        }
+    }
+
+    async fn load(&self) -> Result<VecDeque<RecordedResponse>, SourceError> {
+        let contents = tokio::fs::read_to_string(&self.path).await.map_err(SourceError::Io)?;
+
+        contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(SourceError::Parse))
+            .collect()
+    }
+
+    async fn replay_next(&mut self) -> Result<Reading, SourceError> {
+        if self.responses.is_none() {
+            // A recording that cannot be read is reported once, after that the replay is finished.
+            match self.load().await {
+                Ok(responses) => self.responses = Some(responses),
+                Err(error) => {
+                    self.responses = Some(VecDeque::new());
+                    return Err(error);
+                }
+            }
+        }
+
+        let response = self.responses.as_mut().and_then(|responses| responses.pop_front()).ok_or(SourceError::Finished)?;
+        if let Some(previous) = self.previous
+            && let Ok(gap) = (response.received_at - previous).to_std()
+            && self.speed > 0.0
+        {
+            tokio::time::sleep(gap.div_f64(self.speed)).await;
+        }
+        self.previous = Some(response.received_at);
+
+        Ok(Reading {
+            kind: response.kind,
+            sensor_data: serde_json::from_str(response.body.as_str()).map_err(SourceError::Parse)?,
+        })
+    }
+}
+
+impl DataSource for ReplaySource {
+    fn next_reading(&mut self) -> ReadingFuture<'_> {
+        Box::pin(async move {
+            let result = self.replay_next().await;
+            self.health.record(&result);
+            result
+        })
+    }
+
+    fn metadata(&self) -> SourceMetadata {
+        SourceMetadata {
+            kind: DataSourceKind::Replay,
+            location: self.path.display().to_string(),
+        }
+    }
+
+    fn health(&self) -> &SourceHealth {
+        &self.health
+    }
+}
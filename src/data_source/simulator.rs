@@ -0,0 +1,403 @@
+use crate::aqi::{self, AqiCategory};
+use crate::comfort::dewpoint_f;
+use crate::data_source::{DataSource, Pacer, ReadingFuture, SourceError, SourceHealth, SourceMetadata};
+use crate::history::{Reading, ReadingKind};
+use crate::sensor_data::{LocalSensorData, Status};
+use crate::settings::DataSourceKind;
+use chrono::{DateTime, Timelike, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+/// The reading every simulated reading starts from, a PA-II with both laser counters and a BME280.
+const TEMPLATE: &str = include_str!("../../fixtures/pa-ii.json");
+/// 2025-06-01 00:00 UTC, where the simulated clock starts unless configured otherwise.
+const DEFAULT_START_TIMESTAMP: i64 = 1_748_736_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SimulatorSettings {
+    /// Readings are produced this often in real time.
+    pub interval_seconds: u64,
+
+    /// The same seed always produces the same sequence of readings.
+    pub seed: u64,
+
+    /// How many simulated seconds pass per real second, e.g. 60 plays back a day in 24 minutes.
+    pub time_scale: f64,
+
+    /// Where the simulated clock starts. It is fixed rather than the current time so a seed reproduces a run exactly.
+    pub start_time: DateTime<Utc>,
+
+    /// The offset from UTC of the simulated day's clock, e.g. -7 for California in summer. The temperature
+    /// and traffic peaks follow this clock rather than the local time zone, which would change the readings.
+    pub utc_offset_hours: f64,
+
+    /// The chance per simulated hour that a wildfire smoke event starts.
+    pub smoke_events_per_hour: f64,
+
+    /// The chance per simulated hour of a short coarse dust spike.
+    pub dust_spikes_per_hour: f64,
+
+    /// The chance per reading that the sensor drops off the Wi-Fi.
+    pub dropout_chance: f64,
+
+    /// The chance per reading that an upload status reports an error.
+    pub status_error_chance: f64,
+
+    /// How much channel B drifts away from channel A per simulated day, e.g. 0.01 is 1% a day.
+    pub channel_b_drift_per_day: f64,
+}
+
+impl Default for SimulatorSettings {
+    fn default() -> Self {
+        SimulatorSettings {
+            interval_seconds: 10,
+            seed: 0,
+            time_scale: 1.0,
+            start_time: DateTime::from_timestamp(DEFAULT_START_TIMESTAMP, 0).expect("the default start time is valid"),
+            utc_offset_hours: 0.0,
+            smoke_events_per_hour: 0.02,
+            dust_spikes_per_hour: 0.1,
+            dropout_chance: 0.01,
+            status_error_chance: 0.01,
+            channel_b_drift_per_day: 0.0,
+        }
+    }
+}
+
+/// A pollution event, either smoke or dust, that rises to its peak and decays again.
+struct Event {
+    kind: EventKind,
+    start: DateTime<Utc>,
+    duration: chrono::Duration,
+    peak: f64,
+}
+
+#[derive(Clone, Copy)]
+enum EventKind {
+    /// Fine particles, most of the mass is below 1 µm.
+    Smoke,
+
+    /// Coarse particles, most of the mass is above 2.5 µm.
+    Dust,
+}
+
+impl Event {
+    /// The concentration the event adds at `time`, following a sine bump from start to end.
+    fn concentration(&self, time: DateTime<Utc>) -> f64 {
+        let elapsed = (time - self.start).num_seconds() as f64;
+        let duration = self.duration.num_seconds().max(1) as f64;
+        let progress = (elapsed / duration).clamp(0.0, 1.0);
+        self.peak * (progress * TAU / 2.0).sin()
+    }
+
+    fn is_over(&self, time: DateTime<Utc>) -> bool {
+        time >= self.start + self.duration
+    }
+}
+
+/// The particle mass in µg/m³ of one channel, split by size.
+#[derive(Clone, Copy)]
+struct Particles {
+    /// Below 1 µm.
+    pm1_0: f64,
+    /// Below 2.5 µm, including the PM1.
+    pm2_5: f64,
+    /// Below 10 µm, including the PM2.5.
+    pm10_0: f64,
+}
+
+impl Particles {
+    fn scaled(&self, factor: f64) -> Particles {
+        Particles {
+            pm1_0: self.pm1_0 * factor,
+            pm2_5: self.pm2_5 * factor,
+            pm10_0: self.pm10_0 * factor,
+        }
+    }
+
+    /// The CF=1 values match ATM in clean air and read higher as the concentration rises.
+    fn cf_1(&self) -> Particles {
+        let factor = |atm: f64| 1.0 + (atm - 25.0).max(0.0) / 100.0;
+        Particles {
+            pm1_0: self.pm1_0 * factor(self.pm1_0),
+            pm2_5: self.pm2_5 * factor(self.pm2_5),
+            pm10_0: self.pm10_0 * factor(self.pm10_0),
+        }
+    }
+
+    /// Rough particle counts per deciliter for each size bin, from 0.3 µm up to 10 µm.
+    fn counts(&self) -> [f64; 6] {
+        let fine = self.pm1_0;
+        let medium = self.pm2_5 - self.pm1_0;
+        let coarse = self.pm10_0 - self.pm2_5;
+        [
+            fine * 190.0 + medium * 40.0 + coarse * 10.0,
+            fine * 55.0 + medium * 25.0 + coarse * 8.0,
+            fine * 9.0 + medium * 12.0 + coarse * 6.0,
+            fine * 0.6 + medium * 2.5 + coarse * 4.0,
+            medium * 0.4 + coarse * 1.5,
+            coarse * 0.5,
+        ]
+    }
+}
+
+/// Writes the mass concentrations and counts of both channels along with the AQI and LED color the sensor derives from them.
+fn set_particles(sensor_data: &mut LocalSensorData, channel_a: Particles, channel_b: Particles) {
+    let aqi_a = aqi::pm2_5_aqi(channel_a.pm2_5);
+    let cf_1_a = channel_a.cf_1();
+    let [p_0_3, p_0_5, p_1_0, p_2_5, p_5_0, p_10_0] = channel_a.counts();
+    sensor_data.pm1_0_atm = Some(channel_a.pm1_0);
+    sensor_data.pm2_5_atm = Some(channel_a.pm2_5);
+    sensor_data.pm10_0_atm = Some(channel_a.pm10_0);
+    sensor_data.pm1_0_cf_1 = Some(cf_1_a.pm1_0);
+    sensor_data.pm2_5_cf_1 = Some(cf_1_a.pm2_5);
+    sensor_data.pm10_0_cf_1 = Some(cf_1_a.pm10_0);
+    sensor_data.p_0_3_um = Some(p_0_3);
+    sensor_data.p_0_5_um = Some(p_0_5);
+    sensor_data.p_1_0_um = Some(p_1_0);
+    sensor_data.p_2_5_um = Some(p_2_5);
+    sensor_data.p_5_0_um = Some(p_5_0);
+    sensor_data.p_10_0_um = Some(p_10_0);
+    sensor_data.pm2_5_aqi = Some(aqi_a);
+    sensor_data.p25aqic = Some(AqiCategory::from_aqi(aqi_a).rgb().to_string());
+
+    let aqi_b = aqi::pm2_5_aqi(channel_b.pm2_5);
+    let cf_1_b = channel_b.cf_1();
+    let [p_0_3, p_0_5, p_1_0, p_2_5, p_5_0, p_10_0] = channel_b.counts();
+    sensor_data.pm1_0_atm_b = Some(channel_b.pm1_0);
+    sensor_data.pm2_5_atm_b = Some(channel_b.pm2_5);
+    sensor_data.pm10_0_atm_b = Some(channel_b.pm10_0);
+    sensor_data.pm1_0_cf_1_b = Some(cf_1_b.pm1_0);
+    sensor_data.pm2_5_cf_1_b = Some(cf_1_b.pm2_5);
+    sensor_data.pm10_0_cf_1_b = Some(cf_1_b.pm10_0);
+    sensor_data.p_0_3_um_b = Some(p_0_3);
+    sensor_data.p_0_5_um_b = Some(p_0_5);
+    sensor_data.p_1_0_um_b = Some(p_1_0);
+    sensor_data.p_2_5_um_b = Some(p_2_5);
+    sensor_data.p_5_0_um_b = Some(p_5_0);
+    sensor_data.p_10_0_um_b = Some(p_10_0);
+    sensor_data.pm2_5_aqi_b = Some(aqi_b);
+    sensor_data.p25aqic_b = Some(AqiCategory::from_aqi(aqi_b).rgb().to_string());
+}
+
+/// Generates realistic synthetic readings so the GUI can be developed without a sensor nearby.
+///
+/// The simulated day has a temperature and humidity cycle, traffic peaks in the morning and evening,
+/// occasional wildfire smoke and dust, a slowly drifting channel B, Wi-Fi dropouts and upload errors.
+/// Everything random comes from a seeded generator, so a seed always produces the same readings.
+pub struct SimulatorSource {
+    settings: SimulatorSettings,
+    template: LocalSensorData,
+    rng: StdRng,
+    start: DateTime<Utc>,
+    tick: u64,
+    background_noise: f64,
+    events: Vec<Event>,
+    dropout_remaining: u32,
+    pacer: Pacer,
+    health: SourceHealth,
+}
+
+impl SimulatorSource {
+    pub fn new(settings: SimulatorSettings) -> Self {
+        SimulatorSource {
+            template: serde_json::from_str(TEMPLATE).expect("the simulator template is valid sensor data"),
+            rng: StdRng::seed_from_u64(settings.seed),
+            start: settings.start_time,
+            tick: 0,
+            background_noise: 0.0,
+            events: Vec::new(),
+            dropout_remaining: 0,
+            pacer: Pacer::new(Duration::from_secs(settings.interval_seconds)),
+            health: SourceHealth::default(),
+            settings,
+        }
+    }
+
+    /// How much simulated time passes between two readings.
+    fn step(&self) -> chrono::Duration {
+        chrono::Duration::milliseconds((self.settings.interval_seconds as f64 * self.settings.time_scale * 1000.0) as i64)
+    }
+
+    fn simulated_time(&self) -> DateTime<Utc> {
+        self.start + self.step() * self.tick as i32
+    }
+
+    /// Normally distributed noise using the Box-Muller transform.
+    fn noise(&mut self, standard_deviation: f64) -> f64 {
+        let u1: f64 = self.rng.random_range(f64::EPSILON..1.0);
+        let u2: f64 = self.rng.random();
+        (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos() * standard_deviation
+    }
+
+    /// Rolls for a new event, scaling the hourly chance to the length of one step.
+    fn maybe_start_event(&mut self, kind: EventKind, per_hour: f64, now: DateTime<Utc>) {
+        let hours_per_step = self.step().num_seconds() as f64 / 3600.0;
+        if !self.rng.random_bool((per_hour * hours_per_step).clamp(0.0, 1.0)) {
+            return;
+        }
+
+        let (minutes, peak) = match kind {
+            EventKind::Smoke => (self.rng.random_range(120..480), self.rng.random_range(60.0..250.0)),
+            EventKind::Dust => (self.rng.random_range(10..40), self.rng.random_range(40.0..150.0)),
+        };
+
+        self.events.push(Event {
+            kind,
+            start: now,
+            duration: chrono::Duration::minutes(minutes),
+            peak,
+        });
+    }
+
+    /// Produces the next reading right away, without waiting for the interval.
+    pub fn generate(&mut self) -> Result<Reading, SourceError> {
+        self.tick += 1;
+        let now = self.simulated_time();
+        let hour = (now.num_seconds_from_midnight() as f64 / 3600.0 + self.settings.utc_offset_hours).rem_euclid(24.0);
+
+        if self.dropout_remaining > 0 {
+            self.dropout_remaining -= 1;
+            return Err(SourceError::Unavailable("simulated Wi-Fi dropout".to_string()));
+        }
+        if self.rng.random_bool(self.settings.dropout_chance.clamp(0.0, 1.0)) {
+            self.dropout_remaining = self.rng.random_range(0..5);
+            return Err(SourceError::Unavailable("simulated Wi-Fi dropout".to_string()));
+        }
+
+        // Warmest at 15:00, coolest at 03:00, with the relative humidity doing the opposite.
+        let daily = (TAU * (hour - 15.0) / 24.0).cos();
+        let temperature = 68.0 + 12.0 * daily + self.noise(0.5);
+        let humidity = (55.0 - 20.0 * daily + self.noise(1.0)).clamp(5.0, 100.0);
+
+        // Traffic peaks around 08:00 and 18:00 on top of a slowly wandering background.
+        let traffic = 4.0 * (-(hour - 8.0).powi(2) / 2.0).exp() + 5.0 * (-(hour - 18.0).powi(2) / 3.0).exp();
+        self.background_noise = (0.9 * self.background_noise + self.noise(0.6)).clamp(-4.0, 4.0);
+        let background = (5.0 + traffic + self.background_noise).max(0.5);
+
+        self.maybe_start_event(EventKind::Smoke, self.settings.smoke_events_per_hour, now);
+        self.maybe_start_event(EventKind::Dust, self.settings.dust_spikes_per_hour, now);
+        self.events.retain(|event| !event.is_over(now));
+
+        let mut particles = Particles {
+            pm1_0: background * 0.65,
+            pm2_5: background,
+            pm10_0: background * 1.3,
+        };
+        for event in &self.events {
+            let concentration = event.concentration(now);
+            match event.kind {
+                EventKind::Smoke => {
+                    particles.pm1_0 += concentration * 0.8;
+                    particles.pm2_5 += concentration;
+                    particles.pm10_0 += concentration * 1.1;
+                }
+                EventKind::Dust => {
+                    particles.pm1_0 += concentration * 0.05;
+                    particles.pm2_5 += concentration * 0.2;
+                    particles.pm10_0 += concentration;
+                }
+            }
+        }
+
+        let days = (now - self.start).num_seconds() as f64 / 86_400.0;
+        let channel_a = particles.scaled(1.0 + self.noise(0.02));
+        let channel_b = particles.scaled(1.0 + self.settings.channel_b_drift_per_day * days + self.noise(0.02));
+
+        let mut sensor_data = self.template.clone();
+        sensor_data.date_time = now;
+        sensor_data.id += self.tick;
+        sensor_data.uptime += (self.step().num_seconds().max(0) as u64) * self.tick;
+        sensor_data.rssi = (-60.0 + self.noise(4.0)).round() as i64;
+        sensor_data.current_temp_f = Some(temperature.round() as u64);
+        sensor_data.current_humidity = Some(humidity.round() as u64);
+        sensor_data.current_dewpoint_f = Some(dewpoint_f(temperature, humidity).round() as u64);
+        sensor_data.pressure = Some(1012.0 + self.noise(1.5));
+        set_particles(&mut sensor_data, channel_a, channel_b);
+
+        let uploads = sensor_data.http_sends + self.tick;
+        sensor_data.http_sends = uploads;
+        sensor_data.http_success = uploads - self.tick / 50;
+        if self.rng.random_bool(self.settings.status_error_chance.clamp(0.0, 1.0)) {
+            sensor_data.status_paa = Status::Error;
+            sensor_data.http_success -= 1;
+        }
+
+        Ok(Reading {
+            kind: ReadingKind::Averaged,
+            sensor_data,
+        })
+    }
+}
+
+impl DataSource for SimulatorSource {
+    fn next_reading(&mut self) -> ReadingFuture<'_> {
+        Box::pin(async move {
+            self.pacer.wait().await;
+            let result = self.generate();
+            self.health.record(&result);
+            result
+        })
+    }
+
+    fn metadata(&self) -> SourceMetadata {
+        SourceMetadata {
+            kind: DataSourceKind::Simulator,
+            location: format!("seed {}, {}x speed", self.settings.seed, self.settings.time_scale),
+        }
+    }
+
+    fn health(&self) -> &SourceHealth {
+        &self.health
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn readings(settings: SimulatorSettings, count: usize) -> Vec<String> {
+        let mut simulator = SimulatorSource::new(settings);
+        (0..count)
+            .map(|_| match simulator.generate() {
+                Ok(reading) => serde_json::to_string(&reading.sensor_data).unwrap(),
+                Err(error) => error.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_readings() {
+        let settings = SimulatorSettings {
+            seed: 7,
+            time_scale: 60.0,
+            dropout_chance: 0.05,
+            status_error_chance: 0.05,
+            smoke_events_per_hour: 0.5,
+            ..SimulatorSettings::default()
+        };
+
+        let first = readings(settings.clone(), 500);
+        assert_eq!(first, readings(settings.clone(), 500));
+        assert_ne!(first, readings(SimulatorSettings { seed: 8, ..settings }, 500));
+    }
+
+    #[test]
+    fn the_day_follows_the_utc_offset() {
+        let temperature = |utc_offset_hours: f64| {
+            let mut simulator = SimulatorSource::new(SimulatorSettings {
+                dropout_chance: 0.0,
+                utc_offset_hours,
+                ..SimulatorSettings::default()
+            });
+            simulator.generate().unwrap().sensor_data.current_temp_f.unwrap()
+        };
+
+        // The default start is midnight UTC, the warmest time of day at UTC-9 and the coolest at UTC+3.
+        assert!(temperature(-9.0) > temperature(3.0) + 20);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::i18n::Localizer;
+use crate::sensor_data::LocalSensorData;
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::time::{Duration, Instant};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+const MDNS_BROWSE_TIME: Duration = Duration::from_secs(3);
+const MDNS_SERVICE_TYPE: &str = "_http._tcp.local.";
+
+/// A host on the local network that answered `/json` with sensor data.
+#[derive(Debug, Clone)]
+pub struct DiscoveredSensor {
+    pub url: String,
+    pub sensor_data: LocalSensorData,
+}
+
+impl DiscoveredSensor {
+    pub fn label(&self, localizer: &Localizer) -> String {
+        localizer.format("discovery-sensor", [
+            ("id", self.sensor_data.sensor_id.as_str().into()),
+            ("geo", self.sensor_data.geo.as_str().into()),
+            ("place", self.sensor_data.place.as_str().into()),
+            ("url", self.url.as_str().into()),
+        ])
+    }
+}
+
+/// The result of a full discovery run.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryResult {
+    pub sensors: Vec<DiscoveredSensor>,
+}
+
+impl DiscoveryResult {
+    pub fn find(&self, sensor_id: &str) -> Option<&DiscoveredSensor> {
+        self.sensors.iter().find(|sensor| sensor.sensor_data.sensor_id == sensor_id)
+    }
+}
+
+pub fn sensor_url(address: IpAddr) -> String {
+    match address {
+        IpAddr::V4(address) => format!("http://{}/json", address),
+        IpAddr::V6(address) => format!("http://[{}]/json", address),
+    }
+}
+
+/// Fetches `url` and returns the sensor data if the body parses as `LocalSensorData`.
+pub async fn probe(url: String) -> Option<DiscoveredSensor> {
+    let client = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build().ok()?;
+    let json_text = client.get(url.as_str()).send().await.ok()?.text().await.ok()?;
+    let sensor_data: LocalSensorData = serde_json::from_str(json_text.as_str()).ok()?;
+
+    Some(DiscoveredSensor {
+        url,
+        sensor_data,
+    })
+}
+
+/// The IPv4 address of the interface used for outbound traffic.
+/// Connecting a UDP socket only selects a route, no packets are sent.
+fn local_ipv4() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(address) if !address.is_loopback() => Some(address),
+        _ => None,
+    }
+}
+
+/// Every other host address in the /24 of `local`.
+fn subnet_hosts(local: Ipv4Addr) -> Vec<IpAddr> {
+    let [a, b, c, own] = local.octets();
+    (1..=254)
+        .filter(|host| *host != own)
+        .map(|host| IpAddr::V4(Ipv4Addr::new(a, b, c, host)))
+        .collect()
+}
+
+/// Browses for HTTP services advertised over mDNS and returns their addresses.
+fn mdns_hosts() -> Vec<IpAddr> {
+    let Ok(daemon) = mdns_sd::ServiceDaemon::new() else {
+        return Vec::new();
+    };
+    let Ok(receiver) = daemon.browse(MDNS_SERVICE_TYPE) else {
+        return Vec::new();
+    };
+
+    let mut hosts = Vec::new();
+    let deadline = Instant::now() + MDNS_BROWSE_TIME;
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match receiver.recv_timeout(remaining) {
+            // Link-local IPv6 addresses only work together with the interface, which a URL cannot name.
+            Ok(mdns_sd::ServiceEvent::ServiceResolved(info)) => hosts.extend(
+                info.get_addresses()
+                    .iter()
+                    .copied()
+                    .filter(|address| !matches!(address, IpAddr::V6(address) if address.is_unicast_link_local())),
+            ),
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+
+    let _ = daemon.shutdown();
+    hosts
+}
+
+/// Scans the local /24 and any mDNS-advertised HTTP hosts for PurpleAir sensors.
+pub async fn discover() -> DiscoveryResult {
+    let mdns_hosts = tokio::task::spawn_blocking(mdns_hosts).await.unwrap_or_default();
+
+    let mut addresses: HashSet<IpAddr> = HashSet::new();
+    addresses.extend(mdns_hosts);
+    addresses.extend(local_ipv4().map(subnet_hosts).unwrap_or_default());
+
+    let mut probes = tokio::task::JoinSet::new();
+    for address in addresses {
+        probes.spawn(probe(sensor_url(address)));
+    }
+
+    let mut sensors: Vec<DiscoveredSensor> = Vec::new();
+    while let Some(result) = probes.join_next().await {
+        if let Ok(Some(sensor)) = result && !sensors.iter().any(|known| known.sensor_data.sensor_id == sensor.sensor_data.sensor_id) {
+            sensors.push(sensor);
+        }
+    }
+    sensors.sort_by(|a, b| a.sensor_data.sensor_id.cmp(&b.sensor_data.sensor_id));
+
+    DiscoveryResult {
+        sensors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv6Addr;
+
+    #[test]
+    fn sensor_urls() {
+        assert_eq!(sensor_url(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 23))), "http://192.168.1.23/json");
+        assert_eq!(sensor_url(IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0x17))), "http://[fd00::17]/json");
+        assert!(reqwest::Url::parse(sensor_url(IpAddr::V6(Ipv6Addr::LOCALHOST)).as_str()).is_ok());
+    }
+
+    #[test]
+    fn the_subnet_is_every_other_host_of_the_slash_24() {
+        let hosts = subnet_hosts(Ipv4Addr::new(10, 0, 7, 42));
+
+        assert_eq!(hosts.len(), 253);
+        assert_eq!(hosts.first(), Some(&IpAddr::V4(Ipv4Addr::new(10, 0, 7, 1))));
+        assert_eq!(hosts.last(), Some(&IpAddr::V4(Ipv4Addr::new(10, 0, 7, 254))));
+        assert!(!hosts.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 7, 42))));
+        assert!(!hosts.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 7, 0))));
+        assert!(!hosts.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 7, 255))));
+    }
+}
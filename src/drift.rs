@@ -0,0 +1,357 @@
+use crate::archive::{self, ArchivedReading};
+use chrono::{NaiveDate, TimeDelta};
+use std::path::PathBuf;
+
+/// The analysis looks back this many weeks of the archive.
+pub const DRIFT_WEEKS: i64 = 8;
+/// Only readings where both channels see at least this much PM2.5 are compared, the ratio of two small numbers is
+/// mostly noise.
+const MIN_PM2_5: f64 = 5.0;
+/// A day needs this many compared readings for its ratio to count.
+const MIN_DAY_READINGS: usize = 30;
+/// The trend needs this many days with a ratio.
+pub const MIN_TREND_DAYS: usize = 14;
+/// A channel drifts when it reads this much less than the other one compared with the start of the trend, and the
+/// daily ratios follow the trend at least this well (R²).
+const DRIFT_THRESHOLD: f64 = 0.15;
+const MIN_TREND_FIT: f64 = 0.5;
+/// Dead and saturated channels are looked for in the readings of this many recent days.
+const RECENT_DAYS: i64 = 3;
+/// A channel is dead when it counts no particles this share of the time the other one counts at least `MIN_COUNTS`.
+const DEAD_SHARE: f64 = 0.5;
+const MIN_COUNTS: f64 = 50.0;
+/// The PMS5003 reports its counts as 16-bit numbers, so they stop at this value.
+const COUNT_SATURATION: f64 = 65535.0;
+/// A channel is saturated when its counts are at the limit this share of the time the other one's are not.
+const SATURATED_SHARE: f64 = 0.1;
+
+/// One of the sensor's two particle counters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    A,
+    B,
+}
+
+/// Something wrong with one of the channels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DriftFinding {
+    /// The channel reads `change` (0.2 for 20 %) less relative to the other one than at the start of the trend,
+    /// the way an ageing laser does.
+    Drift { channel: Channel, change: f64 },
+    /// The channel counts no particles while the other one does.
+    NoCounts { channel: Channel },
+    /// The channel's counts are stuck at the limit while the other one's are not.
+    Saturated { channel: Channel },
+}
+
+/// What to do about the findings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Maintenance {
+    /// Dust, insects or webs in the air path, blow out the inlet and check again in a few days.
+    Clean,
+    /// The particle counter is worn out and needs replacing.
+    Replace,
+}
+
+/// How the channels of a sensor have been doing over the last `DRIFT_WEEKS` of its archive.
+#[derive(Debug, Clone)]
+pub struct DriftAnalysis {
+    pub sensor_id: String,
+    /// The last day analysed.
+    pub date: NaiveDate,
+    /// The mean channel B/A PM2.5 ratio of each day with enough readings, oldest first.
+    pub ratios: Vec<(NaiveDate, f64)>,
+    /// How much the B/A ratio changes per week, once there are `MIN_TREND_DAYS` of ratios.
+    pub weekly_change: Option<f64>,
+    pub findings: Vec<DriftFinding>,
+}
+
+impl DriftAnalysis {
+    /// A dead counter has to be replaced. Drift and saturation are often dirt in the air path, worth a cleaning first.
+    pub fn maintenance(&self) -> Option<Maintenance> {
+        if self.findings.iter().any(|finding| matches!(finding, DriftFinding::NoCounts { .. })) {
+            Some(Maintenance::Replace)
+        } else if !self.findings.is_empty() {
+            Some(Maintenance::Clean)
+        } else {
+            None
+        }
+    }
+}
+
+/// The mean B/A ratio of a day's readings.
+fn daily_ratio(readings: &[ArchivedReading]) -> Option<f64> {
+    let ratios: Vec<f64> = readings
+        .iter()
+        .filter_map(|reading| match (reading.pm2_5, reading.pm2_5_b) {
+            (Some(a), Some(b)) if a >= MIN_PM2_5 && b >= MIN_PM2_5 => Some(b / a),
+            _ => None,
+        })
+        .collect();
+    (ratios.len() >= MIN_DAY_READINGS).then(|| ratios.iter().sum::<f64>() / ratios.len() as f64)
+}
+
+/// The slope per day and the R² of the least-squares line through the daily ratios.
+fn trend(ratios: &[(NaiveDate, f64)]) -> Option<(f64, f64)> {
+    let first = ratios.first()?.0;
+    let points: Vec<(f64, f64)> = ratios.iter().map(|(date, ratio)| ((*date - first).num_days() as f64, *ratio)).collect();
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let syy: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let fit = if syy == 0.0 { 1.0 } else { sxy * sxy / (sxx * syy) };
+    Some((sxy / sxx, fit))
+}
+
+/// The share of `readings` in which `channel` is off while the other channel is fine, among those the other one is fine in.
+fn share(readings: &[ArchivedReading], channel: Channel, off: impl Fn(f64) -> bool, fine: impl Fn(f64) -> bool) -> f64 {
+    let compared: Vec<f64> = readings
+        .iter()
+        .filter_map(|reading| match channel {
+            Channel::A => reading.p_0_3_um.zip(reading.p_0_3_um_b),
+            Channel::B => reading.p_0_3_um_b.zip(reading.p_0_3_um),
+        })
+        .filter(|(_, other)| fine(*other))
+        .map(|(counts, _)| counts)
+        .collect();
+    if compared.is_empty() {
+        return 0.0;
+    }
+    compared.iter().filter(|counts| off(**counts)).count() as f64 / compared.len() as f64
+}
+
+/// The trend of the daily B/A `ratios` as the slope per day and its R², and the findings from it and the `recent` readings.
+fn diagnose(ratios: &[(NaiveDate, f64)], recent: &[ArchivedReading]) -> (Option<(f64, f64)>, Vec<DriftFinding>) {
+    let mut findings = Vec::new();
+    for channel in [Channel::A, Channel::B] {
+        if share(recent, channel, |counts| counts == 0.0, |other| other >= MIN_COUNTS) >= DEAD_SHARE {
+            findings.push(DriftFinding::NoCounts { channel });
+        } else if share(recent, channel, |counts| counts >= COUNT_SATURATION, |other| other < COUNT_SATURATION) >= SATURATED_SHARE {
+            findings.push(DriftFinding::Saturated { channel });
+        }
+    }
+
+    let trend = (ratios.len() >= MIN_TREND_DAYS).then(|| trend(ratios)).flatten();
+    if let Some((slope, fit)) = trend
+        && let (Some((first_date, _)), Some((last_date, _))) = (ratios.first(), ratios.last())
+        && fit >= MIN_TREND_FIT
+        && findings.is_empty()
+    {
+        // The trend line at the first and the last day, so single odd days do not decide.
+        let mean_x = ratios.iter().map(|(date, _)| (*date - *first_date).num_days() as f64).sum::<f64>() / ratios.len() as f64;
+        let mean_y = ratios.iter().map(|(_, ratio)| ratio).sum::<f64>() / ratios.len() as f64;
+        let start = mean_y - slope * mean_x;
+        let end = start + slope * (*last_date - *first_date).num_days() as f64;
+        if start > 0.0 && end > 0.0 {
+            // A falling B/A ratio means B reads less, a rising one that A does.
+            let (channel, change) = if end < start { (Channel::B, 1.0 - end / start) } else { (Channel::A, 1.0 - start / end) };
+            if change >= DRIFT_THRESHOLD {
+                findings.push(DriftFinding::Drift { channel, change });
+            }
+        }
+    }
+
+    (trend, findings)
+}
+
+/// Looks for channels of `sensor_id` that drift apart, count nothing or saturate, in the archive up to `today`.
+/// The days are read one at a time, weeks of readings would not fit in memory comfortably.
+pub async fn analyze(directory: PathBuf, sensor_id: String, today: NaiveDate) -> DriftAnalysis {
+    let first = today - TimeDelta::weeks(DRIFT_WEEKS);
+    let recent_first = today - TimeDelta::days(RECENT_DAYS - 1);
+    let mut ratios = Vec::new();
+    let mut recent = Vec::new();
+    for date in archive::days(directory.clone(), sensor_id.clone()).await.into_iter().filter(|date| *date >= first && *date <= today) {
+        let readings = archive::load(directory.clone(), sensor_id.clone(), date, date).await;
+        if let Some(ratio) = daily_ratio(&readings) {
+            ratios.push((date, ratio));
+        }
+        if date >= recent_first {
+            recent.extend(readings);
+        }
+    }
+
+    let (trend, findings) = diagnose(&ratios, &recent);
+    DriftAnalysis {
+        sensor_id,
+        date: today,
+        ratios,
+        weekly_change: trend.map(|(slope, _)| slope * 7.0),
+        findings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn day(days: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap() + TimeDelta::days(days)
+    }
+
+    fn reading(pm2_5: f64, pm2_5_b: f64, counts: f64, counts_b: f64) -> ArchivedReading {
+        ArchivedReading {
+            received_at: Utc.with_ymd_and_hms(2025, 6, 29, 12, 0, 0).unwrap(),
+            aqi: None,
+            pm2_5: Some(pm2_5),
+            pm2_5_cf_1: None,
+            pm2_5_b: Some(pm2_5_b),
+            p_0_3_um: Some(counts),
+            p_0_3_um_b: Some(counts_b),
+            temperature_f: None,
+            humidity: None,
+            uptime: 0,
+            http_success: 0,
+            http_sends: 0,
+        }
+    }
+
+    /// Four weeks of daily B/A ratios.
+    fn ratios(ratio: impl Fn(i64) -> f64) -> Vec<(NaiveDate, f64)> {
+        (0..28).map(|days| (day(days), ratio(days))).collect()
+    }
+
+    fn healthy() -> Vec<ArchivedReading> {
+        vec![reading(10.0, 10.0, 1000.0, 1000.0); 100]
+    }
+
+    #[test]
+    fn a_day_needs_enough_readings_with_both_channels_above_the_noise() {
+        let mut readings = vec![reading(10.0, 8.0, 1000.0, 800.0); MIN_DAY_READINGS - 1];
+        assert_eq!(daily_ratio(&readings), None);
+
+        // Clean air says nothing about the ratio.
+        readings.push(reading(2.0, 1.0, 100.0, 50.0));
+        assert_eq!(daily_ratio(&readings), None);
+
+        readings.push(reading(20.0, 18.0, 2000.0, 1800.0));
+        let expected = (0.8 * (MIN_DAY_READINGS - 1) as f64 + 0.9) / MIN_DAY_READINGS as f64;
+        assert!((daily_ratio(&readings).unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_trend_is_a_least_squares_line() {
+        let (slope, fit) = trend(&ratios(|days| 1.0 - 0.01 * days as f64)).unwrap();
+        assert!((slope + 0.01).abs() < 1e-9);
+        assert!((fit - 1.0).abs() < 1e-9);
+
+        let (slope, _) = trend(&ratios(|_| 0.9)).unwrap();
+        assert!(slope.abs() < 1e-12);
+        assert_eq!(trend(&[(day(0), 0.9)]), None);
+        assert_eq!(trend(&[]), None);
+
+        let (_, fit) = trend(&ratios(|days| if days % 2 == 0 { 1.0 } else { 0.8 })).unwrap();
+        assert!(fit < 0.01);
+    }
+
+    #[test]
+    fn shares_only_count_readings_where_the_other_channel_is_fine() {
+        let readings = [reading(0.0, 0.0, 0.0, 500.0), reading(0.0, 0.0, 0.0, 10.0), reading(0.0, 0.0, 700.0, 600.0), reading(0.0, 0.0, 0.0, 0.0)];
+        let dead = |counts: f64| counts == 0.0;
+        let counting = |other: f64| other >= MIN_COUNTS;
+
+        assert_eq!(share(&readings, Channel::A, dead, counting), 0.5);
+        assert_eq!(share(&readings, Channel::B, dead, counting), 0.0);
+        assert_eq!(share(&[], Channel::A, dead, counting), 0.0);
+    }
+
+    #[test]
+    fn a_falling_ratio_is_channel_b_drifting() {
+        let (trend, findings) = diagnose(&ratios(|days| 1.0 - 0.2 * days as f64 / 27.0), &healthy());
+
+        assert!((trend.unwrap().0 * 7.0 + 0.2 * 7.0 / 27.0).abs() < 1e-9);
+        let [DriftFinding::Drift { channel: Channel::B, change }] = findings[..] else {
+            panic!("expected channel B to drift, got {:?}", findings);
+        };
+        assert!((change - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_rising_ratio_is_channel_a_drifting() {
+        let (_, findings) = diagnose(&ratios(|days| 0.8 + 0.2 * days as f64 / 27.0), &healthy());
+
+        assert!(matches!(findings[..], [DriftFinding::Drift { channel: Channel::A, .. }]));
+    }
+
+    #[test]
+    fn a_flat_ratio_is_fine() {
+        let analysis = DriftAnalysis {
+            sensor_id: String::new(),
+            date: day(27),
+            ratios: ratios(|_| 0.93),
+            weekly_change: None,
+            findings: diagnose(&ratios(|_| 0.93), &healthy()).1,
+        };
+
+        assert!(analysis.findings.is_empty());
+        assert_eq!(analysis.maintenance(), None);
+    }
+
+    #[test]
+    fn drift_below_the_threshold_is_left_alone() {
+        let below = DRIFT_THRESHOLD - 0.01;
+        let above = DRIFT_THRESHOLD + 0.01;
+
+        assert!(diagnose(&ratios(|days| 1.0 - below * days as f64 / 27.0), &healthy()).1.is_empty());
+        assert_eq!(diagnose(&ratios(|days| 1.0 - above * days as f64 / 27.0), &healthy()).1.len(), 1);
+    }
+
+    #[test]
+    fn a_trend_that_fits_badly_is_left_alone() {
+        // The same 30 % fall, with more or less day-to-day scatter around it.
+        let scattered = |scatter: f64| ratios(move |days| 1.0 - 0.3 * days as f64 / 27.0 + if days % 2 == 0 { scatter } else { -scatter });
+        let loose = scattered(0.12);
+        let tight = scattered(0.05);
+        assert!(trend(&loose).unwrap().1 < MIN_TREND_FIT);
+        assert!(trend(&tight).unwrap().1 >= MIN_TREND_FIT);
+
+        assert!(diagnose(&loose, &healthy()).1.is_empty());
+        assert_eq!(diagnose(&tight, &healthy()).1.len(), 1);
+    }
+
+    #[test]
+    fn too_few_days_have_no_trend() {
+        let mut ratios = ratios(|days| 1.0 - 0.02 * days as f64);
+        ratios.truncate(MIN_TREND_DAYS - 1);
+
+        assert_eq!(diagnose(&ratios, &healthy()), (None, Vec::new()));
+    }
+
+    #[test]
+    fn a_dead_channel_needs_replacing() {
+        let mut recent = healthy();
+        for reading in recent.iter_mut().take(60) {
+            reading.p_0_3_um_b = Some(0.0);
+        }
+        let ratios = ratios(|days| 1.0 - 0.02 * days as f64);
+
+        let (_, findings) = diagnose(&ratios, &recent);
+        // The drift of a dead channel is not worth mentioning on its own.
+        assert_eq!(findings, [DriftFinding::NoCounts { channel: Channel::B }]);
+        let analysis = DriftAnalysis { sensor_id: String::new(), date: day(27), ratios, weekly_change: None, findings };
+        assert_eq!(analysis.maintenance(), Some(Maintenance::Replace));
+    }
+
+    #[test]
+    fn a_saturated_channel_needs_cleaning() {
+        let mut recent = healthy();
+        for reading in recent.iter_mut().take(10) {
+            reading.p_0_3_um = Some(COUNT_SATURATION);
+        }
+
+        let (_, findings) = diagnose(&ratios(|_| 1.0), &recent);
+        assert_eq!(findings, [DriftFinding::Saturated { channel: Channel::A }]);
+        let analysis = DriftAnalysis { sensor_id: String::new(), date: day(27), ratios: Vec::new(), weekly_change: None, findings };
+        assert_eq!(analysis.maintenance(), Some(Maintenance::Clean));
+
+        // Both channels at the limit is just very dirty air.
+        let smoky = vec![reading(500.0, 500.0, COUNT_SATURATION, COUNT_SATURATION); 10];
+        assert!(diagnose(&ratios(|_| 1.0), &smoky).1.is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+use crate::archive;
+use crate::sensor_data::LocalSensorData;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+
+/// How many finished events are kept in memory for the charts.
+const RECENT_EVENTS: usize = 50;
+/// The baseline follows PM2.5 outside of events with this time constant, so slow changes like the evening
+/// inversion raise it instead of counting as an event.
+const BASELINE_TIME_CONSTANT: TimeDelta = TimeDelta::hours(1);
+/// No events are detected until the baseline has settled for this long.
+const WARM_UP: TimeDelta = TimeDelta::minutes(10);
+/// A spike starts when PM2.5 is at least this many times the baseline, and at least `MIN_RISE` above it.
+const START_FACTOR: f64 = 2.0;
+const MIN_RISE: f64 = 10.0;
+/// A spike only counts as an event once it lasted this long, single readings are left alone.
+const MIN_DURATION: TimeDelta = TimeDelta::minutes(5);
+/// A raised level that lasts longer than this is the new normal, e.g. after the sensor was moved or the furnace filter
+/// was taken out, so the event ends and the baseline restarts from it.
+const MAX_DURATION: TimeDelta = TimeDelta::hours(24);
+/// An event ends when PM2.5 is back within this share of the peak's rise above the baseline, or within `END_MARGIN`.
+const END_SHARE: f64 = 0.25;
+const END_MARGIN: f64 = 5.0;
+/// Dust and pollen are mostly coarse: PM2.5 is a small part of PM10, and many counted particles are 2.5 µm or larger.
+const DUST_MAX_FINE_RATIO: f64 = 0.6;
+const DUST_MIN_COARSE_SHARE: f64 = 0.03;
+/// Cooking rises within minutes and is gone within hours, while smoke from fires builds slowly and lingers.
+const COOKING_MAX_RISE: TimeDelta = TimeDelta::minutes(20);
+const COOKING_MAX_DURATION: TimeDelta = TimeDelta::hours(2);
+/// Finished events are appended to this file in the sensor's archive directory.
+const EVENTS_FILE: &str = "events.jsonl";
+
+/// What most likely caused a PM spike.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// Fine particles that built up over a long time, e.g. wildfire smoke.
+    Smoke,
+    /// Coarse particles, e.g. dust, pollen or construction.
+    Dust,
+    /// Fine particles that rose sharply and cleared quickly.
+    Cooking,
+}
+
+/// A PM spike with its likely cause. `end` is `None` while it lasts.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParticleEvent {
+    pub kind: EventKind,
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+    pub peak_at: DateTime<Utc>,
+    /// Channel A PM2.5 in µg/m³ at the peak.
+    pub peak_pm2_5: f64,
+    /// The mean PM2.5/PM10 ratio over the event, 1 when all particles are fine.
+    pub fine_ratio: Option<f64>,
+}
+
+impl ParticleEvent {
+    /// Whether the event was going on at `time`.
+    pub fn covers(&self, time: DateTime<Utc>) -> bool {
+        time >= self.start && self.end.is_none_or(|end| time <= end)
+    }
+}
+
+/// A spike being followed, which becomes an event once it lasted `MIN_DURATION`.
+#[derive(Debug, Clone)]
+struct Spike {
+    start: DateTime<Utc>,
+    peak_at: DateTime<Utc>,
+    peak_pm2_5: f64,
+    pm2_5_sum: f64,
+    pm10_sum: f64,
+    fine_counts: f64,
+    coarse_counts: f64,
+}
+
+impl Spike {
+    fn add(&mut self, pm2_5: f64, sensor_data: &LocalSensorData, time: DateTime<Utc>) {
+        if pm2_5 > self.peak_pm2_5 {
+            self.peak_pm2_5 = pm2_5;
+            self.peak_at = time;
+        }
+        if let Some(pm10) = sensor_data.pm10_0_atm {
+            self.pm2_5_sum += pm2_5;
+            self.pm10_sum += pm10;
+        }
+        if let (Some(fine), Some(coarse)) = (sensor_data.p_0_3_um, sensor_data.p_2_5_um) {
+            self.fine_counts += fine;
+            self.coarse_counts += coarse;
+        }
+    }
+
+    fn fine_ratio(&self) -> Option<f64> {
+        (self.pm10_sum > 0.0).then(|| self.pm2_5_sum / self.pm10_sum)
+    }
+
+    fn kind(&self, now: DateTime<Utc>) -> EventKind {
+        let coarse_share = (self.fine_counts > 0.0).then(|| self.coarse_counts / self.fine_counts);
+        if self.fine_ratio().is_some_and(|ratio| ratio < DUST_MAX_FINE_RATIO) || coarse_share.is_some_and(|share| share > DUST_MIN_COARSE_SHARE) {
+            EventKind::Dust
+        } else if self.peak_at - self.start <= COOKING_MAX_RISE && now - self.start <= COOKING_MAX_DURATION {
+            EventKind::Cooking
+        } else {
+            EventKind::Smoke
+        }
+    }
+
+    fn event(&self, now: DateTime<Utc>, end: Option<DateTime<Utc>>) -> ParticleEvent {
+        ParticleEvent {
+            kind: self.kind(now),
+            start: self.start,
+            end,
+            peak_at: self.peak_at,
+            peak_pm2_5: self.peak_pm2_5,
+            fine_ratio: self.fine_ratio(),
+        }
+    }
+}
+
+/// Finds PM spikes in one sensor's readings and classifies them from the particle sizes and how they rise and fall.
+#[derive(Debug, Default)]
+pub struct EventDetector {
+    baseline: Option<f64>,
+    baseline_since: Option<DateTime<Utc>>,
+    previous: Option<DateTime<Utc>>,
+    spike: Option<Spike>,
+    /// The latest time seen, for classifying the ongoing event.
+    now: Option<DateTime<Utc>>,
+    events: VecDeque<ParticleEvent>,
+}
+
+impl EventDetector {
+    /// Follows a reading received at `time`. Returns the event that just ended, if any.
+    pub fn observe(&mut self, sensor_data: &LocalSensorData, time: DateTime<Utc>) -> Option<ParticleEvent> {
+        let pm2_5 = sensor_data.pm2_5_atm?;
+        if self.previous.is_some_and(|previous| time <= previous) {
+            return None;
+        }
+        let elapsed = self.previous.map_or(TimeDelta::zero(), |previous| time - previous);
+        self.previous = Some(time);
+        self.now = Some(time);
+
+        let Some(baseline) = self.baseline else {
+            self.baseline = Some(pm2_5);
+            self.baseline_since = Some(time);
+            return None;
+        };
+
+        if let Some(spike) = &mut self.spike {
+            if time - spike.start >= MAX_DURATION {
+                self.baseline = Some(pm2_5);
+                return self.finish(time);
+            }
+
+            let end_level = baseline + ((spike.peak_pm2_5 - baseline) * END_SHARE).max(END_MARGIN);
+            if pm2_5 > end_level {
+                spike.add(pm2_5, sensor_data, time);
+                return None;
+            }
+            return self.finish(time);
+        }
+
+        let warmed_up = self.baseline_since.is_some_and(|since| time - since >= WARM_UP);
+        if warmed_up && pm2_5 >= (baseline * START_FACTOR).max(baseline + MIN_RISE) {
+            let mut spike = Spike {
+                start: time,
+                peak_at: time,
+                peak_pm2_5: pm2_5,
+                pm2_5_sum: 0.0,
+                pm10_sum: 0.0,
+                fine_counts: 0.0,
+                coarse_counts: 0.0,
+            };
+            spike.add(pm2_5, sensor_data, time);
+            self.spike = Some(spike);
+            return None;
+        }
+
+        // The longer since the previous reading, the more the new one counts.
+        let weight = 1.0 - (-(elapsed.num_seconds() as f64) / BASELINE_TIME_CONSTANT.num_seconds() as f64).exp();
+        self.baseline = Some(baseline + (pm2_5 - baseline) * weight);
+        None
+    }
+
+    /// Ends the spike being followed at `time`, and returns it as an event if it lasted long enough to count.
+    fn finish(&mut self, time: DateTime<Utc>) -> Option<ParticleEvent> {
+        let spike = self.spike.take()?;
+        if time - spike.start < MIN_DURATION {
+            return None;
+        }
+
+        let event = spike.event(time, Some(time));
+        if self.events.len() == RECENT_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event.clone());
+        Some(event)
+    }
+
+    /// The event going on now, once the spike lasted long enough to count.
+    pub fn ongoing(&self) -> Option<ParticleEvent> {
+        let (spike, now) = (self.spike.as_ref()?, self.now?);
+        (now - spike.start >= MIN_DURATION).then(|| spike.event(now, None))
+    }
+
+    /// The recent events, oldest first, with the ongoing one last.
+    pub fn events(&self) -> Vec<ParticleEvent> {
+        self.events.iter().cloned().chain(self.ongoing()).collect()
+    }
+}
+
+/// Appends a finished event to the sensor's event file in the archive.
+pub async fn record(directory: PathBuf, sensor_id: String, event: ParticleEvent) -> std::io::Result<()> {
+    let sensor_directory = archive::sensor_directory(&directory, sensor_id.as_str());
+    tokio::fs::create_dir_all(&sensor_directory).await?;
+
+    let mut line = serde_json::to_string(&event)?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(sensor_directory.join(EVENTS_FILE)).await?;
+    file.write_all(line.as_bytes()).await
+}
+
+/// The recorded events of a sensor that overlap `start` to `end`, oldest first.
+pub async fn load(directory: PathBuf, sensor_id: String, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<ParticleEvent> {
+    let path = archive::sensor_directory(&directory, sensor_id.as_str()).join(EVENTS_FILE);
+    let Ok(contents) = tokio::fs::read_to_string(path).await else {
+        return Vec::new();
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<ParticleEvent>(line).ok())
+        .filter(|event| event.start <= end && event.end.is_none_or(|event_end| event_end >= start))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn minute(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 29, 12, 0, 0).unwrap() + TimeDelta::minutes(minutes)
+    }
+
+    /// A reading with `pm2_5` and `pm10` in µg/m³, and as many 2.5 µm particles as `coarse_share` of the 0.3 µm ones.
+    fn reading(pm2_5: f64, pm10: f64, coarse_share: f64) -> LocalSensorData {
+        let mut sensor_data: LocalSensorData = serde_json::from_str(include_str!("../fixtures/pa-ii.json")).unwrap();
+        sensor_data.pm2_5_atm = Some(pm2_5);
+        sensor_data.pm10_0_atm = Some(pm10);
+        sensor_data.p_0_3_um = Some(1000.0);
+        sensor_data.p_2_5_um = Some(1000.0 * coarse_share);
+        sensor_data
+    }
+
+    fn fine(pm2_5: f64) -> LocalSensorData {
+        reading(pm2_5, pm2_5 * 1.1, 0.005)
+    }
+
+    /// Feeds one reading a minute from `start` up to but not including `end`, and returns the events that ended.
+    fn feed(detector: &mut EventDetector, start: i64, end: i64, sensor_data: impl Fn(i64) -> LocalSensorData) -> Vec<ParticleEvent> {
+        (start..end).filter_map(|minutes| detector.observe(&sensor_data(minutes), minute(minutes))).collect()
+    }
+
+    /// A detector that followed clean air for half an hour.
+    fn settled() -> EventDetector {
+        let mut detector = EventDetector::default();
+        assert!(feed(&mut detector, 0, 30, |_| fine(5.0)).is_empty());
+        detector
+    }
+
+    #[test]
+    fn a_short_sharp_spike_is_cooking() {
+        let mut detector = settled();
+
+        assert!(feed(&mut detector, 30, 35, |_| fine(40.0)).is_empty());
+        assert!(feed(&mut detector, 35, 60, |_| fine(80.0)).is_empty());
+        let ongoing = detector.ongoing().unwrap();
+        assert_eq!((ongoing.kind, ongoing.start, ongoing.end), (EventKind::Cooking, minute(30), None));
+
+        // The event ends once PM2.5 is back within a quarter of the rise above the baseline.
+        assert!(feed(&mut detector, 60, 70, |_| fine(25.0)).is_empty());
+        let events = feed(&mut detector, 70, 71, |_| fine(20.0));
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!((event.kind, event.start, event.end), (EventKind::Cooking, minute(30), Some(minute(70))));
+        assert_eq!((event.peak_pm2_5, event.peak_at), (80.0, minute(35)));
+        assert!(detector.ongoing().is_none());
+        assert_eq!(detector.events().len(), 1);
+    }
+
+    #[test]
+    fn a_long_fine_spike_is_smoke() {
+        let mut detector = settled();
+
+        // It rises for an hour and lingers for three.
+        feed(&mut detector, 30, 90, |minutes| fine(20.0 + minutes as f64));
+        feed(&mut detector, 90, 270, |_| fine(110.0));
+        let events = feed(&mut detector, 270, 271, |_| fine(5.0));
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, EventKind::Smoke);
+        assert_eq!(events[0].peak_at, minute(90));
+    }
+
+    #[test]
+    fn coarse_particles_are_dust() {
+        let mut detector = settled();
+
+        feed(&mut detector, 30, 60, |_| reading(40.0, 120.0, 0.01));
+        let events = feed(&mut detector, 60, 61, |_| fine(5.0));
+        assert_eq!(events[0].kind, EventKind::Dust);
+        assert!((events[0].fine_ratio.unwrap() - 1.0 / 3.0).abs() < 1e-9);
+
+        // Many large particles make it dust even when the mass is mostly fine.
+        feed(&mut detector, 90, 120, |_| reading(40.0, 44.0, 0.05));
+        let events = feed(&mut detector, 120, 121, |_| fine(5.0));
+        assert_eq!(events[0].kind, EventKind::Dust);
+    }
+
+    #[test]
+    fn spikes_shorter_than_the_minimum_are_ignored() {
+        let mut detector = settled();
+
+        assert!(feed(&mut detector, 30, 34, |_| fine(80.0)).is_empty());
+        assert!(detector.ongoing().is_none());
+        assert!(feed(&mut detector, 34, 40, |_| fine(5.0)).is_empty());
+        assert!(detector.events().is_empty());
+    }
+
+    #[test]
+    fn nothing_is_detected_while_warming_up() {
+        let mut detector = EventDetector::default();
+
+        feed(&mut detector, 0, 2, |_| fine(5.0));
+        feed(&mut detector, 2, 9, |_| fine(80.0));
+        feed(&mut detector, 9, 30, |_| fine(5.0));
+        assert!(detector.events().is_empty());
+    }
+
+    #[test]
+    fn a_lasting_step_becomes_the_new_baseline() {
+        let mut detector = settled();
+
+        let events = feed(&mut detector, 30, 30 * 60, |_| fine(40.0));
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].start, events[0].end), (minute(30), Some(minute(30) + MAX_DURATION)));
+        assert!(detector.ongoing().is_none());
+
+        // Spikes are measured against the new level from then on.
+        feed(&mut detector, 30 * 60, 30 * 60 + 20, |_| fine(100.0));
+        assert_eq!(detector.ongoing().unwrap().start, minute(30 * 60));
+    }
+
+    #[test]
+    fn readings_out_of_order_are_skipped() {
+        let mut detector = settled();
+
+        assert!(detector.observe(&fine(80.0), minute(10)).is_none());
+        feed(&mut detector, 30, 31, |_| fine(5.0));
+        assert!(detector.spike.is_none());
+    }
+}
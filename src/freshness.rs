@@ -0,0 +1,110 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct FreshnessSettings {
+    /// The readings are greyed out once the sensor's time has not moved on for this long.
+    pub stale_after_seconds: u64,
+
+    /// Warn when the sensor's clock is further than this from the local clock.
+    pub max_clock_skew_seconds: u64,
+}
+
+impl Default for FreshnessSettings {
+    fn default() -> Self {
+        FreshnessSettings {
+            stale_after_seconds: 300,
+            max_clock_skew_seconds: 120,
+        }
+    }
+}
+
+impl FreshnessSettings {
+    pub fn is_stale(&self, age: TimeDelta) -> bool {
+        age.num_seconds() > self.stale_after_seconds as i64
+    }
+
+    pub fn is_skewed(&self, clock_skew: TimeDelta) -> bool {
+        clock_skew.num_seconds().unsigned_abs() > self.max_clock_skew_seconds
+    }
+}
+
+/// Follows the sensor's `DateTime` to tell how old the shown reading really is.
+///
+/// A reading only counts as new when the sensor's time changed since the previous one, so a sensor that keeps
+/// answering with the same values, e.g. because its firmware or clock stalled, ages like one that stopped answering.
+/// A time that jumps back, e.g. after the sensor rebooted or NTP corrected its clock, counts as new too.
+#[derive(Default, Debug, Clone)]
+pub struct FreshnessTracker {
+    device_time: Option<DateTime<Utc>>,
+    advanced_at: Option<DateTime<Utc>>,
+    clock_skew: Option<TimeDelta>,
+}
+
+impl FreshnessTracker {
+    /// Records a reading stamped `device_time` by the sensor that arrived at `received_at` by the local clock.
+    pub fn observe(&mut self, device_time: DateTime<Utc>, received_at: DateTime<Utc>) {
+        if self.device_time == Some(device_time) {
+            return;
+        }
+
+        self.device_time = Some(device_time);
+        self.advanced_at = Some(received_at);
+        self.clock_skew = Some(device_time - received_at);
+    }
+
+    /// How long ago the sensor's time last changed, by the local clock.
+    pub fn age(&self, now: DateTime<Utc>) -> Option<TimeDelta> {
+        self.advanced_at.map(|advanced_at| (now - advanced_at).max(TimeDelta::zero()))
+    }
+
+    /// How far the sensor's clock was ahead of the local clock when its time last changed. Negative when it is behind.
+    pub fn clock_skew(&self) -> Option<TimeDelta> {
+        self.clock_skew
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 29, 12, 0, 0).unwrap() + TimeDelta::seconds(seconds)
+    }
+
+    #[test]
+    fn a_stalled_clock_ages() {
+        let mut tracker = FreshnessTracker::default();
+        tracker.observe(at(0), at(5));
+        tracker.observe(at(0), at(125));
+
+        assert_eq!(tracker.age(at(245)), Some(TimeDelta::seconds(240)));
+        assert_eq!(tracker.clock_skew(), Some(TimeDelta::seconds(-5)));
+    }
+
+    #[test]
+    fn a_clock_moving_on_is_fresh() {
+        let mut tracker = FreshnessTracker::default();
+        tracker.observe(at(0), at(5));
+        tracker.observe(at(120), at(123));
+
+        assert_eq!(tracker.age(at(133)), Some(TimeDelta::seconds(10)));
+        assert_eq!(tracker.clock_skew(), Some(TimeDelta::seconds(-3)));
+    }
+
+    #[test]
+    fn a_clock_jumping_back_is_a_new_reading() {
+        let mut tracker = FreshnessTracker::default();
+        // The sensor was an hour ahead until NTP corrected its clock.
+        tracker.observe(at(3600), at(0));
+        tracker.observe(at(118), at(120));
+
+        assert_eq!(tracker.age(at(130)), Some(TimeDelta::seconds(10)));
+        assert_eq!(tracker.clock_skew(), Some(TimeDelta::seconds(-2)));
+
+        tracker.observe(at(238), at(240));
+        assert_eq!(tracker.age(at(250)), Some(TimeDelta::seconds(10)));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::sensor_data::LocalSensorData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const HISTORY_CAPACITY: usize = 1000;
+
+/// How the sensor produced a reading.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadingKind {
+    /// The 2-minute averages served by `/json`.
+    #[default]
+    Averaged,
+
+    /// The instantaneous values served by `/json?live=true`.
+    Live,
+}
+
+impl ReadingKind {
+    /// The URL that serves this kind of reading for a sensor whose averaged data is at `url`.
+    pub fn url(&self, url: &str) -> String {
+        match self {
+            ReadingKind::Averaged => url.to_string(),
+            ReadingKind::Live if url.contains('?') => format!("{}&live=true", url),
+            ReadingKind::Live => format!("{}?live=true", url),
+        }
+    }
+}
+
+/// A reading fetched from the sensor.
+#[derive(Debug, Clone)]
+pub struct Reading {
+    pub kind: ReadingKind,
+    pub sensor_data: LocalSensorData,
+}
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub received_at: DateTime<Utc>,
+    pub sensor_data: LocalSensorData,
+}
+
+/// Recent readings, newest last. Live and averaged readings are kept apart so they are never mixed in one series.
+#[derive(Default)]
+pub struct History {
+    averaged: VecDeque<Sample>,
+    live: VecDeque<Sample>,
+}
+
+impl History {
+    pub fn push(&mut self, kind: ReadingKind, sample: Sample) {
+        let samples = match kind {
+            ReadingKind::Averaged => &mut self.averaged,
+            ReadingKind::Live => &mut self.live,
+        };
+
+        if samples.len() == HISTORY_CAPACITY {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    pub fn samples(&self, kind: ReadingKind) -> &VecDeque<Sample> {
+        match kind {
+            ReadingKind::Averaged => &self.averaged,
+            ReadingKind::Live => &self.live,
+        }
+    }
+
+    /// The time of the newest sample and the mean channel A PM2.5 AQI across all samples of this kind.
+    pub fn summary(&self, kind: ReadingKind) -> Option<(DateTime<Utc>, Option<f64>)> {
+        let samples = self.samples(kind);
+        let newest = samples.back()?.received_at;
+
+        let aqis: Vec<f64> = samples.iter().filter_map(|sample| sample.sensor_data.pm2_5_aqi).collect();
+        let mean_aqi = (!aqis.is_empty()).then(|| aqis.iter().sum::<f64>() / aqis.len() as f64);
+
+        Some((newest, mean_aqi))
+    }
+
+    pub fn clear(&mut self) {
+        self.averaged.clear();
+        self.live.clear();
+    }
+}
@@ -0,0 +1,523 @@
+use crate::aqi::{AqiCategory, AqiDisplay, AqiScheme};
+use crate::calendar::DailyStatistic;
+use crate::comfort::{ComfortZone, TemperatureUnit};
+use crate::drift::{Channel, Maintenance};
+use crate::events::EventKind;
+use crate::history::ReadingKind;
+use crate::night::NightMode;
+use crate::report::{ReportFormat, ReportPeriod};
+use crate::settings::DataSourceKind;
+use crate::theme::Theme;
+use crate::webhook::PayloadKind;
+use chrono::{DateTime, NaiveDate, TimeZone};
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentValue;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Spanish,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::Spanish, Language::German];
+
+    /// The name of the language in itself, so it can be found in the list without reading the current one.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// The BCP 47 language tag, e.g. for the `lang` of exported HTML.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Language::English => "en-US",
+            Language::Spanish => "es-ES",
+            Language::German => "de-DE",
+        }
+    }
+
+    fn catalog(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.ftl"),
+            Language::Spanish => include_str!("../locales/es.ftl"),
+            Language::German => include_str!("../locales/de.ftl"),
+        }
+    }
+
+    fn chrono_locale(&self) -> chrono::Locale {
+        match self {
+            Language::English => chrono::Locale::en_US,
+            Language::Spanish => chrono::Locale::es_ES,
+            Language::German => chrono::Locale::de_DE,
+        }
+    }
+
+    /// The decimal and thousands separators.
+    fn separators(&self) -> (char, char) {
+        match self {
+            Language::English => ('.', ','),
+            Language::Spanish | Language::German => (',', '.'),
+        }
+    }
+
+    /// The language of the desktop session from `LC_ALL`, `LC_MESSAGES` or `LANG`, or English.
+    pub fn from_environment() -> Language {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|variable| std::env::var(variable).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+
+        match locale.get(..2) {
+            Some("es") => Language::Spanish,
+            Some("de") => Language::German,
+            _ => Language::English,
+        }
+    }
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Language::from_environment()
+    }
+}
+
+/// A value of one of the settings enums, with the id of its name in the catalogs.
+pub trait Localize {
+    fn message_id(&self) -> &'static str;
+}
+
+impl Localize for AqiCategory {
+    fn message_id(&self) -> &'static str {
+        match self {
+            AqiCategory::Good => "category-good",
+            AqiCategory::Moderate => "category-moderate",
+            AqiCategory::UnhealthyForSensitiveGroups => "category-usg",
+            AqiCategory::Unhealthy => "category-unhealthy",
+            AqiCategory::VeryUnhealthy => "category-very-unhealthy",
+            AqiCategory::Hazardous => "category-hazardous",
+        }
+    }
+}
+
+impl Localize for AqiScheme {
+    fn message_id(&self) -> &'static str {
+        match self {
+            AqiScheme::UsEpa => "scheme-us-epa",
+            AqiScheme::UsEpaCorrected => "scheme-us-epa-corrected",
+            AqiScheme::Lrapa => "scheme-lrapa",
+            AqiScheme::AqAndU => "scheme-aqandu",
+        }
+    }
+}
+
+impl Localize for AqiDisplay {
+    fn message_id(&self) -> &'static str {
+        match self {
+            AqiDisplay::Pm2_5 => "aqi-display-pm2-5",
+            AqiDisplay::Pm10 => "aqi-display-pm10",
+            AqiDisplay::Both => "aqi-display-both",
+        }
+    }
+}
+
+impl Localize for TemperatureUnit {
+    fn message_id(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Fahrenheit => "unit-fahrenheit",
+            TemperatureUnit::Celsius => "unit-celsius",
+        }
+    }
+}
+
+impl Localize for ComfortZone {
+    fn message_id(&self) -> &'static str {
+        match self {
+            ComfortZone::Cold => "comfort-cold",
+            ComfortZone::Dry => "comfort-dry",
+            ComfortZone::Comfortable => "comfort-comfortable",
+            ComfortZone::Humid => "comfort-humid",
+            ComfortZone::Hot => "comfort-hot",
+        }
+    }
+}
+
+impl Localize for ReadingKind {
+    fn message_id(&self) -> &'static str {
+        match self {
+            ReadingKind::Averaged => "reading-averaged",
+            ReadingKind::Live => "reading-live",
+        }
+    }
+}
+
+impl Localize for DataSourceKind {
+    fn message_id(&self) -> &'static str {
+        match self {
+            DataSourceKind::Local => "source-local",
+            DataSourceKind::Cloud => "source-cloud",
+            DataSourceKind::Replay => "source-replay",
+            DataSourceKind::Simulator => "source-simulator",
+        }
+    }
+}
+
+impl Localize for Theme {
+    fn message_id(&self) -> &'static str {
+        match self {
+            Theme::Dark => "theme-dark",
+            Theme::Light => "theme-light",
+            Theme::HighContrast => "theme-high-contrast",
+            Theme::Custom => "theme-custom",
+        }
+    }
+}
+
+impl Localize for NightMode {
+    fn message_id(&self) -> &'static str {
+        match self {
+            NightMode::Off => "night-off",
+            NightMode::Schedule => "night-schedule",
+            NightMode::Sun => "night-sun",
+        }
+    }
+}
+
+impl Localize for ReportPeriod {
+    fn message_id(&self) -> &'static str {
+        match self {
+            ReportPeriod::Day => "report-period-day",
+            ReportPeriod::Week => "report-period-week",
+        }
+    }
+}
+
+impl Localize for ReportFormat {
+    fn message_id(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "report-format-html",
+            ReportFormat::Markdown => "report-format-markdown",
+        }
+    }
+}
+
+impl Localize for DailyStatistic {
+    fn message_id(&self) -> &'static str {
+        match self {
+            DailyStatistic::Mean => "daily-mean",
+            DailyStatistic::Max => "daily-max",
+        }
+    }
+}
+
+impl Localize for EventKind {
+    fn message_id(&self) -> &'static str {
+        match self {
+            EventKind::Smoke => "event-smoke",
+            EventKind::Dust => "event-dust",
+            EventKind::Cooking => "event-cooking",
+        }
+    }
+}
+
+impl Localize for Maintenance {
+    fn message_id(&self) -> &'static str {
+        match self {
+            Maintenance::Clean => "maintenance-clean",
+            Maintenance::Replace => "maintenance-replace",
+        }
+    }
+}
+
+impl Localize for Channel {
+    fn message_id(&self) -> &'static str {
+        match self {
+            Channel::A => "channel-a",
+            Channel::B => "channel-b",
+        }
+    }
+}
+
+impl Localize for PayloadKind {
+    fn message_id(&self) -> &'static str {
+        match self {
+            PayloadKind::Alert => "payload-alert",
+            PayloadKind::Summary => "payload-summary",
+        }
+    }
+}
+
+/// Formats `value` with `decimals` digits after the decimal separator and the thousands grouped.
+fn format_number(value: f64, decimals: usize, language: Language) -> String {
+    let (decimal_separator, group_separator) = language.separators();
+    let text = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = text.split_once('.').unwrap_or((text.as_str(), ""));
+
+    let mut formatted = String::new();
+    if value < 0.0 && text.chars().any(|digit| digit.is_ascii_digit() && digit != '0') {
+        formatted.push('-');
+    }
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index).is_multiple_of(3) {
+            formatted.push(group_separator);
+        }
+        formatted.push(digit);
+    }
+    if !fraction.is_empty() {
+        formatted.push(decimal_separator);
+        formatted.push_str(fraction);
+    }
+    formatted
+}
+
+/// Formats the numbers passed to the catalogs, which Fluent would otherwise always write the English way.
+/// Numbers keep the decimals they were passed with, up to `maximumFractionDigits`.
+fn format_value(value: &FluentValue, language: Language) -> Option<String> {
+    let FluentValue::Number(number) = value else {
+        return None;
+    };
+
+    let shown = number.value.to_string();
+    let decimals = shown.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+    let decimals = number.options.maximum_fraction_digits.map_or(decimals, |maximum| decimals.min(maximum));
+    let decimals = number.options.minimum_fraction_digits.map_or(decimals, |minimum| decimals.max(minimum));
+    Some(format_number(number.value, decimals, language))
+}
+
+fn bundle(language: Language) -> FluentBundle<FluentResource> {
+    let tag: LanguageIdentifier = language.tag().parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![tag]);
+
+    // The isolation marks around arguments show up as boxes in the GUI's fonts.
+    bundle.set_use_isolating(false);
+    bundle.set_formatter(Some(match language {
+        Language::English => |value, _| format_value(value, Language::English),
+        Language::Spanish => |value, _| format_value(value, Language::Spanish),
+        Language::German => |value, _| format_value(value, Language::German),
+    }));
+
+    let resource = FluentResource::try_new(language.catalog().to_string()).unwrap_or_else(|(resource, errors)| {
+        eprintln!("The {} catalog has errors: {:?}", language.name(), errors);
+        resource
+    });
+    if let Err(errors) = bundle.add_resource(resource) {
+        eprintln!("The {} catalog has errors: {:?}", language.name(), errors);
+    }
+    bundle
+}
+
+/// The UI strings, numbers and times in the selected language. Messages missing from its catalog come from the English one.
+pub struct Localizer {
+    language: Language,
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Default for Localizer {
+    fn default() -> Self {
+        Localizer::new(Language::English)
+    }
+}
+
+impl Localizer {
+    pub fn new(language: Language) -> Self {
+        let mut bundles = vec![bundle(language)];
+        if language != Language::English {
+            bundles.push(bundle(Language::English));
+        }
+
+        Localizer { language, bundles }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// The message `id`, or its `attribute` when given. Unknown messages show their id, so they are easy to spot.
+    fn lookup(&self, id: &str, attribute: Option<&str>, args: Option<&FluentArgs>) -> String {
+        for bundle in &self.bundles {
+            let Some(message) = bundle.get_message(id) else {
+                continue;
+            };
+            let pattern = match attribute {
+                Some(attribute) => message.get_attribute(attribute).map(|attribute| attribute.value()).or(message.value()),
+                None => message.value(),
+            };
+            let Some(pattern) = pattern else {
+                continue;
+            };
+
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                eprintln!("Failed to format {}: {:?}", id, errors);
+            }
+            return text.into_owned();
+        }
+
+        id.to_string()
+    }
+
+    pub fn text(&self, id: &str) -> String {
+        self.lookup(id, None, None)
+    }
+
+    /// The message `id` with its `{ $name }` placeables filled in from `args`.
+    pub fn format<'a>(&self, id: &str, args: impl IntoIterator<Item = (&'a str, FluentValue<'a>)>) -> String {
+        let args: FluentArgs = args.into_iter().collect();
+        self.lookup(id, None, Some(&args))
+    }
+
+    pub fn name(&self, value: &impl Localize) -> String {
+        self.text(value.message_id())
+    }
+
+    /// The `.attribute` of message `id`, or the message itself when it has no such attribute.
+    pub fn attribute(&self, id: &str, attribute: &str) -> String {
+        self.lookup(id, Some(attribute), None)
+    }
+
+    /// The `.short` form of a name for tight spaces.
+    pub fn short_name(&self, value: &impl Localize) -> String {
+        self.attribute(value.message_id(), "short")
+    }
+
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        format_number(value, decimals, self.language)
+    }
+
+    /// A length of time in the largest unit that keeps it above 1, e.g. "45 seconds" or "3 hours".
+    pub fn duration(&self, seconds: u64) -> String {
+        let (unit, count) = match seconds {
+            0..120 => ("seconds", seconds),
+            120..7200 => ("minutes", seconds / 60),
+            _ => ("hours", seconds / 3600),
+        };
+        self.format("duration", [("unit", unit.into()), ("count", count.into())])
+    }
+
+    /// A time of day with seconds, e.g. "3:04:05 PM" or "15:04:05".
+    pub fn time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let format = if self.language == Language::English { "%-I:%M:%S %p" } else { "%H:%M:%S" };
+        time.format_localized(format, self.language.chrono_locale()).to_string()
+    }
+
+    /// A time of day without seconds, e.g. "3:04 PM" or "15:04".
+    pub fn short_time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let format = if self.language == Language::English { "%-I:%M %p" } else { "%H:%M" };
+        time.format_localized(format, self.language.chrono_locale()).to_string()
+    }
+
+    /// A date in the locale's numeric form, e.g. "10/18/2026" or "18.10.2026".
+    pub fn date<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        time.format_localized("%x", self.language.chrono_locale()).to_string()
+    }
+
+    /// A date with the month written out, e.g. "October 18, 2026" or "18. Oktober 2026".
+    pub fn long_date<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let format = match self.language {
+            Language::English => "%B %-d, %Y",
+            Language::Spanish => "%-d de %B de %Y",
+            Language::German => "%-d. %B %Y",
+        };
+        time.format_localized(format, self.language.chrono_locale()).to_string()
+    }
+
+    /// A calendar day without a time, e.g. "10/18/2026" or "18.10.2026".
+    pub fn day(&self, date: NaiveDate) -> String {
+        date.format_localized("%x", self.language.chrono_locale()).to_string()
+    }
+
+    /// The abbreviated name of the day's month, e.g. "Oct" or "oct".
+    pub fn short_month(&self, date: NaiveDate) -> String {
+        date.format_localized("%b", self.language.chrono_locale()).to_string()
+    }
+
+    /// The abbreviated name of the day of the week, e.g. "Mon" or "lun".
+    pub fn short_weekday(&self, date: NaiveDate) -> String {
+        date.format_localized("%a", self.language.chrono_locale()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The message ids of a catalog, with `.attribute` ids as `message.attribute`.
+    fn message_ids(catalog: &str) -> Vec<String> {
+        let mut ids = Vec::new();
+        let mut message = "";
+        for line in catalog.lines() {
+            if line.starts_with(|first: char| first.is_ascii_lowercase()) {
+                message = line.split('=').next().unwrap_or_default().trim();
+                ids.push(message.to_string());
+            } else if let Some(attribute) = line.trim_start().strip_prefix('.') {
+                ids.push(format!("{}.{}", message, attribute.split('=').next().unwrap_or_default().trim()));
+            }
+        }
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn every_catalog_has_the_same_messages() {
+        let english = message_ids(Language::English.catalog());
+        assert!(english.len() > 200);
+
+        for language in [Language::Spanish, Language::German] {
+            assert_eq!(message_ids(language.catalog()), english, "{} catalog", language.name());
+        }
+    }
+
+    #[test]
+    fn numbers_use_the_language_separators() {
+        assert_eq!(format_number(1234567.891, 2, Language::English), "1,234,567.89");
+        assert_eq!(format_number(1234567.891, 2, Language::German), "1.234.567,89");
+        assert_eq!(format_number(1234.5, 1, Language::Spanish), "1.234,5");
+        assert_eq!(format_number(999.6, 0, Language::English), "1,000");
+        assert_eq!(format_number(123.0, 0, Language::English), "123");
+        assert_eq!(format_number(-1234.5, 1, Language::English), "-1,234.5");
+        // Rounding to zero does not leave a sign behind.
+        assert_eq!(format_number(-0.04, 1, Language::German), "0,0");
+    }
+
+    #[test]
+    fn catalog_numbers_are_localized() {
+        let german = Localizer::new(Language::German);
+
+        assert_eq!(german.number(2.5, 1), "2,5");
+        assert_eq!(german.format("uptime", [("days", 1200.into()), ("hours", 3.into()), ("minutes", 4.into())]), "1.200 T. 3 Std. 4 Min.");
+    }
+
+    #[test]
+    fn missing_messages_come_from_the_english_catalog() {
+        let mut partial = FluentBundle::new_concurrent(vec![Language::German.tag().parse().unwrap()]);
+        partial.set_use_isolating(false);
+        partial.add_resource(FluentResource::try_new("nav-back = Zurück\n".to_string()).unwrap()).unwrap();
+        let localizer = Localizer { language: Language::German, bundles: vec![partial, bundle(Language::English)] };
+
+        assert_eq!(localizer.text("nav-back"), "Zurück");
+        assert_eq!(localizer.text("discovery-scan-again"), Localizer::new(Language::English).text("discovery-scan-again"));
+        assert_eq!(localizer.text("no-such-message"), "no-such-message");
+    }
+}
@@ -0,0 +1,21 @@
+pub mod aqi;
+pub mod archive;
+pub mod calendar;
+pub mod cloud;
+pub mod comfort;
+pub mod comparison;
+pub mod data_source;
+pub mod discovery;
+pub mod drift;
+pub mod events;
+pub mod freshness;
+pub mod history;
+pub mod i18n;
+pub mod map;
+pub mod night;
+pub mod notifications;
+pub mod report;
+pub mod sensor_data;
+pub mod settings;
+pub mod theme;
+pub mod webhook;
@@ -1,23 +1,112 @@
-mod sensor_data;
-
-use crate::sensor_data::LocalSensorData;
+use purple_air_gui::aqi::{self, AqiCategory, AqiDisplay, AqiScheme};
+use purple_air_gui::archive::{self, ArchivedReading};
+use purple_air_gui::calendar::{self, Calendar, DailyStatistic};
+use purple_air_gui::comfort::{self, ComfortZone, TemperatureUnit};
+use purple_air_gui::comparison::{IndoorOutdoor, Point, Recommendation};
+use purple_air_gui::data_source::{self, DataSource, SourceError, SourceHealth, SourceMetadata};
+use purple_air_gui::discovery::{self, DiscoveryResult};
+use purple_air_gui::drift::{self, DriftAnalysis, DriftFinding};
+use purple_air_gui::events::{self, EventDetector, ParticleEvent};
+use purple_air_gui::freshness::FreshnessTracker;
+use purple_air_gui::history::{History, Reading, ReadingKind, Sample};
+use purple_air_gui::i18n::{FluentValue, Language, Localizer};
+use purple_air_gui::map::{TileId, Viewport};
+#[cfg(feature = "map-tiles")]
+use purple_air_gui::map::{self, TILE_SIZE};
+use purple_air_gui::night::{DisplayMode, NightMode};
+use purple_air_gui::notifications::{self, CategoryChange, CategoryNotifier};
+use purple_air_gui::report::{self, Report, ReportFormat, ReportPeriod};
+use purple_air_gui::sensor_data::LocalSensorData;
+use purple_air_gui::settings::{DataSourceKind, KioskPage, SensorSettings, Settings, WindowSettings};
+use purple_air_gui::theme::{Palette, Rgb, Theme};
+use purple_air_gui::webhook::{self, DeliveryLog, DeliveryOutcome, DeliveryRecord, PendingDelivery, WebhookDispatcher};
+use chrono::Datelike;
+#[cfg(feature = "accessibility")]
+use craft::accesskit::Live;
 use craft::components::{Context, Event};
+#[cfg(feature = "map-tiles")]
+use craft::elements::Image;
 use craft::elements::TinyVg;
 use craft::events::CraftMessage;
+#[cfg(feature = "accessibility")]
+use craft::events::ui_events::keyboard::{Key, KeyState, NamedKey};
 use craft::geometry::Size;
 use craft::resource_manager::ResourceIdentifier;
+#[cfg(feature = "map-tiles")]
+use craft::style::{Overflow, Position};
 use craft::style::Weight;
-use craft::{components::{Component, ComponentSpecification}, elements::{Container, ElementStyles, Text}, palette, style::{AlignItems, Display, FlexDirection, JustifyContent}, Color};
-use std::str::FromStr;
+use craft::{components::{Component, ComponentSpecification}, elements::{Container, ElementStyles, Text}, style::{AlignItems, Display, FlexDirection, JustifyContent}, Color};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+
+/// Look for the tracked sensor on the network after this many failed fetches in a row.
+const REDISCOVER_AFTER_FAILURES: u32 = 3;
+/// How often the config file is checked for changes made outside the app.
+const SETTINGS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const POLL_INTERVAL_CHOICES: [u64; 5] = [5, 10, 30, 60, 120];
+const STALE_AFTER_CHOICES: [u64; 5] = [60, 120, 300, 600, 1800];
+const DAILY_EXPORT_CHOICES: [Option<ReportFormat>; 3] = [None, Some(ReportFormat::Html), Some(ReportFormat::Markdown)];
+/// How often the configured sensors other than the active one are polled, for the map and the indoor/outdoor comparison.
+const OTHER_SENSORS_INTERVAL: Duration = Duration::from_secs(60);
+/// A category change stays on screen this long.
+const ANNOUNCEMENT_DURATION: Duration = Duration::from_secs(60);
+/// The chart shows this many of the most recent readings.
+const CHART_SAMPLES: usize = 60;
+/// The diagnostics page lists this many of the most recent webhook deliveries.
+const DIAGNOSTICS_DELIVERIES: usize = 20;
+
+/// The entry after `current` in `all`, wrapping around. Used by the settings buttons that cycle through their options.
+fn next_choice<T: Copy + PartialEq>(all: &[T], current: T) -> T {
+    let index = all.iter().position(|choice| *choice == current).map_or(0, |index| (index + 1) % all.len());
+    all[index]
+}
+
+fn color(rgb: Rgb) -> Color {
+    Color::from_rgb8(rgb.0, rgb.1, rgb.2)
+}
+
+/// Sizes scaled to the display. The layout was designed for a 1000 x 700 area. Smaller displays, like the
+/// 800 x 480 and 480 x 320 Raspberry Pi screens, get everything scaled down, and portrait displays stack the AQI tiles.
+#[derive(Clone, Copy)]
+struct Layout {
+    scale: f32,
+    portrait: bool,
+}
+
+impl Layout {
+    fn new(window: &WindowSettings) -> Self {
+        let portrait = window.height > window.width;
+        let (design_width, design_height) = if portrait { (700.0, 1000.0) } else { (1000.0, 700.0) };
+        let scale = (window.width / design_width).min(window.height / design_height).clamp(0.45, 2.0);
+
+        Layout { scale, portrait }
+    }
+
+    fn px(&self, pixels: f32) -> String {
+        format!("{}px", (pixels * self.scale).round())
+    }
+
+    fn gap(&self, pixels: i32) -> i32 {
+        (pixels as f32 * self.scale).round() as i32
+    }
+
+    /// Text never gets smaller than this, even on the smallest displays.
+    fn font(&self, size: f32) -> f32 {
+        (size * self.scale).max(11.0)
+    }
 
-const GRAY: Color = Color::from_rgb8(154, 154, 160);
-fn fahrenheit(value: u64) -> String {
-    format!("{} °F", value)
+    /// A row of tiles, or a column on a portrait display.
+    fn tiles(&self) -> Container {
+        if self.portrait { column() } else { row() }
+    }
 }
 
-fn field(label: &str, value: &str) -> Text {
-    Text::new(format!("{}: {}", label, value).as_str())
-        .font_size(20.0)
+fn field(label: &str, value: &str, layout: Layout, localizer: &Localizer) -> Text {
+    Text::new(localizer.format("field", [("label", label.into()), ("value", value.into())]).as_str())
+        .font_size(layout.font(20.0))
 }
 
 fn row() -> Container {
@@ -32,10 +121,681 @@ fn column() -> Container {
         .flex_direction(FlexDirection::Column)
 }
 
+/// Names an element for screen readers. The description is read after the name, e.g. for units or caveats.
+#[cfg(feature = "accessibility")]
+fn accessible<E: ElementStyles>(element: E, name: &str, description: &str) -> E {
+    element.accessible_name(name).accessible_description(description)
+}
+
+#[cfg(not(feature = "accessibility"))]
+fn accessible<E: ElementStyles>(element: E, _name: &str, _description: &str) -> E {
+    element
+}
+
+/// Has screen readers read out changes to the element once they are done speaking.
+#[cfg(feature = "accessibility")]
+fn announced<E: ElementStyles>(element: E) -> E {
+    element.accessible_live(Live::Polite)
+}
+
+#[cfg(not(feature = "accessibility"))]
+fn announced<E: ElementStyles>(element: E) -> E {
+    element
+}
+
+fn button(label: &str, id: &str, palette: &Palette) -> Container {
+    let border_radius = 5.0;
+    row()
+        .id(id)
+        .padding("8px", "16px", "8px", "16px")
+        .border_width("1px", "1px", "1px", "1px")
+        .border_color(color(palette.muted))
+        .border_radius(border_radius, border_radius, border_radius, border_radius)
+        .push(Text::new(label).id(id).color(color(palette.muted)))
+}
+
+/// The key that was just pressed, if the message is a key press.
+#[cfg(feature = "accessibility")]
+fn pressed_key(context: &Context<PurpleAir>) -> Option<Key> {
+    match context.message() {
+        craft::events::Message::CraftMessage(CraftMessage::KeyboardInputEvent(event)) if event.state == KeyState::Down => Some(event.key.clone()),
+        _ => None,
+    }
+}
+
+/// The id of the element that was clicked, if the message is a click.
+fn clicked_id(context: &Context<PurpleAir>) -> Option<String> {
+    if !context.message().clicked() {
+        return None;
+    }
+
+    context.target().and_then(|target| target.get_id().clone())
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+enum Page {
+    #[default]
+    Sensor,
+    Chart,
+    Diagnostics,
+    Map,
+    Comparison,
+    Reports,
+    Calendar,
+    Discovery,
+    Settings,
+}
+
+impl From<KioskPage> for Page {
+    fn from(page: KioskPage) -> Self {
+        match page {
+            KioskPage::Current => Page::Sensor,
+            KioskPage::Chart => Page::Chart,
+            KioskPage::Diagnostics => Page::Diagnostics,
+            KioskPage::Map => Page::Map,
+            KioskPage::Comparison => Page::Comparison,
+        }
+    }
+}
+
+/// The outcome of asking the data source for a reading.
+#[derive(Clone)]
+pub struct SourceUpdate {
+    /// Updates from a source that has since been replaced are dropped.
+    generation: u64,
+    result: Result<Reading, String>,
+    finished: bool,
+    metadata: SourceMetadata,
+    health: SourceHealth,
+}
+
+/// The modification time of the config file, checked periodically to pick up edits made outside the app.
+#[derive(Clone)]
+struct SettingsFileCheck {
+    modified: Option<SystemTime>,
+}
+
+/// Time to move on to the next page in kiosk mode.
+#[derive(Clone)]
+struct PageTick;
+
+/// Time to clear the announcement with this number.
+#[derive(Clone)]
+struct AnnouncementExpired(u64);
+
+/// The reading of one of the other configured sensors, `None` if it did not answer.
+#[derive(Clone)]
+struct OtherSensorReading {
+    url: String,
+    sensor_data: Option<LocalSensorData>,
+}
+
+/// A map tile that finished loading, `None` if it is neither cached nor could be downloaded.
+#[derive(Clone)]
+struct MapTile {
+    tile: TileId,
+    path: Option<PathBuf>,
+}
+
+/// Time to poll the other configured sensors again.
+#[derive(Clone)]
+struct OtherSensorsTick;
+
+/// The ids of the sensors that have readings in the archive.
+#[derive(Clone)]
+struct ArchivedSensors(Vec<String>);
+
+/// The AQI of one day of a sensor's archive in `DAY_BIN_MINUTES` bins and the events recorded that day, for the
+/// calendar's drill-down.
+#[derive(Clone)]
+struct DayProfile {
+    sensor_id: String,
+    date: chrono::NaiveDate,
+    bins: Vec<Option<f64>>,
+    events: Vec<ParticleEvent>,
+}
+
+/// Where an exported report was saved, or why it could not be.
+#[derive(Clone)]
+struct ReportExported(Result<PathBuf, String>);
 
 #[derive(Default)]
 pub struct PurpleAir {
-    sensor_data: Option<LocalSensorData>
+    sensor_data: Option<LocalSensorData>,
+    reading_kind: ReadingKind,
+    history: History,
+    settings: Settings,
+    notifier: CategoryNotifier,
+    webhooks: WebhookDispatcher,
+    deliveries: DeliveryLog,
+    page: Page,
+    discovering: bool,
+    discovery: Option<DiscoveryResult>,
+    source: Option<Arc<Mutex<Box<dyn DataSource>>>>,
+    source_generation: u64,
+    source_metadata: Option<SourceMetadata>,
+    source_health: SourceHealth,
+    settings_path: Option<PathBuf>,
+    settings_modified: Option<SystemTime>,
+    /// Set while the config file cannot be read. The app does not save over a file it could not read.
+    settings_error: Option<String>,
+    /// Whether a `PageTick` is on its way.
+    cycling_pages: bool,
+    /// The last AQI category change, read out by screen readers when it changes and cleared after
+    /// `ANNOUNCEMENT_DURATION`.
+    announcement: Option<CategoryChange>,
+    /// Counts the announcements, so only the timeout of the current one clears it.
+    announcements: u64,
+    localizer: Localizer,
+    freshness: FreshnessTracker,
+    /// The latest readings of the configured sensors other than the active one, by URL.
+    other_sensors: HashMap<String, LocalSensorData>,
+    /// The tiles that were asked for. `None` while loading or if the tile is not available.
+    map_tiles: HashMap<TileId, Option<PathBuf>>,
+    /// Whether an `OtherSensorsTick` is on its way.
+    polling_other_sensors: bool,
+    indoor_outdoor: IndoorOutdoor,
+    /// Looks for smoke, dust and cooking in the readings of each sensor, by sensor id.
+    event_detectors: HashMap<String, EventDetector>,
+    report_period: ReportPeriod,
+    /// A day in the period the report is for. `None` for today.
+    report_date: Option<chrono::NaiveDate>,
+    /// The archived sensor the reports and the calendar are for. `None` for the active sensor.
+    archive_sensor: Option<String>,
+    archived_sensors: Vec<String>,
+    report: Option<Report>,
+    /// Where the last export went, or why it failed.
+    report_status: Option<String>,
+    /// The last day whose reports were exported on their own.
+    daily_export_done: Option<chrono::NaiveDate>,
+    calendar: Option<Calendar>,
+    daily_statistic: DailyStatistic,
+    /// The day picked on the calendar, whose AQI is charted below it.
+    calendar_day: Option<chrono::NaiveDate>,
+    day_profile: Option<DayProfile>,
+    /// How the channels of the active sensor have been doing, worked out once a day for the diagnostics page.
+    drift: Option<DriftAnalysis>,
+    /// Whether a `DriftAnalysis` is on its way.
+    analyzing_drift: bool,
+}
+
+impl PurpleAir {
+    /// Stores the new reading and returns the webhook payloads it triggered, and the category change it caused.
+    fn set_reading(&mut self, reading: Reading) -> (Vec<PendingDelivery>, Option<CategoryChange>) {
+        let Reading { kind, mut sensor_data } = reading;
+        if self.settings.source == DataSourceKind::Local && self.settings.sensor().sensor_id.is_none() {
+            self.settings.sensor_mut().sensor_id = Some(sensor_data.sensor_id.clone());
+            self.save_settings();
+        }
+        self.settings.aqi_scheme.apply(&mut sensor_data);
+
+        let now = chrono::Utc::now();
+        let change = self.notifier.observe(&sensor_data, now);
+
+        let pending = self.webhooks.prepare(&self.settings.webhooks, &sensor_data, change.as_ref(), &self.localizer, now);
+        self.freshness.observe(sensor_data.date_time, now);
+        // The comparison goes by the sensors' URLs, other sources only stand in for the active sensor.
+        if self.settings.source == DataSourceKind::Local {
+            self.indoor_outdoor.observe(self.settings.sensor().url.as_str(), &sensor_data, now);
+        }
+
+        self.history.push(kind, Sample {
+            received_at: now,
+            sensor_data: sensor_data.clone(),
+        });
+        self.reading_kind = kind;
+        self.sensor_data = Some(sensor_data);
+
+        (pending, change)
+    }
+
+    fn source(&mut self) -> Arc<Mutex<Box<dyn DataSource>>> {
+        let settings = &self.settings;
+        self.source
+            .get_or_insert_with(|| Arc::new(Mutex::new(data_source::from_settings(settings))))
+            .clone()
+    }
+
+    /// Follows the known sensors to their new addresses if discovery found them elsewhere.
+    /// Returns true if the address of the active sensor changed.
+    fn set_discovery(&mut self, discovery: DiscoveryResult) -> bool {
+        self.discovering = false;
+
+        let mut moved = Vec::new();
+        for (index, known) in self.settings.sensors.iter_mut().enumerate() {
+            if let Some(sensor_id) = &known.sensor_id
+                && let Some(sensor) = discovery.find(sensor_id)
+                && sensor.url != known.url
+            {
+                known.url = sensor.url.clone();
+                moved.push(index);
+            }
+        }
+        if !moved.is_empty() {
+            self.save_settings();
+        }
+
+        self.discovery = Some(discovery);
+        moved.contains(&self.settings.active_sensor)
+    }
+
+    /// Loads the config file. A file that cannot be read leaves the settings as they are.
+    fn load_settings(&mut self) {
+        let Some(path) = self.settings_path.clone() else {
+            return;
+        };
+
+        self.settings_modified = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+        match Settings::load(&path) {
+            Ok(settings) => {
+                self.settings = settings;
+                self.settings_error = None;
+            }
+            Err(error) => self.settings_error = Some(error.to_string()),
+        }
+        self.update_localizer();
+    }
+
+    /// Loads the catalog of the selected language if it changed.
+    fn update_localizer(&mut self) {
+        if self.localizer.language() != self.settings.language {
+            self.localizer = Localizer::new(self.settings.language);
+        }
+    }
+
+    fn save_settings(&mut self) {
+        let Some(path) = &self.settings_path else {
+            return;
+        };
+        if self.settings_error.is_some() {
+            return;
+        }
+
+        match self.settings.save(path) {
+            // Remember our own write so it is not mistaken for an outside edit.
+            Ok(()) => self.settings_modified = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok(),
+            Err(error) => eprintln!("Failed to save the settings to {}: {}", path.display(), error),
+        }
+    }
+}
+
+/// Asks the data source for its next reading.
+fn schedule_reading(context: &mut Context<PurpleAir>) {
+    let source = context.state_mut().source();
+    let generation = context.state().source_generation;
+
+    context.event_mut().future(async move {
+        let mut source = source.lock().await;
+        let result = source.next_reading().await;
+
+        Event::async_result(SourceUpdate {
+            generation,
+            finished: matches!(result, Err(SourceError::Finished)),
+            result: result.map_err(|error| error.to_string()),
+            metadata: source.metadata(),
+            health: source.health().clone(),
+        })
+    });
+}
+
+/// Replaces the data source after the settings it was created from changed and starts reading from the new one.
+fn restart_source(context: &mut Context<PurpleAir>) {
+    let state = context.state_mut();
+    state.source = None;
+    state.source_generation += 1;
+    schedule_reading(context);
+}
+
+fn start_discovery(context: &mut Context<PurpleAir>) {
+    if context.state().discovering {
+        return;
+    }
+    context.state_mut().discovering = true;
+
+    context.event_mut().future(async move {
+        Event::async_result(discovery::discover().await)
+    });
+}
+
+/// Checks the config file for changes after a short wait.
+fn watch_settings(context: &mut Context<PurpleAir>) {
+    let Some(path) = context.state().settings_path.clone() else {
+        return;
+    };
+
+    context.event_mut().future(async move {
+        tokio::time::sleep(SETTINGS_POLL_INTERVAL).await;
+        let modified = tokio::fs::metadata(&path).await.and_then(|metadata| metadata.modified()).ok();
+        Event::async_result(SettingsFileCheck { modified })
+    });
+}
+
+/// Starts cycling through the kiosk pages, unless kiosk mode is off or the pages are cycling already.
+fn schedule_page_tick(context: &mut Context<PurpleAir>) {
+    let kiosk = &context.state().settings.kiosk;
+    if context.state().cycling_pages || !kiosk.enabled || kiosk.page_seconds == 0 || kiosk.pages.len() < 2 {
+        return;
+    }
+
+    let page_duration = Duration::from_secs(kiosk.page_seconds);
+    context.state_mut().cycling_pages = true;
+    context.event_mut().future(async move {
+        tokio::time::sleep(page_duration).await;
+        Event::async_result(PageTick)
+    });
+}
+
+/// Moves to the kiosk page after the current one. Pages outside the rotation, like the settings, are left alone.
+fn next_kiosk_page(state: &mut PurpleAir) {
+    let pages: Vec<Page> = state.settings.kiosk.pages.iter().map(|page| Page::from(*page)).collect();
+    if let Some(index) = pages.iter().position(|page| *page == state.page) {
+        let next = (index + 1) % pages.len();
+        state.page = pages.into_iter().nth(next).unwrap_or_default();
+    }
+}
+
+/// Has screen readers read out the category change, and clears it again after `ANNOUNCEMENT_DURATION`.
+fn announce(context: &mut Context<PurpleAir>, change: CategoryChange) {
+    let state = context.state_mut();
+    state.announcement = Some(change);
+    state.announcements += 1;
+    let number = state.announcements;
+
+    context.event_mut().future(async move {
+        tokio::time::sleep(ANNOUNCEMENT_DURATION).await;
+        Event::async_result(AnnouncementExpired(number))
+    });
+}
+
+/// Shows the desktop notification off the UI thread, the notification service can take a while to answer.
+fn show_notification(context: &mut Context<PurpleAir>, change: CategoryChange) {
+    let localizer = &context.state().localizer;
+    let (summary, body) = (change.summary(localizer), change.body(localizer));
+    context.event_mut().future(async move {
+        notifications::notify(summary, body).await;
+        Event::async_result(())
+    });
+}
+
+fn deliver_webhooks(context: &mut Context<PurpleAir>, pending: Vec<PendingDelivery>) {
+    for delivery in pending {
+        context.event_mut().future(async move {
+            Event::async_result(webhook::deliver(delivery).await)
+        });
+    }
+}
+
+/// Polls the configured sensors other than the active one, and again every `OTHER_SENSORS_INTERVAL`.
+fn poll_other_sensors(context: &mut Context<PurpleAir>) {
+    if context.state().polling_other_sensors {
+        return;
+    }
+
+    let state = context.state_mut();
+    state.polling_other_sensors = true;
+
+    // Forget the sensors that were removed since the last poll.
+    let configured: Vec<String> = state.settings.sensors.iter().map(|sensor| sensor.url.clone()).collect();
+    state.other_sensors.retain(|url, _| configured.contains(url));
+    state.indoor_outdoor.retain(&configured.iter().map(String::as_str).collect::<Vec<_>>());
+
+    let urls: Vec<String> = state.settings.sensors.iter().enumerate()
+        .filter(|(index, _)| *index != state.settings.active_sensor)
+        .map(|(_, sensor)| sensor.url.clone())
+        .collect();
+    for url in urls {
+        context.event_mut().future(async move {
+            let sensor_data = discovery::probe(url.clone()).await.map(|sensor| sensor.sensor_data);
+            Event::async_result(OtherSensorReading { url, sensor_data })
+        });
+    }
+
+    context.event_mut().future(async move {
+        tokio::time::sleep(OTHER_SENSORS_INTERVAL).await;
+        Event::async_result(OtherSensorsTick)
+    });
+}
+
+/// Loads the tiles under the map when it is shown. Tiles that failed to load before are tried again.
+fn show_map(context: &mut Context<PurpleAir>) {
+    context.state_mut().map_tiles.retain(|_, path| path.is_some());
+    load_map_tiles(context);
+}
+
+/// Starts loading the tiles in view that were not asked for yet.
+#[cfg(feature = "map-tiles")]
+fn load_map_tiles(context: &mut Context<PurpleAir>) {
+    let state = context.state();
+    let Some(viewport) = map_viewport(state, Layout::new(&state.settings.window)) else {
+        return;
+    };
+
+    let missing: Vec<TileId> = viewport.tiles().into_iter().map(|(tile, _, _)| tile).filter(|tile| !state.map_tiles.contains_key(tile)).collect();
+    for tile in missing {
+        context.state_mut().map_tiles.insert(tile, None);
+        let settings = context.state().settings.map.clone();
+        context.event_mut().future(async move {
+            Event::async_result(MapTile { tile, path: map::load_tile(tile, settings).await })
+        });
+    }
+}
+
+/// Without the tile overlay there is nothing to download.
+#[cfg(not(feature = "map-tiles"))]
+fn load_map_tiles(_context: &mut Context<PurpleAir>) {}
+
+/// Appends a reading to the archive the reports are built from. `sensor_data` must be as the sensor reported it,
+/// without the AQI scheme applied.
+fn archive_reading(context: &mut Context<PurpleAir>, sensor_data: &LocalSensorData) {
+    let archive = &context.state().settings.archive;
+    let Some(directory) = archive.directory().filter(|_| archive.enabled) else {
+        return;
+    };
+
+    let sensor_id = sensor_data.sensor_id.clone();
+    let reading = ArchivedReading::new(sensor_data, chrono::Utc::now());
+    context.event_mut().future(async move {
+        if let Err(error) = archive::append(directory.clone(), sensor_id, reading).await {
+            eprintln!("Failed to archive the reading in {}: {}", directory.display(), error);
+        }
+        Event::async_result(())
+    });
+}
+
+/// Looks for a smoke, dust or cooking event in a reading received at `received_at`, and records the events that
+/// ended in the archive if `record` is set.
+fn detect_events(context: &mut Context<PurpleAir>, sensor_data: &LocalSensorData, received_at: chrono::DateTime<chrono::Utc>, record: bool) {
+    let sensor_id = sensor_data.sensor_id.clone();
+    let ended = context.state_mut().event_detectors.entry(sensor_id.clone()).or_default().observe(sensor_data, received_at);
+    let archive = &context.state().settings.archive;
+    let (Some(event), Some(directory)) = (ended.filter(|_| record), archive.directory().filter(|_| archive.enabled)) else {
+        return;
+    };
+
+    context.event_mut().future(async move {
+        if let Err(error) = events::record(directory.clone(), sensor_id, event).await {
+            eprintln!("Failed to record the event in {}: {}", directory.display(), error);
+        }
+        Event::async_result(())
+    });
+}
+
+/// The events the sensor's readings were followed for, with the ongoing one last.
+fn sensor_events(state: &PurpleAir, sensor_id: &str) -> Vec<ParticleEvent> {
+    state.event_detectors.get(sensor_id).map(EventDetector::events).unwrap_or_default()
+}
+
+/// What caused an event, when, and how high PM2.5 got.
+fn describe_event(event: &ParticleEvent, localizer: &Localizer) -> String {
+    let time = |time: chrono::DateTime<chrono::Utc>| localizer.short_time(&time.with_timezone(&chrono::Local));
+    let mut args = vec![
+        ("kind", localizer.name(&event.kind).into()),
+        ("start", time(event.start).into()),
+        ("peak", localizer.number(event.peak_pm2_5, 1).into()),
+    ];
+    match event.end {
+        Some(end) => {
+            args.push(("end", time(end).into()));
+            localizer.format("event-description", args)
+        }
+        None => localizer.format("event-description-ongoing", args),
+    }
+}
+
+/// The name of an archived sensor in the settings, or its id.
+fn sensor_label(settings: &Settings, sensor_id: &str) -> String {
+    settings.sensors.iter()
+        .find(|sensor| sensor.sensor_id.as_deref() == Some(sensor_id))
+        .map_or(sensor_id.to_string(), |sensor| sensor.label().to_string())
+}
+
+/// The sensor the reports and the calendar are for.
+fn archive_sensor(state: &PurpleAir) -> Option<String> {
+    state.archive_sensor.clone()
+        .or_else(|| state.sensor_data.as_ref().map(|sensor_data| sensor_data.sensor_id.clone()))
+        .or_else(|| state.settings.sensor().sensor_id.clone())
+}
+
+/// The sensor, period and day the reports page is set to.
+fn report_selection(state: &PurpleAir) -> Option<(String, ReportPeriod, chrono::NaiveDate)> {
+    let date = state.report_date.unwrap_or_else(|| chrono::Local::now().date_naive());
+    Some((archive_sensor(state)?, state.report_period, date))
+}
+
+/// Builds the report the reports page is set to from the archive.
+fn load_report(context: &mut Context<PurpleAir>) {
+    let state = context.state();
+    let (Some((sensor_id, period, date)), Some(directory)) = (report_selection(state), state.settings.archive.directory()) else {
+        return;
+    };
+
+    let label = sensor_label(&state.settings, sensor_id.as_str());
+    let scheme = state.settings.aqi_scheme;
+    context.event_mut().future(async move {
+        let (first, last) = period.span(date);
+        let readings = archive::load(directory, sensor_id.clone(), first, last).await;
+        Event::async_result(Report::new(sensor_id, label, period, date, &readings, scheme))
+    });
+}
+
+fn load_archived_sensors(context: &mut Context<PurpleAir>) {
+    if let Some(directory) = context.state().settings.archive.directory() {
+        context.event_mut().future(async move {
+            Event::async_result(ArchivedSensors(archive::sensors(directory).await))
+        });
+    }
+}
+
+/// Looks up the archived sensors and loads the report when the reports page is shown.
+fn show_reports(context: &mut Context<PurpleAir>) {
+    context.state_mut().report_status = None;
+    load_archived_sensors(context);
+    load_report(context);
+}
+
+/// Works out the daily AQI of the archived sensor for the calendar, and the profile of the picked day.
+fn load_calendar(context: &mut Context<PurpleAir>) {
+    let state = context.state();
+    let (Some(sensor_id), Some(directory)) = (archive_sensor(state), state.settings.archive.directory()) else {
+        return;
+    };
+
+    let scheme = state.settings.aqi_scheme;
+    context.event_mut().future(async move {
+        Event::async_result(calendar::load(directory, sensor_id, chrono::Local::now().date_naive(), scheme).await)
+    });
+    load_day_profile(context);
+}
+
+fn load_day_profile(context: &mut Context<PurpleAir>) {
+    let state = context.state();
+    let (Some(date), Some(sensor_id), Some(directory)) = (state.calendar_day, archive_sensor(state), state.settings.archive.directory()) else {
+        return;
+    };
+
+    let scheme = state.settings.aqi_scheme;
+    context.event_mut().future(async move {
+        let readings = archive::load(directory.clone(), sensor_id.clone(), date, date).await;
+        let midnight = |date: chrono::NaiveDate| date.and_hms_opt(0, 0, 0).and_then(|time| time.and_local_timezone(chrono::Local).earliest());
+        let events = match (midnight(date), date.succ_opt().and_then(midnight)) {
+            (Some(start), Some(end)) => events::load(directory, sensor_id.clone(), start.to_utc(), end.to_utc()).await,
+            _ => Vec::new(),
+        };
+        Event::async_result(DayProfile { sensor_id, date, bins: calendar::day_profile(&readings, scheme), events })
+    });
+}
+
+/// Analyses the channels of the active sensor from the archive, unless that was done today already.
+fn show_diagnostics(context: &mut Context<PurpleAir>) {
+    let state = context.state();
+    let today = chrono::Local::now().date_naive();
+    let (Some(sensor_data), Some(directory)) = (&state.sensor_data, state.settings.archive.directory()) else {
+        return;
+    };
+    if state.analyzing_drift || state.drift.as_ref().is_some_and(|drift| drift.sensor_id == sensor_data.sensor_id && drift.date == today) {
+        return;
+    }
+
+    let sensor_id = sensor_data.sensor_id.clone();
+    context.state_mut().analyzing_drift = true;
+    context.event_mut().future(async move {
+        Event::async_result(drift::analyze(directory, sensor_id, today).await)
+    });
+}
+
+fn show_calendar(context: &mut Context<PurpleAir>) {
+    load_archived_sensors(context);
+    load_calendar(context);
+}
+
+fn export_report(context: &mut Context<PurpleAir>, format: ReportFormat) {
+    let state = context.state();
+    let (Some(report), Some(directory)) = (&state.report, state.settings.reports.directory()) else {
+        return;
+    };
+
+    let file_name = report.file_name(format);
+    let contents = report.render(format, &state.localizer, state.settings.temperature_unit);
+    context.event_mut().future(async move {
+        Event::async_result(ReportExported(report::export(directory, file_name, contents).await.map_err(|error| error.to_string())))
+    });
+}
+
+/// Saves yesterday's report of every archived sensor once the day is over, if the settings ask for it.
+/// Reports that were saved before, e.g. by an earlier run of the app, are left alone.
+fn export_daily_reports(context: &mut Context<PurpleAir>) {
+    let state = context.state();
+    let yesterday = chrono::Local::now().date_naive() - chrono::TimeDelta::days(1);
+    let (Some(format), Some(archive), Some(directory)) = (state.settings.reports.daily_export, state.settings.archive.directory(), state.settings.reports.directory()) else {
+        return;
+    };
+    if state.daily_export_done == Some(yesterday) {
+        return;
+    }
+
+    let settings = state.settings.clone();
+    context.state_mut().daily_export_done = Some(yesterday);
+    context.event_mut().future(async move {
+        // Made here, the app's localizer cannot be shared with the background task.
+        let localizer = Localizer::new(settings.language);
+        for sensor_id in archive::sensors(archive.clone()).await {
+            let readings = archive::load(archive.clone(), sensor_id.clone(), yesterday, yesterday).await;
+            if readings.is_empty() {
+                continue;
+            }
+
+            let report = Report::new(sensor_id.clone(), sensor_label(&settings, sensor_id.as_str()), ReportPeriod::Day, yesterday, &readings, settings.aqi_scheme);
+            let file_name = report.file_name(format);
+            if tokio::fs::try_exists(directory.join(file_name.as_str())).await.unwrap_or(false) {
+                continue;
+            }
+            let contents = report.render(format, &localizer, settings.temperature_unit);
+            if let Err(error) = report::export(directory.clone(), file_name, contents).await {
+                eprintln!("Failed to save the daily report to {}: {}", directory.display(), error);
+            }
+        }
+        Event::async_result(())
+    });
 }
 
 fn hardware_on_the_board(hardware_discovered: String) -> Vec<String> {
@@ -46,119 +806,213 @@ fn hardware_on_the_board(hardware_discovered: String) -> Vec<String> {
     Vec::new()
 }
 
-fn aqi_a(sensor_data: &LocalSensorData) -> Container {
-    if let Some(p25aqic) = &sensor_data.p25aqic && let Some(pm2_5_aqi) = sensor_data.pm2_5_aqi {
-        let border_radius = 5.0;
-        column()
-            .align_items(AlignItems::Center)
-            .justify_content(JustifyContent::Center)
-            .gap(10)
+/// A meter with one filled segment per category level, so the category shows without relying on color.
+fn category_meter(category: AqiCategory, text: Rgb, layout: Layout, localizer: &Localizer) -> Container {
+    let level = localizer.format("category-level", [("level", category.level().into()), ("count", AqiCategory::ALL.len().into())]);
+    let mut meter = accessible(row().gap(layout.gap(3)), level.as_str(), "");
+    for level in 1..=AqiCategory::ALL.len() {
+        let segment = Container::new()
+            .width(layout.px(12.0).as_str())
+            .height(layout.px(8.0).as_str())
             .border_width("1px", "1px", "1px", "1px")
-            .border_radius(border_radius, border_radius, border_radius, border_radius)
-            .background(Color::from_str(p25aqic.as_str()).unwrap_or(Color::WHITE))
-            .push(Text::new("Ch A PM2.5 AQI"))
-            .push(
-                Text::new((pm2_5_aqi as u64).to_string().as_str())
-                    .font_size(40.0)
-                    .font_weight(Weight::BOLD)
-            )
-            .width("150px")
-            .height("150px")   
-    } else {
-        column()
+            .border_color(color(text));
+        meter.push_in_place(if level <= category.level() { segment.background(color(text)) } else { segment }.component());
     }
+    meter
 }
 
-fn aqi_b(sensor_data: &LocalSensorData) -> Container {
-    if let Some(p25aqic_b) = sensor_data.p25aqic_b.as_ref() && let Some(pm2_5_aqi_b) = sensor_data.pm2_5_aqi_b {
-        let border_radius = 5.0;
-        column()
-            .align_items(AlignItems::Center)
-            .justify_content(JustifyContent::Center)
-            .gap(10)
-            .border_width("1px", "1px", "1px", "1px")
-            .border_radius(border_radius, border_radius, border_radius, border_radius)
-            .background(Color::from_str(p25aqic_b.as_str()).unwrap_or(Color::WHITE))
-            .push(Text::new("Ch B PM2.5 AQI"))
-            .push(
-                Text::new((pm2_5_aqi_b as u64).to_string().as_str())
-                    .font_size(40.0)
-                    .font_weight(Weight::BOLD)
-            )
-            .width("150px")
-            .height("150px")   
-    } else {
-        column()
+/// The AQI with its category name and level meter. The text is black or white, whichever reads better on the category color.
+/// `channel` is the catalog id of the tile's label, which has a `.spoken` form for screen readers.
+fn aqi_tile(channel: &str, aqi: f64, layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    let category = AqiCategory::from_aqi(aqi);
+    let background = palette.category(category);
+    let text = background.contrasting_text();
+    let border_radius = 5.0;
+    let label = localizer.text(channel);
+    let name = localizer.format("aqi-tile-spoken", [
+        ("channel", localizer.attribute(channel, "spoken").into()),
+        ("aqi", (aqi as u64).into()),
+        ("category", localizer.name(&category).into()),
+    ]);
+    let description = localizer.format("aqi-tile-description", [("level", category.level().into()), ("count", AqiCategory::ALL.len().into())]);
+
+    accessible(column(), name.as_str(), description.as_str())
+        .align_items(AlignItems::Center)
+        .justify_content(JustifyContent::Center)
+        .gap(layout.gap(8))
+        .border_width("1px", "1px", "1px", "1px")
+        .border_radius(border_radius, border_radius, border_radius, border_radius)
+        .background(color(background))
+        .color(color(text))
+        .push(Text::new(label.as_str()).font_size(layout.font(16.0)).color(color(text)))
+        .push(
+            Text::new(localizer.number(aqi.trunc(), 0).as_str())
+                .font_size(layout.font(40.0))
+                .font_weight(Weight::BOLD)
+                .color(color(text))
+        )
+        .push(Text::new(localizer.short_name(&category).as_str()).font_size(layout.font(14.0)).color(color(text)))
+        .push(category_meter(category, text, layout, localizer))
+        .width(layout.px(170.0).as_str())
+        .height(layout.px(170.0).as_str())
+}
+
+fn aqi_a(sensor_data: &LocalSensorData, layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    match sensor_data.pm2_5_aqi {
+        Some(pm2_5_aqi) => aqi_tile("aqi-channel-a", pm2_5_aqi, layout, palette, localizer),
+        None => column(),
+    }
+}
+
+fn aqi_b(sensor_data: &LocalSensorData, layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    match sensor_data.pm2_5_aqi_b {
+        Some(pm2_5_aqi_b) => aqi_tile("aqi-channel-b", pm2_5_aqi_b, layout, palette, localizer),
+        None => column(),
+    }
+}
+
+fn pm10_aqi_a(sensor_data: &LocalSensorData, layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    match sensor_data.pm10_0_atm {
+        Some(pm10) => aqi_tile("aqi-pm10-channel-a", aqi::pm10_aqi(pm10), layout, palette, localizer),
+        None => column(),
     }
 }
 
-fn common_measurements(sensor_data: &LocalSensorData) -> Container {
+fn pm10_aqi_b(sensor_data: &LocalSensorData, layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    match sensor_data.pm10_0_atm_b {
+        Some(pm10) => aqi_tile("aqi-pm10-channel-b", aqi::pm10_aqi(pm10), layout, palette, localizer),
+        None => column(),
+    }
+}
+
+/// A channel's PM1.0, PM2.5 and PM10 concentrations, with the ATM and CF=1 estimates side by side.
+/// `channel` is the catalog id of the tile's label, which has a `.spoken` form for screen readers.
+fn concentration_tile(channel: &str, rows: [(&str, Option<f64>, Option<f64>); 3], layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    let border_radius = 5.0;
+    let cell = |text: &str, width: f32| Text::new(text).font_size(layout.font(16.0)).width(layout.px(width).as_str());
+    let value = |value: Option<f64>| value.map_or("–".to_string(), |value| localizer.number(value, 1));
+
+    let mut tile = accessible(column(), localizer.attribute(channel, "spoken").as_str(), "")
+        .gap(layout.gap(6))
+        .padding("10px", "10px", "10px", "10px")
+        .border_width("1px", "1px", "1px", "1px")
+        .border_radius(border_radius, border_radius, border_radius, border_radius)
+        .border_color(color(palette.border))
+        .push(Text::new(localizer.text(channel).as_str()).font_size(layout.font(16.0)).font_weight(Weight::BOLD))
+        .push(
+            row()
+                .push(cell("", 70.0))
+                .push(cell(localizer.text("concentration-atm").as_str(), 70.0).color(color(palette.muted)))
+                .push(cell(localizer.text("concentration-cf-1").as_str(), 70.0).color(color(palette.muted)))
+        );
+    for (size, atm, cf_1) in rows {
+        let name = localizer.format("concentration-spoken", [("size", size.into()), ("atm", value(atm).into()), ("cf_1", value(cf_1).into())]);
+        tile.push_in_place(
+            accessible(row(), name.as_str(), "")
+                .push(cell(size, 70.0).color(color(palette.muted)))
+                .push(cell(value(atm).as_str(), 70.0))
+                .push(cell(value(cf_1).as_str(), 70.0))
+                .component(),
+        );
+    }
+    tile
+}
+
+fn concentrations(sensor_data: &LocalSensorData, layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    let sizes = [localizer.text("pm1-0"), localizer.text("pm2-5"), localizer.text("pm10")];
+    let channel_a = [
+        (sizes[0].as_str(), sensor_data.pm1_0_atm, sensor_data.pm1_0_cf_1),
+        (sizes[1].as_str(), sensor_data.pm2_5_atm, sensor_data.pm2_5_cf_1),
+        (sizes[2].as_str(), sensor_data.pm10_0_atm, sensor_data.pm10_0_cf_1),
+    ];
+    let channel_b = [
+        (sizes[0].as_str(), sensor_data.pm1_0_atm_b, sensor_data.pm1_0_cf_1_b),
+        (sizes[1].as_str(), sensor_data.pm2_5_atm_b, sensor_data.pm2_5_cf_1_b),
+        (sizes[2].as_str(), sensor_data.pm10_0_atm_b, sensor_data.pm10_0_cf_1_b),
+    ];
+    layout.tiles()
+        .gap(layout.gap(25))
+        .push(concentration_tile("concentrations-channel-a", channel_a, layout, palette, localizer))
+        .push(concentration_tile("concentrations-channel-b", channel_b, layout, palette, localizer))
+}
+
+fn common_measurements(sensor_data: &LocalSensorData, temperature_unit: TemperatureUnit, layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    let icon_size = layout.px(50.0);
+    let unit = match temperature_unit {
+        TemperatureUnit::Fahrenheit => "fahrenheit",
+        TemperatureUnit::Celsius => "celsius",
+    };
+    let temperature = |id: &str, fahrenheit: u64| {
+        localizer.format(id, [("value", localizer.number(temperature_unit.convert(fahrenheit as f64), 0).into()), ("unit", unit.into())])
+    };
+    let icon = |icon: TinyVg, id: &str| accessible(icon, localizer.text(id).as_str(), localizer.attribute(id, "description").as_str());
     let mut common_measurements = row()
         .align_items(AlignItems::Center)
-        .gap(25)
+        .gap(layout.gap(25))
         ;
 
     if let Some(current_temp_f) = sensor_data.current_temp_f {
-        let temp = row()
+        let name = temperature("temperature-spoken", current_temp_f);
+        let temp = accessible(row(), name.as_str(), localizer.attribute("temperature-spoken", "description").as_str())
             .align_items(AlignItems::Center)
-            .gap(10)
+            .gap(layout.gap(10))
             .push(
-                TinyVg::new(ResourceIdentifier::Bytes(include_bytes!("../assets/device_thermostat_24dp_E3E3E3_FILL0_wght400_GRAD0_opsz24.tvg")))
-                    .width("50px")
-                    .height("50px")
-                    .max_width("50px")
-                    .max_height("50px")
-                    .color(Color::from_rgb8(255, 183, 77))
+                icon(TinyVg::new(ResourceIdentifier::Bytes(include_bytes!("../assets/device_thermostat_24dp_E3E3E3_FILL0_wght400_GRAD0_opsz24.tvg"))), "icon-thermometer")
+                    .width(icon_size.as_str())
+                    .height(icon_size.as_str())
+                    .max_width(icon_size.as_str())
+                    .max_height(icon_size.as_str())
+                    .color(color(palette.temperature))
             )
             .push(
-                Text::new(fahrenheit(current_temp_f).as_str())
-                    .font_size(21.0)
-                    .color(palette::css::CADET_BLUE)
-                    .color(Color::from_rgb8(255, 183, 77))
+                Text::new(temperature("temperature-value", current_temp_f).as_str())
+                    .font_size(layout.font(21.0))
+                    .color(color(palette.temperature))
             );
         
         common_measurements.push_in_place(temp.component());   
     }
 
     if let Some(current_dewpoint_f) = sensor_data.current_dewpoint_f {
-        let dew = row()
+        let name = temperature("dew-point-spoken", current_dewpoint_f);
+        let dew = accessible(row(), name.as_str(), localizer.attribute("dew-point-spoken", "description").as_str())
             .align_items(AlignItems::Center)
-            .gap(10)
+            .gap(layout.gap(10))
             .push(
-                TinyVg::new(ResourceIdentifier::Bytes(include_bytes!("../assets/dew_point_24dp_E3E3E3_FILL0_wght400_GRAD0_opsz24.tvg")))
-                    .width("50px")
-                    .height("50px")
-                    .max_width("50px")
-                    .max_height("50px")
-                    .color(Color::from_rgb8(128, 203, 196))
+                icon(TinyVg::new(ResourceIdentifier::Bytes(include_bytes!("../assets/dew_point_24dp_E3E3E3_FILL0_wght400_GRAD0_opsz24.tvg"))), "icon-water-drop")
+                    .width(icon_size.as_str())
+                    .height(icon_size.as_str())
+                    .max_width(icon_size.as_str())
+                    .max_height(icon_size.as_str())
+                    .color(color(palette.dew_point))
             )
             .push(
-                Text::new(fahrenheit(current_dewpoint_f).as_str())
-                    .font_size(21.0)
-                    .color(palette::css::CADET_BLUE)
-                    .color(Color::from_rgb8(128, 203, 196))
+                Text::new(temperature("temperature-value", current_dewpoint_f).as_str())
+                    .font_size(layout.font(21.0))
+                    .color(color(palette.dew_point))
             ); 
         
         common_measurements.push_in_place(dew.component());   
     }
 
     if let Some(current_humidity) = sensor_data.current_humidity {
-        let humidity = row()
+        let value = localizer.number(current_humidity as f64, 0);
+        let name = localizer.format("humidity-spoken", [("value", value.as_str().into())]);
+        let humidity = accessible(row(), name.as_str(), localizer.attribute("humidity-spoken", "description").as_str())
             .align_items(AlignItems::Center)
-            .gap(10)
+            .gap(layout.gap(10))
             .push(
-                TinyVg::new(ResourceIdentifier::Bytes(include_bytes!("../assets/humidity_percentage_24dp_E3E3E3_FILL0_wght400_GRAD0_opsz24.tvg")))
-                    .width("50px")
-                    .height("50px")
-                    .max_width("50px")
-                    .max_height("50px")
-                    .color(Color::from_rgb8(129, 212, 250))
+                icon(TinyVg::new(ResourceIdentifier::Bytes(include_bytes!("../assets/humidity_percentage_24dp_E3E3E3_FILL0_wght400_GRAD0_opsz24.tvg"))), "icon-percent")
+                    .width(icon_size.as_str())
+                    .height(icon_size.as_str())
+                    .max_width(icon_size.as_str())
+                    .max_height(icon_size.as_str())
+                    .color(color(palette.humidity))
             )
             .push(
-                Text::new(format!("{}%", current_humidity).as_str())
-                    .font_size(21.0)
-                    .color(palette::css::CADET_BLUE)
-                    .color(Color::from_rgb8(129, 212, 250))
+                Text::new(localizer.format("humidity-value", [("value", value.as_str().into())]).as_str())
+                    .font_size(layout.font(21.0))
+                    .color(color(palette.humidity))
             );
         
         common_measurements.push_in_place(humidity.component());   
@@ -167,89 +1021,1432 @@ fn common_measurements(sensor_data: &LocalSensorData) -> Container {
     common_measurements
 }
 
+/// The heat index, humidex, absolute humidity and comfort zone, worked out from the uncorrected temperature and humidity.
+fn comfort_metrics(sensor_data: &LocalSensorData, temperature_unit: TemperatureUnit, layout: Layout, palette: &Palette, localizer: &Localizer) -> Container {
+    let (Some(temperature_f), Some(humidity)) = (sensor_data.current_temp_f, sensor_data.current_humidity) else {
+        return row();
+    };
+    let (temperature_f, humidity) = (temperature_f as f64, humidity as f64);
+    let unit = match temperature_unit {
+        TemperatureUnit::Fahrenheit => "fahrenheit",
+        TemperatureUnit::Celsius => "celsius",
+    };
+    // `spoken` is the catalog id of the screen reader name, which has a `.description`.
+    let tile = |label: &str, spoken: &str, value: String, shown: String, text: Rgb| {
+        let name = localizer.format(spoken, [("value", value.as_str().into()), ("unit", unit.into())]);
+        accessible(column(), name.as_str(), localizer.attribute(spoken, "description").as_str())
+            .gap(layout.gap(4))
+            .push(Text::new(localizer.text(label).as_str()).font_size(layout.font(14.0)).color(color(palette.muted)))
+            .push(Text::new(shown.as_str()).font_size(layout.font(21.0)).color(color(text)))
+    };
+
+    let heat_index = localizer.number(temperature_unit.convert(comfort::heat_index_f(temperature_f, humidity)), 0);
+    let humidex = localizer.number(comfort::humidex(temperature_f, humidity), 0);
+    let absolute_humidity = localizer.number(comfort::absolute_humidity(temperature_f, humidity), 1);
+    let zone = ComfortZone::new(temperature_f, humidity);
+    row()
+        .align_items(AlignItems::Center)
+        .gap(layout.gap(25))
+        .push(tile(
+            "heat-index",
+            "heat-index-spoken",
+            heat_index.clone(),
+            localizer.format("temperature-value", [("value", heat_index.as_str().into()), ("unit", unit.into())]),
+            palette.temperature,
+        ))
+        .push(tile("humidex", "humidex-spoken", humidex.clone(), humidex, palette.temperature))
+        .push(tile(
+            "absolute-humidity",
+            "absolute-humidity-spoken",
+            absolute_humidity.clone(),
+            localizer.format("absolute-humidity-value", [("value", absolute_humidity.as_str().into())]),
+            palette.humidity,
+        ))
+        .push(tile(
+            "comfort-zone",
+            "comfort-zone-spoken",
+            localizer.name(&zone),
+            localizer.name(&zone),
+            if zone == ComfortZone::Comfortable { palette.text } else { palette.accent },
+        ))
+}
+
+/// The header above the current values: the reading kind, sample count, last update and mean AQI.
+fn reading_summary(state: &PurpleAir) -> String {
+    let localizer = &state.localizer;
+    let reading_kind = state.reading_kind;
+    let history = &state.history;
+    let mut args = vec![("kind", localizer.name(&reading_kind).into()), ("samples", history.samples(reading_kind).len().into())];
+    let id = match history.summary(reading_kind) {
+        None => "reading-summary",
+        Some((newest, mean_aqi)) => {
+            args.push(("time", localizer.time(&newest.with_timezone(&chrono::Local)).into()));
+            match mean_aqi {
+                Some(mean_aqi) => {
+                    args.push(("mean", mean_aqi.round().into()));
+                    "reading-summary-mean"
+                }
+                None => "reading-summary-updated",
+            }
+        }
+    };
+    localizer.format(id, args)
+}
+
+/// How far the sensor's clock is off, e.g. "3 minutes ahead".
+fn clock_offset(clock_skew: chrono::TimeDelta, id: &str, localizer: &Localizer) -> String {
+    let direction = if clock_skew > chrono::TimeDelta::zero() { "ahead" } else { "behind" };
+    let duration = localizer.duration(clock_skew.num_seconds().unsigned_abs());
+    localizer.format(id, [("duration", duration.into()), ("direction", direction.into())])
+}
+
+fn current_page(state: &PurpleAir, sensor_data: &LocalSensorData, layout: Layout, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let freshness = &state.settings.freshness;
+    let age = state.freshness.age(chrono::Utc::now()).unwrap_or_default();
+    let stale = freshness.is_stale(age);
+    let palette = &if stale { palette.stale() } else { palette.clone() };
+    let age = localizer.duration(age.num_seconds() as u64);
+
+    let reading_kind = state.reading_kind;
+    let mut mode = row()
+        .align_items(AlignItems::Center)
+        .gap(layout.gap(15))
+        .push(
+            Text::new(reading_summary(state).as_str())
+                .font_size(layout.font(16.0))
+                .font_weight(Weight::BOLD)
+                .color(if reading_kind == ReadingKind::Live { color(palette.accent) } else { color(palette.muted) })
+        )
+        .push(
+            Text::new(localizer.format("updated-ago", [("duration", age.as_str().into())]).as_str())
+                .font_size(layout.font(16.0))
+                .color(color(palette.muted))
+        );
+    if !state.settings.kiosk.enabled {
+        let on = |on: bool| FluentValue::from(if on { "yes" } else { "no" });
+        let live_mode = localizer.format("live-mode", [("on", on(state.settings.live_mode))]);
+        let recording = localizer.format("recording", [("on", on(state.settings.recording.is_some()))]);
+        mode.push_in_place(button(live_mode.as_str(), "live", palette).component());
+        mode.push_in_place(button(recording.as_str(), "record", palette).component());
+    }
+
+    let aqi_display = state.settings.aqi_display;
+    let mut aqi_container = layout.tiles().gap(layout.gap(25));
+    if aqi_display.shows_pm2_5() {
+        aqi_container.push_in_place(aqi_a(sensor_data, layout, palette, localizer).component());
+        aqi_container.push_in_place(aqi_b(sensor_data, layout, palette, localizer).component());
+    }
+    if aqi_display.shows_pm10() {
+        aqi_container.push_in_place(pm10_aqi_a(sensor_data, layout, palette, localizer).component());
+        aqi_container.push_in_place(pm10_aqi_b(sensor_data, layout, palette, localizer).component());
+    }
+
+    let mut page = column().gap(layout.gap(20)).push(mode);
+    let mut warn = |message: String| page.push_in_place(Text::new(message.as_str()).font_size(layout.font(16.0)).color(color(palette.accent)).component());
+    if stale {
+        warn(localizer.format("stale-warning", [("duration", age.as_str().into())]));
+    }
+    // Only the local sensor stamps readings with its own clock.
+    if state.settings.source == DataSourceKind::Local
+        && let Some(clock_skew) = state.freshness.clock_skew()
+        && freshness.is_skewed(clock_skew)
+    {
+        warn(clock_offset(clock_skew, "clock-skew", localizer));
+    }
+    if let Some(event) = state.event_detectors.get(&sensor_data.sensor_id).and_then(EventDetector::ongoing) {
+        warn(localizer.format("event-ongoing", [("kind", localizer.name(&event.kind).into()), ("peak", localizer.number(event.peak_pm2_5, 1).into())]));
+    }
+
+    page.push_in_place(aqi_container.component());
+    if state.settings.concentrations {
+        page.push_in_place(concentrations(sensor_data, layout, palette, localizer).component());
+    }
+    page.push_in_place(common_measurements(sensor_data, state.settings.temperature_unit, layout, palette, localizer).component());
+    if state.settings.comfort_metrics {
+        page.push_in_place(comfort_metrics(sensor_data, state.settings.temperature_unit, layout, palette, localizer).component());
+    }
+    page
+}
+
+/// A bar per recent reading, as tall as its channel A PM2.5 AQI and colored by its category.
+fn chart_page(state: &PurpleAir, layout: Layout, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let samples = state.history.samples(state.reading_kind);
+    let recent: Vec<&Sample> = samples.iter().skip(samples.len().saturating_sub(CHART_SAMPLES)).collect();
+    let page = column()
+        .gap(layout.gap(15))
+        .push(Text::new(localizer.format("chart-title", [("summary", reading_summary(state).into())]).as_str()).font_size(layout.font(20.0)).font_weight(Weight::BOLD));
+
+    let (Some(first), Some(last)) = (recent.first(), recent.last()) else {
+        return page.push(Text::new(localizer.text("chart-empty").as_str()).color(color(palette.muted)));
+    };
+
+    // Scale to at least the top of the Moderate category so clean air does not fill the chart.
+    let chart_height = 300.0;
+    let bar_width = 600.0 / CHART_SAMPLES as f32;
+    let max_aqi = recent.iter().filter_map(|sample| sample.sensor_data.pm2_5_aqi).fold(100.0, f64::max);
+
+    let mut bars = row()
+        .align_items(AlignItems::End)
+        .gap(layout.gap(2))
+        .height(layout.px(chart_height).as_str());
+    for sample in &recent {
+        let aqi = sample.sensor_data.pm2_5_aqi.unwrap_or(0.0);
+        let bar_color = color(palette.category(AqiCategory::from_aqi(aqi)));
+        let height = (aqi / max_aqi) as f32 * chart_height;
+        bars.push_in_place(
+            Container::new()
+                .width(layout.px(bar_width).as_str())
+                .height(layout.px(height.max(1.0)).as_str())
+                .background(bar_color)
+                .component(),
+        );
+    }
+
+    // A strip under the bars marks the readings taken during an event.
+    let events: Vec<ParticleEvent> = state.sensor_data.as_ref()
+        .map(|sensor_data| sensor_events(state, sensor_data.sensor_id.as_str()))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|event| event.start <= last.received_at && event.end.is_none_or(|end| end >= first.received_at))
+        .collect();
+    let mut strip = row().gap(layout.gap(2));
+    for sample in &recent {
+        let marked = events.iter().any(|event| event.covers(sample.received_at));
+        strip.push_in_place(
+            Container::new()
+                .width(layout.px(bar_width).as_str())
+                .height(layout.px(6.0).as_str())
+                .background(color(if marked { palette.accent } else { palette.border }))
+                .component(),
+        );
+    }
+
+    let time = |sample: &Sample| localizer.short_time(&sample.received_at.with_timezone(&chrono::Local));
+    let mut page = page
+        .push(Text::new(localizer.format("chart-max", [("aqi", max_aqi.round().into())]).as_str()).font_size(layout.font(14.0)).color(color(palette.muted)))
+        .push(bars)
+        .push(strip)
+        .push(
+            row()
+                .justify_content(JustifyContent::SpaceBetween)
+                .width(layout.px(600.0 + CHART_SAMPLES as f32 * 2.0).as_str())
+                .push(Text::new(time(first).as_str()).font_size(layout.font(14.0)).color(color(palette.muted)))
+                .push(Text::new(time(last).as_str()).font_size(layout.font(14.0)).color(color(palette.muted)))
+        );
+    for event in &events {
+        page.push_in_place(Text::new(describe_event(event, localizer).as_str()).font_size(layout.font(14.0)).color(color(palette.accent)).component());
+    }
+    page
+}
+
+fn uptime(seconds: u64, localizer: &Localizer) -> String {
+    localizer.format("uptime", [("days", (seconds / 86_400).into()), ("hours", (seconds % 86_400 / 3600).into()), ("minutes", (seconds % 3600 / 60).into())])
+}
+
+/// The device details and how well the data source and webhooks have been doing.
+fn diagnostics_page(state: &PurpleAir, sensor_data: &LocalSensorData, layout: Layout, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let mut page = column()
+        .gap(layout.gap(12))
+        .push(Text::new(localizer.text("diagnostics-title").as_str()).font_size(layout.font(30.0)).font_weight(Weight::BOLD));
+    let mut push_field = |label: &str, value: &str| {
+        page.push_in_place(field(localizer.text(label).as_str(), value, layout, localizer).color(color(palette.muted)).component())
+    };
+
+    push_field("diagnostics-sensor", format!("{} ({})", sensor_data.geo, sensor_data.sensor_id).as_str());
+    push_field("diagnostics-firmware", sensor_data.version.as_str());
+    push_field("diagnostics-hardware", sensor_data.hardware_version.as_str());
+    push_field("diagnostics-devices", hardware_on_the_board(sensor_data.hardware_discovered.clone()).join(", ").as_str());
+    push_field("diagnostics-uptime", uptime(sensor_data.uptime, localizer).as_str());
+    let mut sensor_time = localizer.time(&sensor_data.date_time.with_timezone(&chrono::Local));
+    if state.settings.source == DataSourceKind::Local && let Some(clock_skew) = state.freshness.clock_skew() {
+        sensor_time = format!("{} ({})", sensor_time, clock_offset(clock_skew, "clock-offset", localizer));
+    }
+    push_field("diagnostics-sensor-time", sensor_time.as_str());
+    push_field("diagnostics-wifi", format!("{} dBm", sensor_data.rssi).as_str());
+    push_field("diagnostics-memory", localizer.format("memory-value", [("bytes", sensor_data.mem.into())]).as_str());
+    push_field(
+        "diagnostics-uploads",
+        localizer.format("uploads-value", [("succeeded", sensor_data.http_success.into()), ("sent", sensor_data.http_sends.into())]).as_str(),
+    );
+
+    if let Some(metadata) = &state.source_metadata {
+        push_field("diagnostics-source", format!("{} ({})", localizer.name(&metadata.kind), metadata.location).as_str());
+    }
+
+    let health = &state.source_health;
+    push_field(
+        "diagnostics-readings",
+        localizer.format("readings-value", [("received", health.total_readings.into()), ("failed", health.total_failures.into())]).as_str(),
+    );
+    if let Some(error) = &health.last_error {
+        push_field("diagnostics-last-error", error.as_str());
+    }
+
+    if let Some(drift) = state.drift.as_ref().filter(|drift| drift.sensor_id == sensor_data.sensor_id) {
+        let channels = match drift.weekly_change {
+            Some(change) => localizer.format("drift-trend", [
+                ("ratio", localizer.number(drift.ratios.last().map_or(1.0, |(_, ratio)| *ratio), 2).into()),
+                ("change", localizer.number(change, 3).into()),
+                ("weeks", drift::DRIFT_WEEKS.into()),
+            ]),
+            None => localizer.format("drift-no-trend", [("days", drift.ratios.len().into()), ("needed", drift::MIN_TREND_DAYS.into())]),
+        };
+        push_field("diagnostics-channels", channels.as_str());
+        if let Some(maintenance) = drift.maintenance() {
+            push_field("diagnostics-maintenance", localizer.name(&maintenance).as_str());
+        }
+        for finding in &drift.findings {
+            let message = match finding {
+                DriftFinding::Drift { channel, change } => {
+                    localizer.format("drift-drift", [("channel", localizer.name(channel).into()), ("percent", localizer.number(change * 100.0, 0).into())])
+                }
+                DriftFinding::NoCounts { channel } => localizer.format("drift-no-counts", [("channel", localizer.name(channel).into())]),
+                DriftFinding::Saturated { channel } => localizer.format("drift-saturated", [("channel", localizer.name(channel).into())]),
+            };
+            page.push_in_place(Text::new(message.as_str()).font_size(layout.font(16.0)).color(color(palette.accent)).component());
+        }
+    }
+
+    if state.deliveries.recent().next().is_some() {
+        page.push_in_place(Text::new(localizer.text("diagnostics-webhooks").as_str()).font_size(layout.font(20.0)).color(color(palette.muted)).component());
+        for delivery in state.deliveries.recent().take(DIAGNOSTICS_DELIVERIES) {
+            let failed = matches!(delivery.outcome, DeliveryOutcome::Failed(_));
+            let text_color = if failed { palette.accent } else { palette.muted };
+            page.push_in_place(Text::new(delivery.describe(localizer).as_str()).font_size(layout.font(14.0)).color(color(text_color)).component());
+        }
+    }
+
+    page
+}
+
+/// The size of the map in pixels.
+fn map_size(layout: Layout) -> (f64, f64) {
+    let (width, height) = if layout.portrait { (650.0, 800.0) } else { (950.0, 520.0) };
+    ((width * layout.scale) as f64, (height * layout.scale) as f64)
+}
+
+/// The configured sensors that reported a location, with their latest reading.
+fn map_markers(state: &PurpleAir) -> Vec<(&SensorSettings, &LocalSensorData)> {
+    let settings = &state.settings;
+    settings.sensors.iter().enumerate()
+        .filter_map(|(index, sensor)| {
+            let sensor_data = if index == settings.active_sensor { state.sensor_data.as_ref() } else { state.other_sensors.get(&sensor.url) }?;
+            // Sensors that were never given a location report 0, 0.
+            (sensor_data.lat != 0.0 || sensor_data.lon != 0.0).then_some((sensor, sensor_data))
+        })
+        .collect()
+}
+
+fn map_viewport(state: &PurpleAir, layout: Layout) -> Option<Viewport> {
+    let points: Vec<(f64, f64)> = map_markers(state).iter().map(|(_, sensor_data)| (sensor_data.lat, sensor_data.lon)).collect();
+    let (width, height) = map_size(layout);
+    Viewport::fit(&points, width, height, state.settings.map.zoom)
+}
+
+/// The color of a sensor's marker from its channel A PM2.5 AQI, the text next to it and what screen readers say for it.
+fn map_marker(sensor: &SensorSettings, sensor_data: &LocalSensorData, localizer: &Localizer, palette: &Palette) -> (Rgb, String, String) {
+    match sensor_data.pm2_5_aqi {
+        Some(aqi) => {
+            let category = AqiCategory::from_aqi(aqi);
+            let spoken = localizer.format("map-marker-spoken", [
+                ("sensor", sensor.label().into()),
+                ("place", sensor_data.place.as_str().into()),
+                ("aqi", aqi.round().into()),
+                ("category", localizer.name(&category).into()),
+            ]);
+            (palette.category(category), localizer.format("map-marker", [("sensor", sensor.label().into()), ("aqi", aqi.round().into())]), spoken)
+        }
+        None => (palette.muted, sensor.label().to_string(), sensor.label().to_string()),
+    }
+}
+
+/// The configured sensors on a map, each colored by its channel A PM2.5 AQI. Indoor sensors are square.
+fn map_page(state: &PurpleAir, layout: Layout, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let page = column()
+        .gap(layout.gap(12))
+        .push(Text::new(localizer.text("map-title").as_str()).font_size(layout.font(30.0)).font_weight(Weight::BOLD));
+
+    let Some(viewport) = map_viewport(state, layout) else {
+        return page.push(Text::new(localizer.text("map-no-locations").as_str()).color(color(palette.muted)));
+    };
+
+    page.push(map_view(state, viewport, layout, palette))
+}
+
+/// The markers over the map tiles, with the tile provider's attribution below.
+#[cfg(feature = "map-tiles")]
+fn map_view(state: &PurpleAir, viewport: Viewport, layout: Layout, palette: &Palette) -> Container {
+    let px = |pixels: f64| format!("{}px", pixels.round());
+    let mut map = Container::new()
+        .position(Position::Relative)
+        .overflow(Overflow::Hidden)
+        .width(px(viewport.width))
+        .height(px(viewport.height))
+        .background(color(palette.border));
+    for (tile, x, y) in viewport.tiles() {
+        if let Some(Some(path)) = state.map_tiles.get(&tile) {
+            map.push_in_place(
+                Image::new(ResourceIdentifier::File(path.clone()))
+                    .position(Position::Absolute)
+                    .inset(px(y), "auto", "auto", px(x))
+                    .width(px(TILE_SIZE))
+                    .height(px(TILE_SIZE))
+                    .component(),
+            );
+        }
+    }
+
+    let size = 24.0 * layout.scale as f64;
+    for (sensor, sensor_data) in map_markers(state) {
+        let (x, y) = viewport.project(sensor_data.lat, sensor_data.lon);
+        let indoor = sensor_data.place == "inside";
+        let radius = if indoor { 3.0 } else { size as f32 / 2.0 };
+        let (fill, label, spoken) = map_marker(sensor, sensor_data, &state.localizer, palette);
+
+        map.push_in_place(
+            accessible(Container::new(), spoken.as_str(), "")
+                .position(Position::Absolute)
+                .inset(px(y - size / 2.0), "auto", "auto", px(x - size / 2.0))
+                .width(px(size))
+                .height(px(size))
+                .border_width("2px", "2px", "2px", "2px")
+                .border_color(color(fill.contrasting_text()))
+                .border_radius(radius, radius, radius, radius)
+                .background(color(fill))
+                .component(),
+        );
+        map.push_in_place(
+            Text::new(label.as_str())
+                .position(Position::Absolute)
+                .inset(px(y - size / 2.0), "auto", "auto", px(x + size * 0.75))
+                .padding("2px", "6px", "2px", "6px")
+                .font_size(layout.font(14.0))
+                .color(color(palette.text))
+                .background(color(palette.background))
+                .component(),
+        );
+    }
+
+    column()
+        .gap(layout.gap(12))
+        .push(map)
+        .push(Text::new(state.settings.map.attribution.as_str()).font_size(layout.font(12.0)).color(color(palette.muted)))
+}
+
+/// Without the tile overlay, the markers are listed from west to east instead.
+#[cfg(not(feature = "map-tiles"))]
+fn map_view(state: &PurpleAir, viewport: Viewport, layout: Layout, palette: &Palette) -> Container {
+    let mut markers = map_markers(state);
+    markers.sort_by(|(_, a), (_, b)| viewport.project(a.lat, a.lon).0.total_cmp(&viewport.project(b.lat, b.lon).0));
+
+    let size = 24.0 * layout.scale;
+    let mut list = column().gap(layout.gap(8));
+    for (sensor, sensor_data) in markers {
+        let radius = if sensor_data.place == "inside" { 3.0 } else { size / 2.0 };
+        let (fill, label, spoken) = map_marker(sensor, sensor_data, &state.localizer, palette);
+        list.push_in_place(
+            row()
+                .gap(layout.gap(8))
+                .align_items(AlignItems::Center)
+                .push(
+                    accessible(Container::new(), spoken.as_str(), "")
+                        .width(layout.px(24.0).as_str())
+                        .height(layout.px(24.0).as_str())
+                        .border_width("2px", "2px", "2px", "2px")
+                        .border_color(color(fill.contrasting_text()))
+                        .border_radius(radius, radius, radius, radius)
+                        .background(color(fill)),
+                )
+                .push(Text::new(label.as_str()).font_size(layout.font(14.0)).color(color(palette.text)))
+                .component(),
+        );
+    }
+    list
+}
+
+/// The indoor sensor next to the outdoor one: how much of the outdoor PM2.5 gets in, how long it takes and whether to open the windows.
+fn comparison_page(state: &PurpleAir, layout: Layout, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let page = column()
+        .gap(layout.gap(12))
+        .push(Text::new(localizer.text("comparison-title").as_str()).font_size(layout.font(30.0)).font_weight(Weight::BOLD));
+
+    let Some(comparison) = state.indoor_outdoor.pair() else {
+        return page.push(Text::new(localizer.text("comparison-no-pair").as_str()).color(color(palette.muted)));
+    };
+    let Some((indoor, outdoor)) = comparison.latest() else {
+        return page.push(Text::new(localizer.text("comparison-waiting").as_str()).color(color(palette.muted)));
+    };
+
+    let label = |url: &str| state.settings.sensors.iter().find(|sensor| sensor.url == url).map_or(url.to_string(), |sensor| sensor.label().to_string());
+    let reading = |url: &str, point: &Point| {
+        localizer.format("comparison-reading", [
+            ("sensor", label(url).into()),
+            ("pm2_5", localizer.number(point.pm2_5, 1).into()),
+            ("aqi", point.aqi.round().into()),
+        ])
+    };
+    let ratios = comparison.ratios();
+    let ratio = match ratios.last() {
+        Some((time, ratio)) if *time == indoor.time => localizer.number(*ratio, 2),
+        _ => localizer.text("comparison-ratio-unknown"),
+    };
+
+    let mut page = page
+        .push(field(localizer.text("comparison-indoor").as_str(), reading(comparison.indoor_url, indoor).as_str(), layout, localizer))
+        .push(field(localizer.text("comparison-outdoor").as_str(), reading(comparison.outdoor_url, outdoor).as_str(), layout, localizer))
+        .push(field(localizer.text("comparison-ratio").as_str(), ratio.as_str(), layout, localizer));
+
+    let (advice, background) = match Recommendation::new(indoor, outdoor) {
+        Recommendation::KeepWindowsClosed => ("comparison-keep-closed", palette.category(AqiCategory::from_aqi(outdoor.aqi))),
+        Recommendation::SafeToVentilate => ("comparison-ventilate", palette.category(AqiCategory::Good)),
+    };
+    page.push_in_place(
+        row().push(
+            announced(accessible(Container::new(), localizer.text(advice).as_str(), localizer.attribute(advice, "description").as_str()))
+                .padding("8px", "16px", "8px", "16px")
+                .border_radius(8.0, 8.0, 8.0, 8.0)
+                .background(color(background))
+                .push(Text::new(localizer.text(advice).as_str()).font_size(layout.font(24.0)).font_weight(Weight::BOLD).color(color(background.contrasting_text())))
+        ).component(),
+    );
+
+    let lag = match comparison.lag() {
+        Some(lag) if lag.is_zero() => localizer.text("comparison-lag-immediate"),
+        Some(lag) => localizer.format("comparison-lag", [("duration", localizer.duration(lag.num_seconds().unsigned_abs()).into())]),
+        None => localizer.text("comparison-lag-unknown"),
+    };
+    page.push_in_place(Text::new(lag.as_str()).font_size(layout.font(20.0)).component());
+
+    let recent = &ratios[ratios.len().saturating_sub(CHART_SAMPLES)..];
+    let (Some(first), Some(last)) = (recent.first(), recent.last()) else {
+        return page;
+    };
+
+    // Scaled to at least a ratio of 1, so the bars show at a glance how much of the outdoor air gets in.
+    let chart_height = 200.0;
+    let bar_width = 600.0 / CHART_SAMPLES as f32;
+    let max_ratio = recent.iter().map(|(_, ratio)| *ratio).fold(1.0, f64::max);
+    let mut bars = row()
+        .align_items(AlignItems::End)
+        .gap(layout.gap(2))
+        .height(layout.px(chart_height).as_str());
+    for (_, ratio) in recent {
+        let bar_color = if *ratio > 1.0 { palette.accent } else { palette.muted };
+        let height = (ratio / max_ratio) as f32 * chart_height;
+        bars.push_in_place(
+            Container::new()
+                .width(layout.px(bar_width).as_str())
+                .height(layout.px(height.max(1.0)).as_str())
+                .background(color(bar_color))
+                .component(),
+        );
+    }
+
+    let time = |time: &chrono::DateTime<chrono::Utc>| localizer.short_time(&time.with_timezone(&chrono::Local));
+    page.push(Text::new(localizer.format("comparison-chart", [("max", localizer.number(max_ratio, 1).into())]).as_str()).font_size(layout.font(14.0)).color(color(palette.muted)))
+        .push(bars)
+        .push(
+            row()
+                .justify_content(JustifyContent::SpaceBetween)
+                .width(layout.px(600.0 + CHART_SAMPLES as f32 * 2.0).as_str())
+                .push(Text::new(time(&first.0).as_str()).font_size(layout.font(14.0)).color(color(palette.muted)))
+                .push(Text::new(time(&last.0).as_str()).font_size(layout.font(14.0)).color(color(palette.muted)))
+        )
+}
+
+/// A day's or week's summary of a sensor from the archive, with buttons to pick another and to export it.
+fn reports_page(state: &PurpleAir, layout: Layout, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let selection = report_selection(state);
+    let report = state.report.as_ref().filter(|report| {
+        selection.as_ref().is_some_and(|(sensor_id, period, date)| report.sensor_id == *sensor_id && report.period == *period && report.first == period.span(*date).0)
+    });
+
+    let title = report.map_or(localizer.text("reports-title"), |report| report.title(localizer));
+    let mut page = column()
+        .gap(layout.gap(12))
+        .push(Text::new(title.as_str()).font_size(layout.font(30.0)).font_weight(Weight::BOLD));
+
+    let mut controls = row()
+        .gap(15)
+        .push(button(localizer.name(&state.report_period).as_str(), "report-period", palette))
+        .push(button(localizer.text("report-earlier").as_str(), "report-earlier", palette))
+        .push(button(localizer.text("report-later").as_str(), "report-later", palette));
+    if state.archived_sensors.len() > 1 && let Some((sensor_id, _, _)) = &selection {
+        controls.push_in_place(button(sensor_label(&state.settings, sensor_id.as_str()).as_str(), "archive-sensor", palette).component());
+    }
+    for format in ReportFormat::ALL {
+        let label = localizer.format("report-export", [("format", localizer.name(&format).into())]);
+        controls.push_in_place(button(label.as_str(), format!("report-export-{}", format.extension()).as_str(), palette).component());
+    }
+    page.push_in_place(controls.component());
+
+    if let Some(status) = &state.report_status {
+        page.push_in_place(Text::new(status.as_str()).font_size(layout.font(16.0)).color(color(palette.muted)).component());
+    }
+
+    let Some(report) = report else {
+        let message = if selection.is_some() { "report-loading" } else { "report-no-sensor" };
+        return page.push(Text::new(localizer.text(message).as_str()).color(color(palette.muted)));
+    };
+    if report.readings == 0 {
+        return page.push(Text::new(localizer.text("report-no-readings").as_str()).color(color(palette.muted)));
+    }
+
+    for (label, value) in report.fields(localizer, state.settings.temperature_unit) {
+        page.push_in_place(field(label.as_str(), value.as_str(), layout, localizer).component());
+    }
+
+    page.push_in_place(Text::new(localizer.text("report-category-hours").as_str()).font_size(layout.font(20.0)).font_weight(Weight::BOLD).component());
+    let longest = report.category_time.iter().max().copied().unwrap_or_default().num_seconds().max(1) as f32;
+    for ((category, name, hours), time) in report.category_hours(localizer).into_iter().zip(report.category_time) {
+        let width = time.num_seconds() as f32 / longest * 400.0;
+        page.push_in_place(
+            row()
+                .align_items(AlignItems::Center)
+                .gap(layout.gap(12))
+                .push(Text::new(name.as_str()).font_size(layout.font(16.0)).width(layout.px(260.0).as_str()))
+                .push(Container::new().width(layout.px(width.max(1.0)).as_str()).height(layout.px(16.0).as_str()).background(color(palette.category(category))))
+                .push(Text::new(hours.as_str()).font_size(layout.font(16.0)).color(color(palette.muted)))
+                .component(),
+        );
+    }
+
+    page
+}
+
+/// A year of daily AQI, one square per day colored by its category. Picking a day charts it below the calendar.
+fn calendar_page(state: &PurpleAir, layout: Layout, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let sensor_id = archive_sensor(state);
+    let title = sensor_id.as_ref().map_or(localizer.text("calendar-heading"), |sensor_id| {
+        localizer.format("calendar-title", [("sensor", sensor_label(&state.settings, sensor_id.as_str()).into())])
+    });
+    let mut page = column()
+        .gap(layout.gap(12))
+        .push(Text::new(title.as_str()).font_size(layout.font(30.0)).font_weight(Weight::BOLD));
+
+    let mut controls = row()
+        .gap(15)
+        .push(button(localizer.name(&state.daily_statistic).as_str(), "calendar-statistic", palette));
+    if state.archived_sensors.len() > 1 && let Some(sensor_id) = &sensor_id {
+        controls.push_in_place(button(sensor_label(&state.settings, sensor_id.as_str()).as_str(), "archive-sensor", palette).component());
+    }
+    page.push_in_place(controls.component());
+
+    let Some(calendar) = state.calendar.as_ref().filter(|calendar| Some(&calendar.sensor_id) == sensor_id.as_ref()) else {
+        let message = if sensor_id.is_some() { "calendar-loading" } else { "report-no-sensor" };
+        return page.push(Text::new(localizer.text(message).as_str()).color(color(palette.muted)));
+    };
+
+    let cell = layout.px(14.0);
+    let small = |text: &str| Text::new(text).font_size(layout.font(11.0)).color(color(palette.muted)).height(cell.as_str());
+    let weeks = Calendar::weeks(chrono::Local::now().date_naive());
+
+    // Mondays, Wednesdays and Fridays are labelled, like on a wall calendar's side.
+    let mut weekdays = column().gap(layout.gap(3)).push(small(""));
+    for (index, date) in weeks.first().map(|week| week.as_slice()).unwrap_or_default().iter().enumerate() {
+        weekdays.push_in_place(small(if index % 2 == 0 && index < 6 { localizer.short_weekday(*date) } else { String::new() }.as_str()).component());
+    }
+    let mut grid = row().gap(layout.gap(3)).push(weekdays);
+
+    for (index, week) in weeks.iter().enumerate() {
+        // A week is labelled with the month that starts in it.
+        let month = week.iter().find(|date| date.day() == 1).or(week.first().filter(|_| index == 0));
+        let mut column_of_days = column().gap(layout.gap(3)).push(small(month.map(|date| localizer.short_month(*date)).unwrap_or_default().as_str()));
+        for date in week {
+            let daily = calendar.days.get(date).map(|daily| daily.get(state.daily_statistic));
+            let (fill, name) = match daily {
+                Some(aqi) => {
+                    let category = AqiCategory::from_aqi(aqi);
+                    let name = localizer.format("calendar-day-spoken", [
+                        ("date", localizer.day(*date).into()),
+                        ("statistic", localizer.name(&state.daily_statistic).into()),
+                        ("aqi", aqi.round().into()),
+                        ("category", localizer.name(&category).into()),
+                    ]);
+                    (palette.category(category), name)
+                }
+                None => (palette.border, localizer.format("calendar-day-empty", [("date", localizer.day(*date).into())])),
+            };
+            let mut day = accessible(Container::new(), name.as_str(), "")
+                .id(format!("calendar-{}", date.format("%Y-%m-%d")).as_str())
+                .width(cell.as_str())
+                .height(cell.as_str())
+                .border_radius(2.0, 2.0, 2.0, 2.0)
+                .background(color(fill));
+            if state.calendar_day == Some(*date) {
+                day = day.border_width("2px", "2px", "2px", "2px").border_color(color(palette.text));
+            }
+            column_of_days.push_in_place(day.component());
+        }
+        grid.push_in_place(column_of_days.component());
+    }
+    page.push_in_place(grid.component());
+
+    let mut legend = row().align_items(AlignItems::Center).gap(layout.gap(8));
+    for category in AqiCategory::ALL {
+        legend.push_in_place(Container::new().width(cell.as_str()).height(cell.as_str()).background(color(palette.category(category))).component());
+        legend.push_in_place(Text::new(localizer.short_name(&category).as_str()).font_size(layout.font(12.0)).color(color(palette.muted)).component());
+    }
+    legend.push_in_place(Container::new().width(cell.as_str()).height(cell.as_str()).background(color(palette.border)).component());
+    legend.push_in_place(Text::new(localizer.text("calendar-no-data").as_str()).font_size(layout.font(12.0)).color(color(palette.muted)).component());
+    page.push_in_place(legend.component());
+
+    if let Some(date) = state.calendar_day {
+        page.push_in_place(day_chart(state, date, layout, palette).component());
+    }
+    page
+}
+
+/// The AQI over the day picked on the calendar.
+fn day_chart(state: &PurpleAir, date: chrono::NaiveDate, layout: Layout, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let title = format!("{} {}", localizer.short_weekday(date), localizer.day(date));
+    let mut chart = column()
+        .gap(layout.gap(8))
+        .push(
+            row()
+                .align_items(AlignItems::Center)
+                .gap(15)
+                .push(Text::new(title.as_str()).font_size(layout.font(20.0)).font_weight(Weight::BOLD))
+                .push(button(localizer.text("calendar-close-day").as_str(), "calendar-close-day", palette))
+        );
+
+    let sensor_id = archive_sensor(state);
+    let Some(profile) = state.day_profile.as_ref().filter(|profile| profile.date == date && Some(&profile.sensor_id) == sensor_id.as_ref()) else {
+        return chart.push(Text::new(localizer.text("calendar-loading").as_str()).color(color(palette.muted)));
+    };
+    if profile.bins.iter().all(Option::is_none) {
+        return chart.push(Text::new(localizer.text("report-no-readings").as_str()).color(color(palette.muted)));
+    }
+    if let Some(daily) = state.calendar.as_ref().and_then(|calendar| calendar.days.get(&date)) {
+        let summary = localizer.format("calendar-day-summary", [("mean", daily.mean.round().into()), ("max", daily.max.round().into())]);
+        chart.push_in_place(Text::new(summary.as_str()).font_size(layout.font(16.0)).color(color(palette.muted)).component());
+    }
+
+    // Scaled like the live chart, to at least the top of the Moderate category.
+    let chart_height = 160.0;
+    let bar_width = 600.0 / profile.bins.len() as f32;
+    let max_aqi = profile.bins.iter().flatten().copied().fold(100.0, f64::max);
+    let mut bars = row()
+        .align_items(AlignItems::End)
+        .gap(layout.gap(1))
+        .height(layout.px(chart_height).as_str());
+    for aqi in &profile.bins {
+        let (height, fill) = match aqi {
+            Some(aqi) => ((aqi / max_aqi) as f32 * chart_height, palette.category(AqiCategory::from_aqi(*aqi))),
+            None => (0.0, palette.border),
+        };
+        bars.push_in_place(
+            Container::new()
+                .width(layout.px(bar_width).as_str())
+                .height(layout.px(height.max(1.0)).as_str())
+                .background(color(fill))
+                .component(),
+        );
+    }
+
+    // Marks the bins that overlap a recorded event.
+    let mut strip = row().gap(layout.gap(1));
+    for bin in 0..profile.bins.len() as u32 {
+        let minutes = bin * calendar::DAY_BIN_MINUTES;
+        let start = date.and_hms_opt(minutes / 60, minutes % 60, 0).and_then(|time| time.and_local_timezone(chrono::Local).earliest());
+        let marked = start.is_some_and(|start| {
+            let end = start.to_utc() + chrono::TimeDelta::minutes(calendar::DAY_BIN_MINUTES as i64);
+            profile.events.iter().any(|event| event.start < end && event.end.is_none_or(|event_end| event_end >= start.to_utc()))
+        });
+        strip.push_in_place(
+            Container::new()
+                .width(layout.px(bar_width).as_str())
+                .height(layout.px(6.0).as_str())
+                .background(color(if marked { palette.accent } else { palette.border }))
+                .component(),
+        );
+    }
+
+    let mut hours = row()
+        .justify_content(JustifyContent::SpaceBetween)
+        .width(layout.px(600.0 + profile.bins.len() as f32).as_str());
+    for hour in [0, 6, 12, 18] {
+        let time = date.and_hms_opt(hour, 0, 0).and_then(|time| time.and_local_timezone(chrono::Local).earliest());
+        let label = time.map(|time| localizer.short_time(&time)).unwrap_or_default();
+        hours.push_in_place(Text::new(label.as_str()).font_size(layout.font(14.0)).color(color(palette.muted)).component());
+    }
+    chart.push_in_place(bars.component());
+    chart.push_in_place(strip.component());
+    chart.push_in_place(hours.component());
+    for event in &profile.events {
+        chart.push_in_place(Text::new(describe_event(event, localizer).as_str()).font_size(layout.font(14.0)).color(color(palette.accent)).component());
+    }
+    chart
+}
+
+fn navigation(palette: &Palette, localizer: &Localizer) -> Container {
+    let shortcut = |label: &str, id: &str, keys: &str| {
+        let text = localizer.text(label);
+        accessible(button(text.as_str(), id, palette), text.as_str(), localizer.format("keyboard-shortcut", [("keys", keys.into())]).as_str())
+    };
+    row()
+        .gap(15)
+        .push(shortcut("nav-current", "page-current", "1"))
+        .push(shortcut("nav-chart", "page-chart", "2"))
+        .push(shortcut("nav-diagnostics", "page-diagnostics", "3"))
+        .push(shortcut("nav-map", "page-map", "4"))
+        .push(shortcut("nav-comparison", "page-comparison", "5"))
+        .push(shortcut("nav-reports", "page-reports", "6"))
+        .push(shortcut("nav-calendar", "page-calendar", "7"))
+        .push(shortcut("nav-find-sensors", "discover", "F"))
+        .push(shortcut("nav-settings", "settings", "S"))
+}
+
+/// The reading pages in the order the arrow keys move through them.
+#[cfg(feature = "accessibility")]
+const READING_PAGES: [Page; 7] = [Page::Sensor, Page::Chart, Page::Diagnostics, Page::Map, Page::Comparison, Page::Reports, Page::Calendar];
+
+/// Moves between pages with the keyboard. Returns true if the key was handled.
+#[cfg(feature = "accessibility")]
+fn navigate_with_key(context: &mut Context<PurpleAir>, key: &Key) -> bool {
+    let page = context.state().page;
+    let step = |forward: bool| {
+        let index = READING_PAGES.iter().position(|reading_page| *reading_page == page).unwrap_or(0);
+        let count = READING_PAGES.len();
+        READING_PAGES[if forward { (index + 1) % count } else { (index + count - 1) % count }]
+    };
+
+    let next = match key {
+        Key::Named(NamedKey::ArrowRight | NamedKey::PageDown) if READING_PAGES.contains(&page) => step(true),
+        Key::Named(NamedKey::ArrowLeft | NamedKey::PageUp) if READING_PAGES.contains(&page) => step(false),
+        Key::Named(NamedKey::Escape) => Page::Sensor,
+        Key::Character(character) => match character.to_lowercase().as_str() {
+            "1" => Page::Sensor,
+            "2" => Page::Chart,
+            "3" => Page::Diagnostics,
+            "4" => Page::Map,
+            "5" => Page::Comparison,
+            "6" => Page::Reports,
+            "7" => Page::Calendar,
+            "s" => Page::Settings,
+            "f" => {
+                context.state_mut().page = Page::Discovery;
+                start_discovery(context);
+                return true;
+            }
+            _ => return false,
+        },
+        _ => return false,
+    };
+
+    context.state_mut().page = next;
+    match next {
+        Page::Map => show_map(context),
+        Page::Diagnostics => show_diagnostics(context),
+        Page::Reports => show_reports(context),
+        Page::Calendar => show_calendar(context),
+        _ => {}
+    }
+    true
+}
+
+fn discovery_page(state: &PurpleAir, palette: &Palette) -> Container {
+    let localizer = &state.localizer;
+    let mut page = column()
+        .gap(15)
+        .push(Text::new(localizer.text("discovery-title").as_str()).font_size(30.0).font_weight(Weight::BOLD));
+
+    match &state.discovery {
+        _ if state.discovering => page.push_in_place(Text::new(localizer.text("discovery-scanning").as_str()).color(color(palette.muted)).component()),
+        Some(discovery) if discovery.sensors.is_empty() => page.push_in_place(Text::new(localizer.text("discovery-none").as_str()).color(color(palette.muted)).component()),
+        Some(discovery) => {
+            for (index, sensor) in discovery.sensors.iter().enumerate() {
+                let selected = state.settings.sensor().sensor_id.as_deref() == Some(sensor.sensor_data.sensor_id.as_str());
+                let label = if selected { localizer.format("discovery-selected", [("sensor", sensor.label(localizer).into())]) } else { sensor.label(localizer) };
+                page.push_in_place(row().push(button(label.as_str(), format!("sensor-{}", index).as_str(), palette)).component());
+            }
+        }
+        None => {}
+    }
+
+    page.push(
+        row()
+            .gap(15)
+            .push(button(localizer.text("discovery-scan-again").as_str(), "rescan", palette))
+            .push(button(localizer.text("nav-back").as_str(), "back", palette))
+    )
+}
+
+/// `label` is the catalog id of the setting's name.
+fn setting(label: &str, value: &str, id: &str, palette: &Palette, localizer: &Localizer) -> Container {
+    row()
+        .align_items(AlignItems::Center)
+        .gap(15)
+        .push(Text::new(localizer.text(label).as_str()).width("220px"))
+        .push(button(value, id, palette))
+}
+
+fn settings_page(state: &PurpleAir, palette: &Palette) -> Container {
+    let settings = &state.settings;
+    let localizer = &state.localizer;
+    let mut page = column()
+        .gap(15)
+        .push(Text::new(localizer.text("settings-title").as_str()).font_size(30.0).font_weight(Weight::BOLD));
+
+    let location = match &state.settings_path {
+        Some(path) => localizer.format("settings-saved-to", [("path", path.display().to_string().into())]),
+        None => localizer.text("settings-no-directory"),
+    };
+    page.push_in_place(Text::new(location.as_str()).color(color(palette.muted)).component());
+    if let Some(error) = &state.settings_error {
+        let message = localizer.format("settings-read-error", [("error", error.as_str().into())]);
+        page.push_in_place(Text::new(message.as_str()).color(color(palette.accent)).component());
+    }
+
+    page.push_in_place(Text::new(localizer.text("settings-sensors").as_str()).font_weight(Weight::BOLD).component());
+    for (index, sensor) in settings.sensors.iter().enumerate() {
+        let label = if index == settings.active_sensor {
+            localizer.format("settings-active-sensor", [("sensor", sensor.label().into())])
+        } else {
+            sensor.label().to_string()
+        };
+        let mut sensor_row = row().gap(15).push(button(label.as_str(), format!("active-sensor-{}", index).as_str(), palette));
+        if settings.sensors.len() > 1 {
+            sensor_row.push_in_place(button(localizer.text("settings-remove-sensor").as_str(), format!("remove-sensor-{}", index).as_str(), palette).component());
+        }
+        page.push_in_place(sensor_row.component());
+    }
+    page.push_in_place(row().push(button(localizer.text("settings-add-sensor").as_str(), "discover", palette)).component());
+
+    let alert_threshold = settings.notifications.categories.iter().min().copied().unwrap_or(AqiCategory::Good);
+    let on_off = |on: bool| localizer.text(if on { "on" } else { "off" });
+    let setting = |label: &str, value: String, id: &str| setting(label, value.as_str(), id, palette, localizer);
+    page.push(setting("setting-language", settings.language.name().to_string(), "setting-language"))
+        .push(setting("setting-source", localizer.name(&settings.source), "setting-source"))
+        .push(setting("setting-poll-interval", localizer.format("poll-interval-value", [("seconds", settings.poll_interval_seconds.into())]), "setting-poll-interval"))
+        .push(setting("setting-temperature", localizer.name(&settings.temperature_unit), "setting-temperature-unit"))
+        .push(setting("setting-comfort", on_off(settings.comfort_metrics), "setting-comfort"))
+        .push(setting("setting-aqi-scheme", localizer.name(&settings.aqi_scheme), "setting-aqi-scheme"))
+        .push(setting("setting-aqi-display", localizer.name(&settings.aqi_display), "setting-aqi-display"))
+        .push(setting("setting-concentrations", on_off(settings.concentrations), "setting-concentrations"))
+        .push(setting("setting-theme", localizer.name(&settings.theme), "setting-theme"))
+        .push(setting("setting-aqi-colors", localizer.text(if settings.colorblind_safe { "aqi-colors-colorblind" } else { "aqi-colors-epa" }), "setting-aqi-colors"))
+        .push(setting("setting-alerts", on_off(settings.notifications.enabled), "setting-alerts"))
+        .push(setting("setting-alert-from", localizer.name(&alert_threshold), "setting-alert-threshold"))
+        .push(setting("setting-stale-after", localizer.duration(settings.freshness.stale_after_seconds), "setting-stale-after"))
+        .push(setting("setting-night-mode", localizer.name(&settings.night.mode), "setting-night-mode"))
+        .push(setting("setting-at-night", localizer.text(if settings.night.blank { "night-blank" } else { "night-dim" }), "setting-night-blank"))
+        .push(setting("setting-daily-report", settings.reports.daily_export.map_or(localizer.text("off"), |format| localizer.name(&format)), "setting-daily-report"))
+        .push(row().push(button(localizer.text("nav-back").as_str(), "back", palette)))
+}
+
+/// Applies a click on one of the settings page buttons. Returns true if the data source has to be recreated.
+fn change_setting(state: &mut PurpleAir, id: &str) -> bool {
+    let settings = &mut state.settings;
+    let mut restart = false;
+
+    match id {
+        "setting-language" => {
+            settings.language = next_choice(&Language::ALL, settings.language);
+            state.update_localizer();
+        }
+        "setting-source" => {
+            settings.source = next_choice(&DataSourceKind::ALL, settings.source);
+            restart = true;
+        }
+        "setting-poll-interval" => {
+            settings.poll_interval_seconds = next_choice(&POLL_INTERVAL_CHOICES, settings.poll_interval_seconds);
+            restart = settings.source == DataSourceKind::Local;
+        }
+        "setting-temperature-unit" => settings.temperature_unit = next_choice(&TemperatureUnit::ALL, settings.temperature_unit),
+        "setting-comfort" => settings.comfort_metrics = !settings.comfort_metrics,
+        "setting-aqi-scheme" => {
+            settings.aqi_scheme = next_choice(&AqiScheme::ALL, settings.aqi_scheme);
+            // The history and the shown reading were converted with the previous scheme.
+            reset_readings(state);
+            restart = true;
+        }
+        "setting-aqi-display" => settings.aqi_display = next_choice(&AqiDisplay::ALL, settings.aqi_display),
+        "setting-concentrations" => settings.concentrations = !settings.concentrations,
+        "setting-stale-after" => settings.freshness.stale_after_seconds = next_choice(&STALE_AFTER_CHOICES, settings.freshness.stale_after_seconds),
+        "setting-theme" => settings.theme = next_choice(&Theme::ALL, settings.theme),
+        "setting-aqi-colors" => settings.colorblind_safe = !settings.colorblind_safe,
+        "setting-night-mode" => settings.night.mode = next_choice(&NightMode::ALL, settings.night.mode),
+        "setting-night-blank" => settings.night.blank = !settings.night.blank,
+        "setting-daily-report" => settings.reports.daily_export = next_choice(&DAILY_EXPORT_CHOICES, settings.reports.daily_export),
+        "setting-alerts" => settings.notifications.enabled = !settings.notifications.enabled,
+        "setting-alert-threshold" => {
+            let current = settings.notifications.categories.iter().min().copied().unwrap_or(AqiCategory::Good);
+            let threshold = next_choice(&AqiCategory::ALL, current);
+            settings.notifications.categories = AqiCategory::ALL.into_iter().filter(|category| *category >= threshold).collect();
+        }
+        _ => {
+            if let Some(index) = id.strip_prefix("active-sensor-").and_then(|index| index.parse::<usize>().ok())
+                && index < settings.sensors.len()
+                && index != settings.active_sensor
+            {
+                settings.active_sensor = index;
+                reset_readings(state);
+                restart = true;
+            } else if let Some(index) = id.strip_prefix("remove-sensor-").and_then(|index| index.parse::<usize>().ok())
+                && index < settings.sensors.len()
+                && settings.sensors.len() > 1
+            {
+                settings.sensors.remove(index);
+                if index == settings.active_sensor {
+                    settings.active_sensor = 0;
+                    reset_readings(state);
+                    restart = true;
+                } else if index < settings.active_sensor {
+                    settings.active_sensor -= 1;
+                }
+            } else {
+                return false;
+            }
+        }
+    }
+
+    state.save_settings();
+    restart
+}
+
+/// Forgets the readings of the previous sensor, or those converted with the previous AQI scheme.
+fn reset_readings(state: &mut PurpleAir) {
+    state.notifier = CategoryNotifier::default();
+    state.history.clear();
+    state.reading_kind = ReadingKind::Averaged;
+    state.sensor_data = None;
+    state.announcement = None;
+    state.freshness = FreshnessTracker::default();
+}
+
+/// Switches to a sensor picked from the discovery results and tracks it by its id from now on.
+/// The sensor is added to the known sensors unless it is one of them already.
+fn select_sensor(state: &mut PurpleAir, index: usize) {
+    let Some(sensor) = state.discovery.as_ref().and_then(|discovery| discovery.sensors.get(index)).cloned() else {
+        return;
+    };
+
+    let settings = &mut state.settings;
+    let sensor_id = sensor.sensor_data.sensor_id.clone();
+    match settings.sensors.iter().position(|known| known.sensor_id.as_deref() == Some(sensor_id.as_str())) {
+        Some(known) => settings.active_sensor = known,
+        None => {
+            settings.sensors.push(SensorSettings {
+                name: sensor.sensor_data.geo.clone(),
+                url: sensor.url.clone(),
+                sensor_id: Some(sensor_id),
+            });
+            settings.active_sensor = settings.sensors.len() - 1;
+        }
+    }
+    settings.source = DataSourceKind::Local;
+    settings.sensor_mut().url = sensor.url;
+    state.save_settings();
+
+    reset_readings(state);
+    let mut sensor_data = sensor.sensor_data;
+    state.settings.aqi_scheme.apply(&mut sensor_data);
+    state.sensor_data = Some(sensor_data);
+    state.page = Page::Sensor;
+}
+
 impl Component for PurpleAir {
     type GlobalState = ();
     type Props = ();
-    type Message = LocalSensorData;
+    type Message = SourceUpdate;
 
     fn view(context: &mut Context<Self>) -> ComponentSpecification {
+        let state = context.state();
+        let layout = Layout::new(&state.settings.window);
+        let (latitude, longitude, aqi) = state.sensor_data.as_ref().map_or((0.0, 0.0, None), |sensor_data| (sensor_data.lat, sensor_data.lon, sensor_data.pm2_5_aqi));
+        let display = state.settings.night.display_mode(chrono::Utc::now(), latitude, longitude, aqi);
+
+        // Settings and discovery stay visible at night, so the display can still be set up.
+        let display = match state.page {
+            Page::Settings | Page::Discovery if display == DisplayMode::Blank => DisplayMode::Dim,
+            _ => display,
+        };
+        if display == DisplayMode::Blank {
+            return column().width("100%").height("100%").background(Color::BLACK).component();
+        }
+
+        let palette = state.settings.theme.palette(&state.settings.custom_theme);
+        let palette = if state.settings.colorblind_safe { palette.colorblind_safe() } else { palette };
+        let palette = if display == DisplayMode::Dim { Palette::night(&palette) } else { palette };
+        let palette = &palette;
+
+        let padding = layout.px(25.0);
         let mut device_container = column()
-            .gap(20)
+            .gap(layout.gap(20))
             .border_width("2px", "2px", "2px", "2px")
-            .border_color(Color::from_rgb8(25, 27, 42))
+            .border_color(color(palette.border))
             .width("100%")
             .height("100%")
-            .padding("25px", "25px", "25px", "25px")
-            .background(Color::from_rgb8(35, 37, 52));
-        
-        if context.state().sensor_data.is_none() {
-            return device_container.push(Text::new("Failed to fetch the sensor data. Retrying...")).component();    
+            .padding(padding.as_str(), padding.as_str(), padding.as_str(), padding.as_str())
+            .color(color(palette.text))
+            .background(color(palette.background));
+
+        let localizer = &context.state().localizer;
+        if let Some(change) = &context.state().announcement {
+            let announcement = localizer.format("announcement", [
+                ("category", localizer.name(&change.to).into()),
+                ("aqi", change.aqi.round().into()),
+                ("previous", localizer.name(&change.from).into()),
+            ]);
+            device_container.push_in_place(
+                announced(Text::new(announcement.as_str()).font_size(layout.font(14.0)).color(color(palette.muted))).component(),
+            );
         }
         
-        let sensor_data = context.state().sensor_data.as_ref().unwrap();
+        if context.state().page == Page::Discovery {
+            return device_container.push(discovery_page(context.state(), palette)).component();
+        }
+
+        if context.state().page == Page::Settings {
+            return device_container.push(settings_page(context.state(), palette)).component();
+        }
 
-        let aqi_container = row().gap(25)
-            .push(aqi_a(&sensor_data))
-            .push(aqi_b(&sensor_data));
+        let kiosk = context.state().settings.kiosk.enabled;
+        let Some(sensor_data) = context.state().sensor_data.as_ref() else {
+            let status = match &context.state().source_health.last_error {
+                Some(error) => localizer.format("status-failed", [("error", error.as_str().into())]),
+                None => localizer.text("status-fetching"),
+            };
 
-        device_container.push_in_place(aqi_container.component());
-        device_container.push_in_place(common_measurements(&sensor_data).component());
-        
-        device_container.push_in_place(field("Firmware Version", sensor_data.version.as_str()).color(GRAY).component());
-        device_container.push_in_place(field("Hardware Version", sensor_data.hardware_version.as_str()).color(GRAY).component());
+            device_container.push_in_place(Text::new(status.as_str()).font_size(layout.font(20.0)).component());
+            if !kiosk {
+                device_container.push_in_place(
+                    row()
+                        .gap(15)
+                        .push(button(localizer.text("nav-find-sensors").as_str(), "discover", palette))
+                        .push(button(localizer.text("nav-settings").as_str(), "settings", palette))
+                        .component(),
+                );
+            }
+            return device_container.component();
+        };
 
-        let all_hardware = &hardware_on_the_board(sensor_data.hardware_discovered.clone()).join(", ");
-        device_container.push_in_place(field("Devices", all_hardware).color(GRAY).component());
+        let state = context.state();
+        let page = match state.page {
+            Page::Chart => chart_page(state, layout, palette),
+            Page::Diagnostics => diagnostics_page(state, sensor_data, layout, palette),
+            Page::Map => map_page(state, layout, palette),
+            Page::Comparison => comparison_page(state, layout, palette),
+            Page::Reports => reports_page(state, layout, palette),
+            Page::Calendar => calendar_page(state, layout, palette),
+            _ => current_page(state, sensor_data, layout, palette),
+        };
+        device_container.push_in_place(page.component());
+
+        if !kiosk {
+            device_container.push_in_place(navigation(palette, &state.localizer).component());
+        }
         
         device_container.component()
     }
 
     fn update(context: &mut Context<Self>) {
-        let url = include_str!("../device_url");
+        if let craft::events::Message::CraftMessage(CraftMessage::Initialized) = *context.message() {
+            let state = context.state_mut();
+            state.settings_path = Settings::path();
+            state.load_settings();
+            if state.settings.kiosk.enabled {
+                state.page = state.settings.kiosk.pages.first().map(|page| Page::from(*page)).unwrap_or_default();
+            }
+            schedule_reading(context);
+            watch_settings(context);
+            schedule_page_tick(context);
+            poll_other_sensors(context);
+            if context.state().page == Page::Map {
+                show_map(context);
+            }
+            return;
+        }
 
-        let fetch_and_retry = async move {
-            loop {
-                tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
-                if let Ok(res) = reqwest::get(url).await && let Ok(json_text) = res.text().await {
-                    let sensor_data: LocalSensorData = serde_json::from_str(json_text.as_str()).unwrap();
-                    
-                    return sensor_data;
+        // Kiosk mode has no keyboard to speak of, and cycles the pages on its own.
+        #[cfg(feature = "accessibility")]
+        if let Some(key) = pressed_key(context) && !context.state().settings.kiosk.enabled && navigate_with_key(context, &key) {
+            return;
+        }
+
+        if let Some(id) = clicked_id(context) {
+            match id.as_str() {
+                "discover" => {
+                    context.state_mut().page = Page::Discovery;
+                    start_discovery(context);
+                }
+                "rescan" => start_discovery(context),
+                "settings" => context.state_mut().page = Page::Settings,
+                "page-current" => context.state_mut().page = Page::Sensor,
+                "page-chart" => context.state_mut().page = Page::Chart,
+                "page-diagnostics" => {
+                    context.state_mut().page = Page::Diagnostics;
+                    show_diagnostics(context);
+                }
+                "page-comparison" => context.state_mut().page = Page::Comparison,
+                "page-reports" => {
+                    context.state_mut().page = Page::Reports;
+                    show_reports(context);
+                }
+                "report-period" => {
+                    let state = context.state_mut();
+                    state.report_period = next_choice(&[ReportPeriod::Day, ReportPeriod::Week], state.report_period);
+                    load_report(context);
+                }
+                "report-earlier" | "report-later" => {
+                    let state = context.state_mut();
+                    let today = chrono::Local::now().date_naive();
+                    let date = state.report_period.step(state.report_date.unwrap_or(today), if id == "report-earlier" { -1 } else { 1 });
+                    // Nothing has been recorded after today yet.
+                    state.report_date = (date < today).then_some(date);
+                    load_report(context);
+                }
+                "archive-sensor" => {
+                    let state = context.state_mut();
+                    if let Some(sensor_id) = archive_sensor(state) && !state.archived_sensors.is_empty() {
+                        let sensors: Vec<&str> = state.archived_sensors.iter().map(String::as_str).collect();
+                        state.archive_sensor = Some(next_choice(&sensors, sensor_id.as_str()).to_string());
+                        if state.page == Page::Calendar {
+                            load_calendar(context);
+                        } else {
+                            load_report(context);
+                        }
+                    }
+                }
+                "page-calendar" => {
+                    context.state_mut().page = Page::Calendar;
+                    show_calendar(context);
+                }
+                "calendar-statistic" => {
+                    let state = context.state_mut();
+                    state.daily_statistic = next_choice(&DailyStatistic::ALL, state.daily_statistic);
+                }
+                "calendar-close-day" => context.state_mut().calendar_day = None,
+                "report-export-html" => export_report(context, ReportFormat::Html),
+                "report-export-md" => export_report(context, ReportFormat::Markdown),
+                "page-map" => {
+                    context.state_mut().page = Page::Map;
+                    show_map(context);
+                }
+                "back" => context.state_mut().page = Page::Sensor,
+                "live" => {
+                    let state = context.state_mut();
+                    state.settings.live_mode = !state.settings.live_mode;
+                    state.save_settings();
+                    restart_source(context);
+                }
+                "record" => {
+                    // Records into the file the Replay source plays back.
+                    let state = context.state_mut();
+                    state.settings.recording = match state.settings.recording {
+                        Some(_) => None,
+                        None => Some(state.settings.replay.path.clone()),
+                    };
+                    state.save_settings();
+                    if state.settings.source == DataSourceKind::Local {
+                        restart_source(context);
+                    }
+                }
+                _ => {
+                    if let Some(date) = id.strip_prefix("calendar-").and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()) {
+                        context.state_mut().calendar_day = Some(date);
+                        load_day_profile(context);
+                    } else if let Some(index) = id.strip_prefix("sensor-").and_then(|index| index.parse::<usize>().ok()) {
+                        select_sensor(context.state_mut(), index);
+                        restart_source(context);
+                    } else if change_setting(context.state_mut(), id.as_str()) {
+                        restart_source(context);
+                    }
                 }
             }
-        };
-        
-        if let craft::events::Message::CraftMessage(CraftMessage::Initialized) = *context.message() {
-            if let Ok(json_data) = reqwest::blocking::get(url) && let Ok(json_text) = json_data.text() {
-                let sensor_data: LocalSensorData = serde_json::from_str(json_text.as_str()).unwrap();
-                context.state_mut().sensor_data = Some(sensor_data);
-            }
-            
-            context.event_mut().future(async move {
-                let sensor_data = fetch_and_retry.await;
-                Event::async_result(sensor_data)
-            });
-            
             return;
         }
 
-        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(sensor_data) = msg.downcast_ref::<LocalSensorData>() {
-            context.state_mut().sensor_data = Some(sensor_data.clone());
-    
-            context.event_mut().future(async move {
-                let sensor_data = fetch_and_retry.await;
-                Event::async_result(sensor_data)
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(update) = msg.downcast_ref::<SourceUpdate>() {
+            let update = update.clone();
+            if update.generation != context.state().source_generation {
+                return;
+            }
+
+            let state = context.state_mut();
+            state.source_metadata = Some(update.metadata);
+            state.source_health = update.health;
+
+            if let Ok(reading) = update.result {
+                // Archived as the sensor reported it, the reports apply the AQI scheme selected when they are made.
+                let unconverted = reading.sensor_data.clone();
+                let (pending, change) = context.state_mut().set_reading(reading);
+                deliver_webhooks(context, pending);
+                if let Some(change) = change {
+                    if context.state().settings.notifications.should_notify(&change, chrono::Local::now().time()) {
+                        show_notification(context, change.clone());
+                    }
+                    announce(context, change);
+                }
+                let state = context.state();
+                let received_at = state.history.samples(state.reading_kind).back().map(|sample| sample.received_at);
+                if let (Some(sensor_data), Some(received_at)) = (state.sensor_data.clone(), received_at) {
+                    // Replayed and simulated readings are not worth keeping.
+                    let keep = matches!(state.settings.source, DataSourceKind::Local | DataSourceKind::Cloud);
+                    detect_events(context, &sensor_data, received_at, keep);
+                    if keep {
+                        archive_reading(context, &unconverted);
+                        export_daily_reports(context);
+                    }
+                }
+                if context.state().page == Page::Map {
+                    load_map_tiles(context);
+                }
+            }
+
+            let state = context.state();
+            if state.source_health.consecutive_failures > 0
+                && state.source_health.consecutive_failures.is_multiple_of(REDISCOVER_AFTER_FAILURES)
+                && state.settings.source == DataSourceKind::Local
+                && state.settings.sensor().sensor_id.is_some()
+            {
+                start_discovery(context);
+            }
+
+            if !update.finished {
+                schedule_reading(context);
+            }
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(reading) = msg.downcast_ref::<OtherSensorReading>() {
+            let reading = reading.clone();
+            let state = context.state_mut();
+            match reading.sensor_data {
+                Some(mut sensor_data) => {
+                    let unconverted = sensor_data.clone();
+                    state.settings.aqi_scheme.apply(&mut sensor_data);
+                    state.indoor_outdoor.observe(reading.url.as_str(), &sensor_data, chrono::Utc::now());
+                    archive_reading(context, &unconverted);
+                    detect_events(context, &sensor_data, chrono::Utc::now(), true);
+                    context.state_mut().other_sensors.insert(reading.url, sensor_data);
+                }
+                None => {
+                    state.other_sensors.remove(&reading.url);
+                }
+            }
+            if context.state().page == Page::Map {
+                load_map_tiles(context);
+            }
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(tile) = msg.downcast_ref::<MapTile>() {
+            let tile = tile.clone();
+            context.state_mut().map_tiles.insert(tile.tile, tile.path);
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(report) = msg.downcast_ref::<Report>() {
+            let report = report.clone();
+            context.state_mut().report = Some(report);
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(drift) = msg.downcast_ref::<DriftAnalysis>() {
+            let drift = drift.clone();
+            let state = context.state_mut();
+            state.analyzing_drift = false;
+            state.drift = Some(drift);
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(calendar) = msg.downcast_ref::<Calendar>() {
+            let calendar = calendar.clone();
+            context.state_mut().calendar = Some(calendar);
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(profile) = msg.downcast_ref::<DayProfile>() {
+            let profile = profile.clone();
+            context.state_mut().day_profile = Some(profile);
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(sensors) = msg.downcast_ref::<ArchivedSensors>() {
+            let sensors = sensors.0.clone();
+            context.state_mut().archived_sensors = sensors;
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(exported) = msg.downcast_ref::<ReportExported>() {
+            let exported = exported.0.clone();
+            let state = context.state_mut();
+            state.report_status = Some(match exported {
+                Ok(path) => state.localizer.format("report-exported", [("path", path.display().to_string().into())]),
+                Err(error) => state.localizer.format("report-export-failed", [("error", error.into())]),
             });
         }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && msg.downcast_ref::<OtherSensorsTick>().is_some() {
+            context.state_mut().polling_other_sensors = false;
+            poll_other_sensors(context);
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(discovery) = msg.downcast_ref::<DiscoveryResult>() {
+            let discovery = discovery.clone();
+            if context.state_mut().set_discovery(discovery) && context.state().settings.source == DataSourceKind::Local {
+                restart_source(context);
+            }
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(delivery) = msg.downcast_ref::<DeliveryRecord>() {
+            let delivery = delivery.clone();
+            context.state_mut().deliveries.push(delivery);
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && let Some(check) = msg.downcast_ref::<SettingsFileCheck>() {
+            let check = check.clone();
+            if check.modified.is_some() && check.modified != context.state().settings_modified {
+                let state = context.state_mut();
+                let previous = (state.settings.source, state.settings.sensor().url.clone());
+                state.load_settings();
+                if previous != (state.settings.source, state.settings.sensor().url.clone()) {
+                    reset_readings(state);
+                }
+                restart_source(context);
+                schedule_page_tick(context);
+            }
+            watch_settings(context);
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message()
+            && let Some(AnnouncementExpired(number)) = msg.downcast_ref::<AnnouncementExpired>()
+            && *number == context.state().announcements
+        {
+            context.state_mut().announcement = None;
+        }
+
+        if let craft::events::Message::UserMessage(msg) = context.message() && msg.downcast_ref::<PageTick>().is_some() {
+            let state = context.state_mut();
+            state.cycling_pages = false;
+            if state.settings.kiosk.enabled {
+                next_kiosk_page(state);
+            }
+            match context.state().page {
+                Page::Map => show_map(context),
+                Page::Diagnostics => show_diagnostics(context),
+                _ => {}
+            }
+            schedule_page_tick(context);
+        }
     }
 }
 
 
 fn main() {
+    // The component loads the settings again once it starts, this only reads the window options.
+    let settings = Settings::path()
+        .and_then(|path| Settings::load(&path).ok())
+        .unwrap_or_default();
+    let window = settings.window;
+    let title = Localizer::new(settings.language).text("app-title");
 
     use craft::CraftOptions;
     craft::craft_main(PurpleAir::component(), (), CraftOptions {
         renderer: Default::default(),
-        window_title: "PurpleAir GUI".to_string(),
-        window_size: Some(Size::new(1600.0, 900.0)),
+        window_title: title,
+        window_size: Some(Size::new(window.width, window.height)),
     });
 }
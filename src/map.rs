@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Map tiles are square images of this many pixels.
+pub const TILE_SIZE: f64 = 256.0;
+const MIN_ZOOM: u8 = 2;
+const MAX_ZOOM: u8 = 17;
+/// Fitting a single sensor, or a few close together, stops at street level.
+const MAX_FIT_ZOOM: u8 = 15;
+/// Web Mercator stops here, the map is square between these latitudes.
+const MAX_LATITUDE: f64 = 85.051_128;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+/// Tile servers like OpenStreetMap's refuse requests that do not say which app they come from.
+const USER_AGENT: &str = concat!("purple-air-gui/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MapSettings {
+    /// Where tiles are downloaded from. `{z}`, `{x}` and `{y}` are replaced by the zoom level and tile position.
+    pub tile_url: String,
+
+    /// Shown under the map, as the tile provider's license requires.
+    pub attribution: String,
+
+    /// Downloaded tiles are kept here as `{z}/{x}/{y}.png`. Defaults to the platform's cache directory.
+    pub tile_cache: Option<PathBuf>,
+
+    /// Only show tiles that are in the cache already, e.g. on a kiosk without internet access that was given a copy of the cache.
+    pub offline: bool,
+
+    /// The zoom level from 2 to 17. By default the map zooms in as far as it can while showing every sensor.
+    pub zoom: Option<u8>,
+}
+
+impl Default for MapSettings {
+    fn default() -> Self {
+        MapSettings {
+            tile_url: "https://tile.openstreetmap.org/{z}/{x}/{y}.png".to_string(),
+            attribution: "© OpenStreetMap contributors".to_string(),
+            tile_cache: None,
+            offline: false,
+            zoom: None,
+        }
+    }
+}
+
+impl MapSettings {
+    pub fn tile_cache(&self) -> Option<PathBuf> {
+        self.tile_cache.clone().or_else(|| dirs::cache_dir().map(|cache_dir| cache_dir.join("purple-air-gui").join("tiles")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileId {
+    pub zoom: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl TileId {
+    pub fn url(&self, template: &str) -> String {
+        template
+            .replace("{z}", self.zoom.to_string().as_str())
+            .replace("{x}", self.x.to_string().as_str())
+            .replace("{y}", self.y.to_string().as_str())
+    }
+
+    pub fn path(&self, cache: &Path) -> PathBuf {
+        cache.join(self.zoom.to_string()).join(self.x.to_string()).join(format!("{}.png", self.y))
+    }
+}
+
+/// The position of a point in pixels on the whole Web Mercator map at `zoom`, from the top left corner.
+pub fn world_pixel(latitude: f64, longitude: f64, zoom: u8) -> (f64, f64) {
+    let size = TILE_SIZE * 2f64.powi(zoom as i32);
+    let latitude = latitude.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    let x = (longitude + 180.0) / 360.0 * size;
+    let y = (1.0 - (latitude.tan() + 1.0 / latitude.cos()).ln() / PI) / 2.0 * size;
+    (x, y)
+}
+
+/// The part of the map that is shown, `width` x `height` pixels at `zoom`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub zoom: u8,
+    left: f64,
+    top: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Viewport {
+    /// Centers the view on `points` given as latitude and longitude. Without a `zoom`, it picks the
+    /// closest one that keeps all of them inside the middle 80 % of the view.
+    pub fn fit(points: &[(f64, f64)], width: f64, height: f64, zoom: Option<u8>) -> Option<Viewport> {
+        if points.is_empty() {
+            return None;
+        }
+
+        let bounds = |zoom: u8| {
+            let pixels: Vec<(f64, f64)> = points.iter().map(|(latitude, longitude)| world_pixel(*latitude, *longitude, zoom)).collect();
+            let (min_x, max_x) = pixels.iter().fold((f64::MAX, f64::MIN), |(min, max), (x, _)| (min.min(*x), max.max(*x)));
+            let (min_y, max_y) = pixels.iter().fold((f64::MAX, f64::MIN), |(min, max), (_, y)| (min.min(*y), max.max(*y)));
+            (min_x, max_x, min_y, max_y)
+        };
+        let fits = |zoom: u8| {
+            let (min_x, max_x, min_y, max_y) = bounds(zoom);
+            max_x - min_x <= width * 0.8 && max_y - min_y <= height * 0.8
+        };
+
+        let zoom = match zoom {
+            Some(zoom) => zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+            None => (MIN_ZOOM..=MAX_FIT_ZOOM).rev().find(|zoom| fits(*zoom)).unwrap_or(MIN_ZOOM),
+        };
+        let (min_x, max_x, min_y, max_y) = bounds(zoom);
+
+        Some(Viewport {
+            zoom,
+            left: (min_x + max_x - width) / 2.0,
+            top: (min_y + max_y - height) / 2.0,
+            width,
+            height,
+        })
+    }
+
+    /// Where a point shows up in the view, in pixels from its top left corner.
+    pub fn project(&self, latitude: f64, longitude: f64) -> (f64, f64) {
+        let (x, y) = world_pixel(latitude, longitude, self.zoom);
+        (x - self.left, y - self.top)
+    }
+
+    /// The tiles that cover the view, each with the position of its top left corner in the view.
+    pub fn tiles(&self) -> Vec<(TileId, f64, f64)> {
+        let count = 1i64 << self.zoom;
+        let first_column = (self.left / TILE_SIZE).floor() as i64;
+        let last_column = ((self.left + self.width) / TILE_SIZE).floor() as i64;
+        let first_row = (self.top / TILE_SIZE).floor().max(0.0) as i64;
+        let last_row = (((self.top + self.height) / TILE_SIZE).floor() as i64).min(count - 1);
+
+        let mut tiles = Vec::new();
+        for row in first_row..=last_row {
+            for column in first_column..=last_column {
+                let tile = TileId {
+                    zoom: self.zoom,
+                    // The map wraps around at the date line.
+                    x: column.rem_euclid(count) as u32,
+                    y: row as u32,
+                };
+                tiles.push((tile, column as f64 * TILE_SIZE - self.left, row as f64 * TILE_SIZE - self.top));
+            }
+        }
+        tiles
+    }
+}
+
+/// The cached image of `tile`, downloaded first unless it is cached already or the map is offline.
+pub async fn load_tile(tile: TileId, settings: MapSettings) -> Option<PathBuf> {
+    let path = tile.path(&settings.tile_cache()?);
+    if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Some(path);
+    }
+    if settings.offline {
+        return None;
+    }
+
+    let client = reqwest::Client::builder().timeout(DOWNLOAD_TIMEOUT).user_agent(USER_AGENT).build().ok()?;
+    let response = client.get(tile.url(settings.tile_url.as_str())).send().await.ok()?.error_for_status().ok()?;
+    let image = response.bytes().await.ok()?;
+
+    // Written next to the tile and moved into place, so an interrupted download never leaves a broken tile in the cache.
+    tokio::fs::create_dir_all(path.parent()?).await.ok()?;
+    let temporary = path.with_extension("png.tmp");
+    tokio::fs::write(&temporary, &image).await.ok()?;
+    tokio::fs::rename(&temporary, &path).await.ok()?;
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: (f64, f64), expected: (f64, f64)) -> bool {
+        (actual.0 - expected.0).abs() < 1e-3 && (actual.1 - expected.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn points_are_projected_onto_the_mercator_map() {
+        assert!(close(world_pixel(0.0, 0.0, 0), (128.0, 128.0)));
+        assert!(close(world_pixel(0.0, -180.0, 1), (0.0, 256.0)));
+        assert!(close(world_pixel(MAX_LATITUDE, 180.0, 2), (1024.0, 0.0)));
+        // The poles are clamped to the edges of the square map.
+        assert!(close(world_pixel(90.0, 0.0, 2), world_pixel(MAX_LATITUDE, 0.0, 2)));
+        assert!(close(world_pixel(-90.0, 0.0, 2), (512.0, 1024.0)));
+
+        // The Space Needle is on tile 14/2623/5721, by the slippy map formula on the OpenStreetMap wiki.
+        let (x, y) = world_pixel(47.6205, -122.3493, 14);
+        assert_eq!(((x / TILE_SIZE) as u32, (y / TILE_SIZE) as u32), (2623, 5721));
+    }
+
+    #[test]
+    fn tiles_are_named_like_the_tile_server() {
+        let tile = TileId { zoom: 14, x: 2623, y: 5721 };
+
+        assert_eq!(tile.url("https://tile.openstreetmap.org/{z}/{x}/{y}.png"), "https://tile.openstreetmap.org/14/2623/5721.png");
+        assert_eq!(tile.path(Path::new("/cache")), PathBuf::from("/cache/14/2623/5721.png"));
+    }
+
+    #[test]
+    fn the_view_fits_every_point() {
+        assert_eq!(Viewport::fit(&[], 800.0, 600.0, None), None);
+
+        // A single sensor is centered at street level.
+        let single = Viewport::fit(&[(47.6205, -122.3493)], 800.0, 600.0, None).unwrap();
+        assert_eq!(single.zoom, MAX_FIT_ZOOM);
+        assert!(close(single.project(47.6205, -122.3493), (400.0, 300.0)));
+
+        // Seattle and Tacoma are about 45 km apart.
+        let points = [(47.6205, -122.3493), (47.2529, -122.4443)];
+        let both = Viewport::fit(&points, 800.0, 600.0, None).unwrap();
+        for (latitude, longitude) in points {
+            let (x, y) = both.project(latitude, longitude);
+            assert!((80.0..=720.0).contains(&x) && (60.0..=540.0).contains(&y), "{}, {} at {}, {}", latitude, longitude, x, y);
+        }
+        // One level closer and they would not fit any more.
+        let closer = Viewport::fit(&points, 800.0, 600.0, Some(both.zoom + 1)).unwrap();
+        let (_, top) = closer.project(points[0].0, points[0].1);
+        let (_, bottom) = closer.project(points[1].0, points[1].1);
+        assert!(bottom - top > 600.0 * 0.8);
+    }
+
+    #[test]
+    fn a_fixed_zoom_is_kept_within_range() {
+        let points = [(47.6205, -122.3493)];
+
+        assert_eq!(Viewport::fit(&points, 800.0, 600.0, Some(12)).unwrap().zoom, 12);
+        assert_eq!(Viewport::fit(&points, 800.0, 600.0, Some(0)).unwrap().zoom, MIN_ZOOM);
+        assert_eq!(Viewport::fit(&points, 800.0, 600.0, Some(20)).unwrap().zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn the_tiles_cover_the_view() {
+        let viewport = Viewport { zoom: 3, left: 300.0, top: 100.0, width: 400.0, height: 300.0 };
+
+        let tiles = viewport.tiles();
+        let ids: Vec<(u32, u32)> = tiles.iter().map(|(tile, _, _)| (tile.x, tile.y)).collect();
+        assert_eq!(ids, [(1, 0), (2, 0), (1, 1), (2, 1)]);
+        assert_eq!((tiles[0].1, tiles[0].2), (-44.0, -100.0));
+        assert_eq!((tiles[3].1, tiles[3].2), (212.0, 156.0));
+    }
+
+    #[test]
+    fn the_tiles_wrap_around_the_date_line() {
+        // At zoom 2 the world is 4 tiles wide and tall; this view reaches past both its left edge and its top.
+        let viewport = Viewport { zoom: 2, left: -100.0, top: -50.0, width: 300.0, height: 200.0 };
+
+        let ids: Vec<(u32, u32)> = viewport.tiles().iter().map(|(tile, _, _)| (tile.x, tile.y)).collect();
+        assert_eq!(ids, [(3, 0), (0, 0)]);
+        assert_eq!(viewport.tiles()[0].1, -156.0);
+    }
+}
@@ -0,0 +1,208 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The sun is below the horizon once its center is this far below it, accounting for refraction and its radius.
+const HORIZON_DEGREES: f64 = -0.833;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NightMode {
+    #[default]
+    Off,
+
+    /// Night lasts from `start` to `end` every day.
+    Schedule,
+
+    /// Night lasts from sunset to sunrise at the sensor's location.
+    Sun,
+}
+
+impl NightMode {
+    pub const ALL: [NightMode; 3] = [NightMode::Off, NightMode::Schedule, NightMode::Sun];
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NightSettings {
+    pub mode: NightMode,
+
+    /// The local times night starts and ends with `NightMode::Schedule`. The night may wrap past midnight.
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+
+    /// Used instead of the sensor's `lat` and `lon`, e.g. for a sensor that does not report its location.
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+
+    /// Turn the display off at night instead of dimming it.
+    pub blank: bool,
+
+    /// A blanked display comes back on, dimmed, while the PM2.5 AQI is at or above this.
+    pub wake_aqi: f64,
+}
+
+impl Default for NightSettings {
+    fn default() -> Self {
+        NightSettings {
+            mode: NightMode::Off,
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap_or_default(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap_or_default(),
+            latitude: None,
+            longitude: None,
+            blank: false,
+            wake_aqi: 101.0,
+        }
+    }
+}
+
+/// How the display should look right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMode {
+    Normal,
+    Dim,
+    Blank,
+}
+
+impl NightSettings {
+    /// Whether it is night at `now` for a sensor at `latitude`/`longitude`.
+    pub fn is_night(&self, now: DateTime<Utc>, latitude: f64, longitude: f64) -> bool {
+        match self.mode {
+            NightMode::Off => false,
+            NightMode::Schedule => {
+                let time = now.with_timezone(&chrono::Local).time();
+                if self.start <= self.end {
+                    self.start <= time && time < self.end
+                } else {
+                    time >= self.start || time < self.end
+                }
+            }
+            NightMode::Sun => {
+                let latitude = self.latitude.unwrap_or(latitude);
+                let longitude = self.longitude.unwrap_or(longitude);
+                solar_elevation(now, latitude, longitude) < HORIZON_DEGREES
+            }
+        }
+    }
+
+    pub fn display_mode(&self, now: DateTime<Utc>, latitude: f64, longitude: f64, aqi: Option<f64>) -> DisplayMode {
+        if !self.is_night(now, latitude, longitude) {
+            return DisplayMode::Normal;
+        }
+
+        if self.blank && aqi.is_none_or(|aqi| aqi < self.wake_aqi) {
+            DisplayMode::Blank
+        } else {
+            DisplayMode::Dim
+        }
+    }
+}
+
+/// The angle of the sun above the horizon in degrees, using the low-precision formulas of the
+/// Astronomical Almanac, which are good to about a hundredth of a degree this century.
+pub fn solar_elevation(time: DateTime<Utc>, latitude: f64, longitude: f64) -> f64 {
+    // Days since the J2000.0 epoch.
+    let days = time.timestamp() as f64 / 86_400.0 + 2_440_587.5 - 2_451_545.0;
+
+    let mean_longitude = (280.460 + 0.985_647_4 * days).rem_euclid(360.0);
+    let mean_anomaly = (357.528 + 0.985_600_3 * days).rem_euclid(360.0).to_radians();
+    let ecliptic_longitude = (mean_longitude + 1.915 * mean_anomaly.sin() + 0.020 * (2.0 * mean_anomaly).sin()).to_radians();
+    let obliquity = (23.439 - 0.000_000_4 * days).to_radians();
+
+    let declination = (obliquity.sin() * ecliptic_longitude.sin()).asin();
+    let right_ascension = (obliquity.cos() * ecliptic_longitude.sin()).atan2(ecliptic_longitude.cos()).to_degrees();
+
+    let sidereal_degrees = (18.697_374_558 + 24.065_709_824_419_08 * days).rem_euclid(24.0) * 15.0;
+    let hour_angle = (sidereal_degrees + longitude - right_ascension).to_radians();
+
+    let latitude = latitude.to_radians();
+    (latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos()).asin().to_degrees()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeDelta, TimeZone};
+
+    const GREENWICH: (f64, f64) = (51.4769, 0.0);
+
+    fn local(hour: u32, minute: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2025, 6, 29, hour, minute, 0).earliest().unwrap().to_utc()
+    }
+
+    fn schedule(start: (u32, u32), end: (u32, u32)) -> NightSettings {
+        NightSettings {
+            mode: NightMode::Schedule,
+            start: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap(),
+            ..NightSettings::default()
+        }
+    }
+
+    #[test]
+    fn the_sun_rises_and_sets_on_time() {
+        // Sunrise at the Royal Observatory on the solstice is at 03:43 UTC and sunset at 20:21 UTC.
+        let sunrise = Utc.with_ymd_and_hms(2025, 6, 21, 3, 43, 0).unwrap();
+        let sunset = Utc.with_ymd_and_hms(2025, 6, 21, 20, 21, 0).unwrap();
+        let margin = TimeDelta::minutes(3);
+        let elevation = |time| solar_elevation(time, GREENWICH.0, GREENWICH.1);
+
+        assert!(elevation(sunrise - margin) < HORIZON_DEGREES);
+        assert!(elevation(sunrise + margin) > HORIZON_DEGREES);
+        assert!(elevation(sunset - margin) > HORIZON_DEGREES);
+        assert!(elevation(sunset + margin) < HORIZON_DEGREES);
+
+        // At noon on the solstice, the sun stands 90 - 51.48 + 23.44 degrees high.
+        let noon = Utc.with_ymd_and_hms(2025, 6, 21, 12, 2, 0).unwrap();
+        assert!((elevation(noon) - 61.96).abs() < 0.1);
+    }
+
+    #[test]
+    fn the_sun_mode_uses_the_configured_location() {
+        let settings = NightSettings {
+            mode: NightMode::Sun,
+            latitude: Some(GREENWICH.0),
+            longitude: Some(GREENWICH.1),
+            ..NightSettings::default()
+        };
+        let midnight = Utc.with_ymd_and_hms(2025, 6, 21, 0, 0, 0).unwrap();
+
+        // The sensor's own location, on the other side of the world, is ignored.
+        assert!(settings.is_night(midnight, -33.87, 151.21));
+        assert!(!settings.is_night(midnight + TimeDelta::hours(12), -33.87, 151.21));
+    }
+
+    #[test]
+    fn a_schedule_can_wrap_past_midnight() {
+        let settings = schedule((22, 0), (7, 0));
+
+        assert!(!settings.is_night(local(21, 59), 0.0, 0.0));
+        assert!(settings.is_night(local(22, 0), 0.0, 0.0));
+        assert!(settings.is_night(local(0, 30), 0.0, 0.0));
+        assert!(settings.is_night(local(6, 59), 0.0, 0.0));
+        assert!(!settings.is_night(local(7, 0), 0.0, 0.0));
+        assert!(!settings.is_night(local(12, 0), 0.0, 0.0));
+    }
+
+    #[test]
+    fn a_schedule_can_lie_within_one_day() {
+        let settings = schedule((13, 0), (15, 30));
+
+        assert!(!settings.is_night(local(12, 59), 0.0, 0.0));
+        assert!(settings.is_night(local(14, 0), 0.0, 0.0));
+        assert!(!settings.is_night(local(15, 30), 0.0, 0.0));
+        assert!(!settings.is_night(local(23, 0), 0.0, 0.0));
+    }
+
+    #[test]
+    fn a_blanked_display_wakes_up_for_bad_air() {
+        let settings = NightSettings { blank: true, ..schedule((22, 0), (7, 0)) };
+        let night = local(23, 0);
+
+        assert_eq!(settings.display_mode(night, 0.0, 0.0, None), DisplayMode::Blank);
+        assert_eq!(settings.display_mode(night, 0.0, 0.0, Some(100.9)), DisplayMode::Blank);
+        assert_eq!(settings.display_mode(night, 0.0, 0.0, Some(101.0)), DisplayMode::Dim);
+        assert_eq!(settings.display_mode(local(12, 0), 0.0, 0.0, Some(180.0)), DisplayMode::Normal);
+
+        let dimmed = NightSettings { blank: false, ..settings };
+        assert_eq!(dimmed.display_mode(night, 0.0, 0.0, None), DisplayMode::Dim);
+    }
+}
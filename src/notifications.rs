@@ -0,0 +1,263 @@
+use crate::aqi::AqiCategory;
+use crate::i18n::Localizer;
+use crate::sensor_data::LocalSensorData;
+use chrono::{DateTime, NaiveTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How long the air quality has to stay in a new category before the change is reported. Readings hovering
+/// around a breakpoint would otherwise set off a burst of notifications and webhook alerts.
+pub const CATEGORY_HOLD: TimeDelta = TimeDelta::minutes(5);
+
+/// A daily window during which no notifications are shown. The window may wrap past midnight, e.g. 22:00 - 07:00.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+
+    /// Notify when the air quality enters one of these categories.
+    pub categories: Vec<AqiCategory>,
+
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        NotificationSettings {
+            enabled: true,
+            categories: AqiCategory::ALL.to_vec(),
+            quiet_hours: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CategoryChange {
+    pub from: AqiCategory,
+    pub to: AqiCategory,
+    pub aqi: f64,
+}
+
+impl CategoryChange {
+    pub fn summary(&self, localizer: &Localizer) -> String {
+        localizer.format("notification-summary", [("category", localizer.name(&self.to).into())])
+    }
+
+    pub fn body(&self, localizer: &Localizer) -> String {
+        localizer.format("notification-body", [("aqi", self.aqi.round().into()), ("previous", localizer.name(&self.from).into())])
+    }
+}
+
+impl NotificationSettings {
+    pub fn should_notify(&self, change: &CategoryChange, now: NaiveTime) -> bool {
+        if !self.enabled || !self.categories.contains(&change.to) {
+            return false;
+        }
+
+        !self.quiet_hours.as_ref().is_some_and(|quiet_hours| quiet_hours.contains(now))
+    }
+}
+
+/// Tracks the EPA category of the channel A PM2.5 AQI between readings.
+#[derive(Default)]
+pub struct CategoryNotifier {
+    /// The category that was last reported, or the first one seen.
+    reported: Option<AqiCategory>,
+
+    /// The other category the readings are in, and since when.
+    candidate: Option<(AqiCategory, DateTime<Utc>)>,
+}
+
+impl CategoryNotifier {
+    /// Records the category of the new reading and returns the change once the readings have stayed in a new
+    /// category for `CATEGORY_HOLD`. The first reading only establishes the baseline.
+    pub fn observe(&mut self, sensor_data: &LocalSensorData, now: DateTime<Utc>) -> Option<CategoryChange> {
+        let aqi = sensor_data.pm2_5_aqi?;
+        let category = AqiCategory::from_aqi(aqi);
+        let Some(reported) = self.reported else {
+            self.reported = Some(category);
+            return None;
+        };
+
+        if category == reported {
+            self.candidate = None;
+            return None;
+        }
+
+        let since = match self.candidate {
+            Some((candidate, since)) if candidate == category => since,
+            _ => {
+                self.candidate = Some((category, now));
+                now
+            }
+        };
+        if now - since < CATEGORY_HOLD {
+            return None;
+        }
+
+        self.reported = Some(category);
+        self.candidate = None;
+        Some(CategoryChange {
+            from: reported,
+            to: category,
+            aqi,
+        })
+    }
+}
+
+/// Shows a native desktop notification. On Linux this goes through the freedesktop notification service over D-Bus,
+/// which blocks until the service answers, so it runs on a blocking thread.
+pub async fn notify(summary: String, body: String) {
+    let result = tokio::task::spawn_blocking(move || {
+        notify_rust::Notification::new()
+            .appname("PurpleAir GUI")
+            .summary(summary.as_str())
+            .body(body.as_str())
+            .show()
+            .map(|_| ())
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(error)) => eprintln!("Failed to show the desktop notification: {}", error),
+        Err(error) => eprintln!("Failed to show the desktop notification: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 29, 12, 0, 0).unwrap() + TimeDelta::seconds(seconds)
+    }
+
+    fn sensor_data(aqi: f64) -> LocalSensorData {
+        let mut sensor_data: LocalSensorData = serde_json::from_str(include_str!("../fixtures/pa-ii.json")).unwrap();
+        sensor_data.pm2_5_aqi = Some(aqi);
+        sensor_data
+    }
+
+    fn change(to: AqiCategory) -> CategoryChange {
+        CategoryChange {
+            from: AqiCategory::Moderate,
+            to,
+            aqi: 120.0,
+        }
+    }
+
+    #[test]
+    fn quiet_hours_within_a_day() {
+        let quiet_hours = QuietHours {
+            start: time(13, 0),
+            end: time(15, 0),
+        };
+
+        assert!(!quiet_hours.contains(time(12, 59)));
+        assert!(quiet_hours.contains(time(13, 0)));
+        assert!(quiet_hours.contains(time(14, 59)));
+        assert!(!quiet_hours.contains(time(15, 0)));
+    }
+
+    #[test]
+    fn quiet_hours_past_midnight() {
+        let quiet_hours = QuietHours {
+            start: time(22, 0),
+            end: time(7, 0),
+        };
+
+        assert!(quiet_hours.contains(time(22, 0)));
+        assert!(quiet_hours.contains(time(0, 0)));
+        assert!(quiet_hours.contains(time(6, 59)));
+        assert!(!quiet_hours.contains(time(7, 0)));
+        assert!(!quiet_hours.contains(time(21, 59)));
+        assert!(!quiet_hours.contains(time(12, 0)));
+    }
+
+    #[test]
+    fn quiet_hours_starting_and_ending_together_are_empty() {
+        let quiet_hours = QuietHours {
+            start: time(8, 0),
+            end: time(8, 0),
+        };
+
+        assert!(!quiet_hours.contains(time(8, 0)));
+        assert!(!quiet_hours.contains(time(20, 0)));
+    }
+
+    #[test]
+    fn notifies_for_chosen_categories_outside_quiet_hours() {
+        let mut settings = NotificationSettings {
+            categories: vec![AqiCategory::UnhealthyForSensitiveGroups],
+            quiet_hours: Some(QuietHours {
+                start: time(22, 0),
+                end: time(7, 0),
+            }),
+            ..NotificationSettings::default()
+        };
+
+        assert!(settings.should_notify(&change(AqiCategory::UnhealthyForSensitiveGroups), time(12, 0)));
+        assert!(!settings.should_notify(&change(AqiCategory::Unhealthy), time(12, 0)));
+        assert!(!settings.should_notify(&change(AqiCategory::UnhealthyForSensitiveGroups), time(23, 0)));
+
+        settings.enabled = false;
+        assert!(!settings.should_notify(&change(AqiCategory::UnhealthyForSensitiveGroups), time(12, 0)));
+    }
+
+    #[test]
+    fn the_first_reading_is_the_baseline() {
+        let mut notifier = CategoryNotifier::default();
+
+        assert!(notifier.observe(&sensor_data(120.0), at(0)).is_none());
+        assert!(notifier.observe(&sensor_data(120.0), at(600)).is_none());
+    }
+
+    #[test]
+    fn a_new_category_is_reported_once_it_held() {
+        let mut notifier = CategoryNotifier::default();
+        notifier.observe(&sensor_data(80.0), at(0));
+
+        assert!(notifier.observe(&sensor_data(120.0), at(10)).is_none());
+        assert!(notifier.observe(&sensor_data(125.0), at(10 + 299)).is_none());
+
+        let change = notifier.observe(&sensor_data(130.0), at(10 + 300)).unwrap();
+        assert_eq!(change.from, AqiCategory::Moderate);
+        assert_eq!(change.to, AqiCategory::UnhealthyForSensitiveGroups);
+        assert_eq!(change.aqi, 130.0);
+
+        assert!(notifier.observe(&sensor_data(130.0), at(1000)).is_none());
+    }
+
+    #[test]
+    fn readings_around_a_breakpoint_are_not_reported() {
+        let mut notifier = CategoryNotifier::default();
+        notifier.observe(&sensor_data(99.0), at(0));
+
+        // Live readings every 2 seconds that keep crossing 100 for half an hour.
+        for tick in 1..900 {
+            let aqi = if tick % 2 == 0 { 99.0 } else { 102.0 };
+            assert!(notifier.observe(&sensor_data(aqi), at(tick * 2)).is_none());
+        }
+    }
+}
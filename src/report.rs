@@ -0,0 +1,503 @@
+use crate::aqi::{AqiCategory, AqiScheme};
+use crate::archive::ArchivedReading;
+use crate::comfort::TemperatureUnit;
+use crate::i18n::Localizer;
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A reading counts until the next one, but for no longer than this. Longer gaps are time the sensor was not read.
+const MAX_READING_GAP: TimeDelta = TimeDelta::minutes(10);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ReportSettings {
+    /// Where exported reports are saved. Defaults to a `PurpleAir Reports` folder in the platform's documents directory.
+    pub directory: Option<PathBuf>,
+
+    /// Save the report of every archived sensor once a day is over.
+    pub daily_export: Option<ReportFormat>,
+}
+
+impl ReportSettings {
+    pub fn directory(&self) -> Option<PathBuf> {
+        self.directory.clone().or_else(|| dirs::document_dir().map(|documents| documents.join("PurpleAir Reports")))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportPeriod {
+    #[default]
+    Day,
+    /// Monday to Sunday.
+    Week,
+}
+
+impl ReportPeriod {
+    /// The first and last day of the period that contains `date`.
+    pub fn span(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            ReportPeriod::Day => (date, date),
+            ReportPeriod::Week => {
+                let first = date.week(chrono::Weekday::Mon).first_day();
+                (first, first + TimeDelta::days(6))
+            }
+        }
+    }
+
+    /// A date in the period before (`-1`) or after (`1`) the one that contains `date`.
+    pub fn step(&self, date: NaiveDate, steps: i64) -> NaiveDate {
+        match self {
+            ReportPeriod::Day => date + TimeDelta::days(steps),
+            ReportPeriod::Week => date + TimeDelta::weeks(steps),
+        }
+    }
+
+    /// The key of the period in the catalogs' selectors and in file names.
+    fn key(&self) -> &'static str {
+        match self {
+            ReportPeriod::Day => "day",
+            ReportPeriod::Week => "week",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 2] = [ReportFormat::Html, ReportFormat::Markdown];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AqiSummary {
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+    /// When the maximum was first reached.
+    pub peak_at: DateTime<Utc>,
+}
+
+/// The readings of one sensor over a day or week, summed up.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub sensor_id: String,
+    /// The name of the sensor in the settings, or its id.
+    pub sensor: String,
+    pub period: ReportPeriod,
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+    pub readings: usize,
+    /// The time the readings cover, leaving out the gaps in which the sensor was not read.
+    pub covered: TimeDelta,
+    pub aqi: Option<AqiSummary>,
+    /// How long the AQI was in each category, in the order of `AqiCategory::ALL`.
+    pub category_time: [TimeDelta; 6],
+    pub temperature_f: Option<(u64, u64)>,
+    /// How often the sensor's uptime started over.
+    pub restarts: u32,
+    /// How many of the sensor's uploads to PurpleAir succeeded, out of how many it sent.
+    pub uploads: (u64, u64),
+}
+
+impl Report {
+    /// Sums up `readings`, which have to be sorted by time, for the period that contains `date`, with the AQI in `scheme`.
+    pub fn new(sensor_id: String, sensor: String, period: ReportPeriod, date: NaiveDate, readings: &[ArchivedReading], scheme: AqiScheme) -> Report {
+        let (first, last) = period.span(date);
+        let mut report = Report {
+            sensor_id,
+            sensor,
+            period,
+            first,
+            last,
+            readings: readings.len(),
+            covered: TimeDelta::zero(),
+            aqi: None,
+            category_time: [TimeDelta::zero(); 6],
+            temperature_f: None,
+            restarts: 0,
+            uploads: (0, 0),
+        };
+
+        // How long each reading stands for. The last one counts as long as the one before it.
+        let mut durations: Vec<TimeDelta> = readings.windows(2).map(|pair| (pair[1].received_at - pair[0].received_at).min(MAX_READING_GAP)).collect();
+        durations.push(durations.last().copied().unwrap_or_default());
+
+        let mut aqi_time = TimeDelta::zero();
+        let mut weighted_aqi = 0.0;
+        for (reading, duration) in readings.iter().zip(&durations) {
+            report.covered += *duration;
+
+            if let Some(aqi) = reading.aqi_in(scheme) {
+                let category = AqiCategory::from_aqi(aqi);
+                report.category_time[category as usize] += *duration;
+                aqi_time += *duration;
+                weighted_aqi += aqi * duration.num_seconds() as f64;
+
+                let summary = report.aqi.get_or_insert(AqiSummary { min: aqi, mean: aqi, max: aqi, peak_at: reading.received_at });
+                summary.min = summary.min.min(aqi);
+                if aqi > summary.max {
+                    summary.max = aqi;
+                    summary.peak_at = reading.received_at;
+                }
+            }
+
+            if let Some(temperature) = reading.temperature_f {
+                let (min, max) = report.temperature_f.get_or_insert((temperature, temperature));
+                *min = (*min).min(temperature);
+                *max = (*max).max(temperature);
+            }
+        }
+
+        // A single reading has no duration, its AQI is the mean.
+        if let Some(summary) = &mut report.aqi && aqi_time > TimeDelta::zero() {
+            summary.mean = weighted_aqi / aqi_time.num_seconds() as f64;
+        }
+
+        // The upload counters count from when the sensor started, so they start over with it.
+        for pair in readings.windows(2) {
+            let (previous, reading) = (&pair[0], &pair[1]);
+            if reading.uptime < previous.uptime || reading.http_sends < previous.http_sends || reading.http_success < previous.http_success {
+                report.restarts += 1;
+                report.uploads.0 += reading.http_success;
+                report.uploads.1 += reading.http_sends;
+            } else {
+                report.uploads.0 += reading.http_success - previous.http_success;
+                report.uploads.1 += reading.http_sends - previous.http_sends;
+            }
+        }
+
+        report
+    }
+
+    /// The local day or days the report covers, e.g. "October 18, 2026" or "10/12/2026 – 10/18/2026".
+    pub fn dates(&self, localizer: &Localizer) -> String {
+        let local = |date: NaiveDate| date.and_hms_opt(12, 0, 0).and_then(|time| time.and_local_timezone(Local).single());
+        match (self.period, local(self.first), local(self.last)) {
+            (ReportPeriod::Day, Some(first), _) => localizer.long_date(&first),
+            (ReportPeriod::Week, Some(first), Some(last)) => localizer.format("report-week", [("first", localizer.date(&first).into()), ("last", localizer.date(&last).into())]),
+            _ => self.first.to_string(),
+        }
+    }
+
+    pub fn title(&self, localizer: &Localizer) -> String {
+        localizer.format("report-title", [
+            ("period", self.period.key().into()),
+            ("sensor", self.sensor.as_str().into()),
+            ("dates", self.dates(localizer).into()),
+        ])
+    }
+
+    /// The figures of the report as labels and values, in the order they are shown.
+    pub fn fields(&self, localizer: &Localizer, unit: TemperatureUnit) -> Vec<(String, String)> {
+        let hours = |time: TimeDelta| localizer.format("report-hours", [("hours", localizer.number(time.num_seconds() as f64 / 3600.0, 1).into())]);
+        let mut fields = Vec::new();
+
+        if let Some(aqi) = &self.aqi {
+            fields.push((localizer.text("report-aqi"), localizer.format("report-aqi-value", [
+                ("min", aqi.min.round().into()),
+                ("mean", aqi.mean.round().into()),
+                ("max", aqi.max.round().into()),
+            ])));
+            let peak_at = aqi.peak_at.with_timezone(&Local);
+            let peak = match self.period {
+                ReportPeriod::Day => localizer.format("report-peak-value", [("aqi", aqi.max.round().into()), ("time", localizer.short_time(&peak_at).into())]),
+                ReportPeriod::Week => localizer.format("report-peak-value-week", [
+                    ("aqi", aqi.max.round().into()),
+                    ("date", localizer.date(&peak_at).into()),
+                    ("time", localizer.short_time(&peak_at).into()),
+                ]),
+            };
+            fields.push((localizer.text("report-peak"), peak));
+        }
+        if let Some((min, max)) = self.temperature_f {
+            let unit_key = match unit {
+                TemperatureUnit::Fahrenheit => "fahrenheit",
+                TemperatureUnit::Celsius => "celsius",
+            };
+            let temperature = |fahrenheit: u64| {
+                localizer.format("temperature-value", [("value", localizer.number(unit.convert(fahrenheit as f64), 0).into()), ("unit", unit_key.into())])
+            };
+            fields.push((localizer.text("report-temperature"), localizer.format("report-range", [("min", temperature(min).into()), ("max", temperature(max).into())])));
+        }
+        fields.push((localizer.text("report-readings"), localizer.format("report-readings-value", [("count", self.readings.into()), ("hours", hours(self.covered).into())])));
+        fields.push((localizer.text("report-restarts"), self.restarts.to_string()));
+
+        let (succeeded, sent) = self.uploads;
+        let uploads = match sent {
+            0 => localizer.text("report-no-uploads"),
+            _ => localizer.format("report-uploads-value", [
+                ("succeeded", succeeded.into()),
+                ("sent", sent.into()),
+                ("percent", localizer.number(succeeded as f64 / sent as f64 * 100.0, 1).into()),
+            ]),
+        };
+        fields.push((localizer.text("report-uploads"), uploads));
+        fields
+    }
+
+    /// How long the AQI spent in each category, as the category's name and the hours.
+    pub fn category_hours(&self, localizer: &Localizer) -> Vec<(AqiCategory, String, String)> {
+        AqiCategory::ALL
+            .iter()
+            .zip(self.category_time)
+            .map(|(category, time)| {
+                let hours = localizer.format("report-hours", [("hours", localizer.number(time.num_seconds() as f64 / 3600.0, 1).into())]);
+                (*category, localizer.name(category), hours)
+            })
+            .collect()
+    }
+
+    pub fn file_name(&self, format: ReportFormat) -> String {
+        format!("{}-{}-{}.{}", self.sensor_id.replace(':', "-"), self.period.key(), self.first.format("%Y-%m-%d"), format.extension())
+    }
+
+    pub fn render(&self, format: ReportFormat, localizer: &Localizer, unit: TemperatureUnit) -> String {
+        match format {
+            ReportFormat::Html => self.html(localizer, unit),
+            ReportFormat::Markdown => self.markdown(localizer, unit),
+        }
+    }
+
+    fn markdown(&self, localizer: &Localizer, unit: TemperatureUnit) -> String {
+        let cell = |text: &str| text.replace('|', "\\|");
+        let mut markdown = format!("# {}\n\n", self.title(localizer));
+
+        if self.readings == 0 {
+            markdown.push_str(format!("{}\n", localizer.text("report-no-readings")).as_str());
+            return markdown;
+        }
+
+        markdown.push_str("| | |\n|---|---|\n");
+        for (label, value) in self.fields(localizer, unit) {
+            markdown.push_str(format!("| {} | {} |\n", cell(label.as_str()), cell(value.as_str())).as_str());
+        }
+
+        markdown.push_str(format!("\n## {}\n\n| | |\n|---|---|\n", localizer.text("report-category-hours")).as_str());
+        for (_, category, hours) in self.category_hours(localizer) {
+            markdown.push_str(format!("| {} | {} |\n", cell(category.as_str()), cell(hours.as_str())).as_str());
+        }
+
+        markdown.push_str(format!("\n{}\n", generated(localizer)).as_str());
+        markdown
+    }
+
+    /// A standalone page with the styles inline, so it can be mailed or opened anywhere.
+    fn html(&self, localizer: &Localizer, unit: TemperatureUnit) -> String {
+        let title = escape(self.title(localizer).as_str());
+        let mut body = format!("<h1>{}</h1>\n", title);
+
+        if self.readings == 0 {
+            body.push_str(format!("<p>{}</p>\n", escape(localizer.text("report-no-readings").as_str())).as_str());
+        } else {
+            body.push_str("<table>\n");
+            for (label, value) in self.fields(localizer, unit) {
+                body.push_str(format!("<tr><th>{}</th><td>{}</td></tr>\n", escape(label.as_str()), escape(value.as_str())).as_str());
+            }
+            body.push_str("</table>\n");
+
+            body.push_str(format!("<h2>{}</h2>\n<table>\n", escape(localizer.text("report-category-hours").as_str())).as_str());
+            let total = self.category_time.iter().map(|time| time.num_seconds()).sum::<i64>().max(1) as f64;
+            for ((category, name, hours), time) in self.category_hours(localizer).into_iter().zip(self.category_time) {
+                let width = time.num_seconds() as f64 / total * 100.0;
+                body.push_str(format!(
+                    "<tr><th>{}</th><td>{}</td><td class=\"bar\"><div style=\"width: {:.1}%; background: {}\"></div></td></tr>\n",
+                    escape(name.as_str()),
+                    escape(hours.as_str()),
+                    width,
+                    category.rgb(),
+                ).as_str());
+            }
+            body.push_str("</table>\n");
+            body.push_str(format!("<p class=\"generated\">{}</p>\n", escape(generated(localizer).as_str())).as_str());
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            localizer.language().tag(),
+            title,
+            HTML_STYLE,
+            body,
+        )
+    }
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; } \
+    th, td { text-align: left; padding: 0.3em 1em 0.3em 0; } \
+    td.bar { width: 40%; } td.bar div { height: 1em; min-width: 1px; } \
+    .generated { color: #777; font-size: 0.9em; }";
+
+fn generated(localizer: &Localizer) -> String {
+    let now = Local::now();
+    localizer.format("report-generated", [("date", localizer.date(&now).into()), ("time", localizer.short_time(&now).into())])
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Saves the report into `directory` and returns where it went.
+pub async fn export(directory: PathBuf, file_name: String, contents: String) -> std::io::Result<PathBuf> {
+    tokio::fs::create_dir_all(&directory).await?;
+    let path = directory.join(file_name);
+    tokio::fs::write(&path, contents).await?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use chrono::TimeZone;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 29, 12, 0, 0).unwrap() + TimeDelta::minutes(minutes)
+    }
+
+    fn reading(minutes: i64, aqi: f64) -> ArchivedReading {
+        ArchivedReading {
+            received_at: at(minutes),
+            aqi: Some(aqi),
+            pm2_5: None,
+            pm2_5_cf_1: None,
+            pm2_5_b: None,
+            p_0_3_um: None,
+            p_0_3_um_b: None,
+            temperature_f: Some(70),
+            humidity: Some(40),
+            uptime: 3600 + minutes as u64 * 60,
+            http_success: 100 + minutes as u64,
+            http_sends: 100 + minutes as u64,
+        }
+    }
+
+    fn report(readings: &[ArchivedReading]) -> Report {
+        let date = at(0).with_timezone(&Local).date_naive();
+        Report::new("84:f3:eb:7b:c8:ee".to_string(), "Backyard".to_string(), ReportPeriod::Day, date, readings, AqiScheme::UsEpa)
+    }
+
+    #[test]
+    fn the_mean_is_weighted_by_how_long_each_reading_stood() {
+        let report = report(&[reading(0, 10.0), reading(2, 100.0), reading(10, 50.0)]);
+
+        let aqi = report.aqi.unwrap();
+        assert_eq!((aqi.min, aqi.max, aqi.peak_at), (10.0, 100.0, at(2)));
+        // 2 minutes at 10, 8 at 100 and the last reading as long as the one before it.
+        assert!((aqi.mean - (10.0 * 2.0 + 100.0 * 8.0 + 50.0 * 8.0) / 18.0).abs() < 1e-9);
+        assert_eq!(report.covered, TimeDelta::minutes(18));
+        assert_eq!(report.readings, 3);
+    }
+
+    #[test]
+    fn time_is_counted_per_category() {
+        let report = report(&[reading(0, 10.0), reading(2, 100.0), reading(10, 50.0), reading(15, 160.0), reading(20, 160.0)]);
+
+        let minutes = report.category_time.map(|time| time.num_minutes());
+        assert_eq!(minutes, [2 + 5, 8, 0, 5 + 5, 0, 0]);
+
+        let hours = report.category_hours(&Localizer::new(Language::English));
+        assert_eq!(hours[0].0, AqiCategory::Good);
+        assert_eq!(hours[0].2, "0.1 h");
+    }
+
+    #[test]
+    fn the_peak_is_when_the_maximum_was_first_reached() {
+        let report = report(&[reading(0, 80.0), reading(1, 120.0), reading(2, 90.0), reading(3, 120.0)]);
+
+        assert_eq!(report.aqi.unwrap().peak_at, at(1));
+    }
+
+    #[test]
+    fn gaps_do_not_count_as_covered() {
+        let report = report(&[reading(0, 10.0), reading(60, 200.0), reading(61, 200.0)]);
+
+        // The first reading stands for `MAX_READING_GAP`, not the hour until the next one.
+        assert_eq!(report.covered, MAX_READING_GAP + TimeDelta::minutes(2));
+        assert_eq!(report.category_time[AqiCategory::Good as usize], MAX_READING_GAP);
+        assert!((report.aqi.unwrap().mean - (10.0 * 10.0 + 200.0 * 2.0) / 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_single_reading_is_its_own_mean() {
+        let report = report(&[reading(0, 42.0)]);
+
+        assert_eq!(report.aqi.unwrap().mean, 42.0);
+        assert_eq!(report.covered, TimeDelta::zero());
+        assert_eq!(report.uploads, (0, 0));
+    }
+
+    #[test]
+    fn uploads_are_counted_across_restarts() {
+        let mut readings = vec![reading(0, 10.0), reading(1, 10.0), reading(2, 10.0), reading(3, 10.0)];
+        (readings[1].http_success, readings[1].http_sends) = (104, 106);
+        // The sensor restarted and its counters with it.
+        (readings[2].uptime, readings[2].http_success, readings[2].http_sends) = (60, 1, 2);
+        (readings[3].uptime, readings[3].http_success, readings[3].http_sends) = (120, 3, 4);
+
+        let report = report(&readings);
+        assert_eq!(report.restarts, 1);
+        assert_eq!(report.uploads, (4 + 1 + 2, 6 + 2 + 2));
+    }
+
+    #[test]
+    fn an_uptime_reset_alone_is_a_restart() {
+        let mut readings = vec![reading(0, 10.0), reading(1, 10.0)];
+        readings[1].uptime = 30;
+
+        assert_eq!(report(&readings).restarts, 1);
+    }
+
+    #[test]
+    fn reports_export_as_markdown_and_html() {
+        let localizer = Localizer::new(Language::English);
+        let mut report = report(&[reading(0, 10.0), reading(2, 100.0)]);
+        report.sensor = "Kitchen <b>&</b> \"east\"".to_string();
+
+        let markdown = report.render(ReportFormat::Markdown, &localizer, TemperatureUnit::Fahrenheit);
+        assert!(markdown.starts_with("# "));
+        assert!(markdown.contains("| PM2.5 AQI | min 10, mean 55, max 100 |\n"));
+        assert!(markdown.contains("| Sensor Restarts | 0 |\n"));
+        assert!(markdown.contains("| Uploads | 2 of 2 succeeded (100.0 %) |\n"));
+
+        let html = report.render(ReportFormat::Html, &localizer, TemperatureUnit::Fahrenheit);
+        assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en-US\">"));
+        assert!(html.contains("Kitchen &lt;b&gt;&amp;&lt;/b&gt; &quot;east&quot;"));
+        assert!(!html.contains("<b>"));
+        assert!(html.contains("<tr><th>PM2.5 AQI</th><td>min 10, mean 55, max 100</td></tr>"));
+        assert!(html.contains(AqiCategory::Moderate.rgb()));
+
+        assert_eq!(report.file_name(ReportFormat::Html), format!("84-f3-eb-7b-c8-ee-day-{}.html", report.first.format("%Y-%m-%d")));
+    }
+
+    #[test]
+    fn an_empty_report_says_so() {
+        let localizer = Localizer::new(Language::English);
+        let report = report(&[]);
+
+        assert!(report.aqi.is_none());
+        let markdown = report.render(ReportFormat::Markdown, &localizer, TemperatureUnit::Celsius);
+        assert!(markdown.ends_with(format!("{}\n", localizer.text("report-no-readings")).as_str()));
+    }
+
+    #[test]
+    fn temperatures_are_shown_in_the_chosen_unit() {
+        let localizer = Localizer::new(Language::English);
+        let mut readings = vec![reading(0, 10.0), reading(1, 10.0)];
+        readings[1].temperature_f = Some(86);
+
+        let report = report(&readings);
+        assert_eq!(report.temperature_f, Some((70, 86)));
+        let markdown = report.render(ReportFormat::Markdown, &localizer, TemperatureUnit::Celsius);
+        assert!(markdown.contains("| Temperature | 21 °C to 30 °C |\n"));
+    }
+}
@@ -1,5 +1,7 @@
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
-use serde::{de, Deserialize, Deserializer, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+const DATETIME_FORMAT: &str = "%Y/%m/%dT%H:%M:%S";
 
 fn parse_nonstandard_datetime<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
 where
@@ -9,11 +11,19 @@ where
 
     let s = String::deserialize(deserializer)?;
     let s = s.trim_end_matches('z');
-    let naive = NaiveDateTime::parse_from_str(s, "%Y/%m/%dT%H:%M:%S")
+    let naive = NaiveDateTime::parse_from_str(s, DATETIME_FORMAT)
         .map_err(serde::de::Error::custom)?;
     Ok(Utc.from_utc_datetime(&naive))
 }
 
+/// Writes the date the same way the sensor does, so serialized data parses again.
+fn serialize_nonstandard_datetime<S>(date_time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(format!("{}z", date_time.format(DATETIME_FORMAT)).as_str())
+}
+
 #[repr(u8)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Status {
@@ -44,6 +54,23 @@ where
         .ok_or_else(|| de::Error::custom(format!("Invalid status number: {}", number)))
 }
 
+pub fn serialize_status<S>(status: &Status, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_u8(status.clone() as u8)
+}
+
+pub fn serialize_optional_status<S>(status: &Option<Status>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match status {
+        Some(status) => serializer.serialize_some(&(status.clone() as u8)),
+        None => serializer.serialize_none(),
+    }
+}
+
 pub fn parse_optional_status<'de, D>(deserializer: D) -> Result<Option<Status>, D::Error>
 where
     D: Deserializer<'de>,
@@ -68,7 +95,7 @@ pub struct LocalSensorData {
     pub sensor_id: String,
 
     /// The current time reported by the device. This is provided in UTC and ISO 8601 format
-    #[serde(rename = "DateTime", deserialize_with = "parse_nonstandard_datetime")]
+    #[serde(rename = "DateTime", deserialize_with = "parse_nonstandard_datetime", serialize_with = "serialize_nonstandard_datetime")]
     pub date_time: DateTime<Utc>,
 
     /// This is the name of the PurpleAir-**** WiFI network displayed for device setup
@@ -317,43 +344,43 @@ pub struct LocalSensorData {
     // --- Status Fields START ---
 
     /// NTP: Network Time Protocol time sync
-    #[serde(rename = "status_0", deserialize_with = "parse_status")]
+    #[serde(rename = "status_0", deserialize_with = "parse_status", serialize_with = "serialize_status")]
     pub status_ntp: Status,
 
     /// LOC: Google location lookup
-    #[serde(rename = "status_1", deserialize_with = "parse_status")]
+    #[serde(rename = "status_1", deserialize_with = "parse_status", serialize_with = "serialize_status")]
     pub status_loc: Status,
 
     /// UPD: Update check
-    #[serde(rename = "status_2", deserialize_with = "parse_status")]
+    #[serde(rename = "status_2", deserialize_with = "parse_status", serialize_with = "serialize_status")]
     pub status_upd: Status,
 
     /// PAA: Connection to PurpleAir servers
-    #[serde(rename = "status_3", deserialize_with = "parse_status")]
+    #[serde(rename = "status_3", deserialize_with = "parse_status", serialize_with = "serialize_status")]
     pub status_paa: Status,
 
     /// TSA: ThingSpeak A Channel (no longer used)
-    #[serde(rename = "status_4", deserialize_with = "parse_status")]
+    #[serde(rename = "status_4", deserialize_with = "parse_status", serialize_with = "serialize_status")]
     pub status_tsa: Status,
 
     /// TSS: ThingSpeak A Secondary (no longer used)
-    #[serde(rename = "status_5", deserialize_with = "parse_status")]
+    #[serde(rename = "status_5", deserialize_with = "parse_status", serialize_with = "serialize_status")]
     pub status_tss_a: Status,
 
     /// 3RD: Status for Data Processor #1 (if setup in the sensor’s registration)
-    #[serde(rename = "status_6", deserialize_with = "parse_optional_status", default)]
+    #[serde(rename = "status_6", deserialize_with = "parse_optional_status", serialize_with = "serialize_optional_status", default)]
     pub status_for_processor_1: Option<Status>,
 
     /// TSB: ThingSpeak B Channel (no longer used)
-    #[serde(rename = "status_7", deserialize_with = "parse_status")]
+    #[serde(rename = "status_7", deserialize_with = "parse_status", serialize_with = "serialize_status")]
     pub status_tsb: Status,
 
     /// TSS: ThingSpeak B Secondary (no longer used)
-    #[serde(rename = "status_8", deserialize_with = "parse_status")]
+    #[serde(rename = "status_8", deserialize_with = "parse_status", serialize_with = "serialize_status")]
     pub status_tss_b: Status,
 
     /// 3RD: Status for Data Processor #2 (if setup in the sensor’s registration)
-    #[serde(rename = "status_10", deserialize_with = "parse_optional_status", default)]
+    #[serde(rename = "status_10", deserialize_with = "parse_optional_status", serialize_with = "serialize_optional_status", default)]
     pub status_for_processor_2: Option<Status>,
 
     // --- Status Fields END ---
@@ -0,0 +1,376 @@
+use crate::aqi::{AqiDisplay, AqiScheme};
+use crate::archive::ArchiveSettings;
+use crate::cloud::CloudSettings;
+use crate::comfort::TemperatureUnit;
+use crate::data_source::{ReplaySettings, SimulatorSettings};
+use crate::freshness::FreshnessSettings;
+use crate::i18n::Language;
+use crate::map::MapSettings;
+use crate::night::NightSettings;
+use crate::theme::{Palette, Theme};
+use crate::notifications::NotificationSettings;
+use crate::report::ReportSettings;
+use crate::webhook::WebhookSettings;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The version of the config file layout. Bump it when a change would make older files load incorrectly.
+pub const SETTINGS_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SensorSettings {
+    /// Shown in the sensor list instead of the URL when set.
+    pub name: String,
+
+    /// The `/json` endpoint of the sensor.
+    pub url: String,
+
+    /// The MAC-derived id of the sensor. When set, the sensor is looked up again on the
+    /// local network if it stops answering at `url`, e.g. after DHCP assigned it a new address.
+    pub sensor_id: Option<String>,
+}
+
+impl Default for SensorSettings {
+    fn default() -> Self {
+        SensorSettings {
+            name: String::new(),
+            url: include_str!("../device_url").trim().to_string(),
+            sensor_id: None,
+        }
+    }
+}
+
+impl SensorSettings {
+    pub fn label(&self) -> &str {
+        if self.name.is_empty() { self.url.as_str() } else { self.name.as_str() }
+    }
+}
+
+/// Where readings come from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataSourceKind {
+    /// The sensor's `/json` endpoint on the local network.
+    #[default]
+    Local,
+
+    /// The PurpleAir API, for when the sensor is not reachable on the local network.
+    Cloud,
+
+    /// A recording of raw `/json` responses.
+    Replay,
+
+    /// Synthetic readings.
+    Simulator,
+}
+
+impl DataSourceKind {
+    pub const ALL: [DataSourceKind; 4] = [DataSourceKind::Local, DataSourceKind::Cloud, DataSourceKind::Replay, DataSourceKind::Simulator];
+}
+
+/// The window size, or the display resolution in kiosk mode. The layout scales to it and stacks
+/// the tiles when it is taller than wide, e.g. 480 x 800 for a display rotated to portrait.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        WindowSettings {
+            width: 1600.0,
+            height: 900.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KioskPage {
+    Current,
+    Chart,
+    Diagnostics,
+    Map,
+    Comparison,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct KioskSettings {
+    /// Hides the buttons and cycles through `pages`, for a dedicated display such as a Raspberry Pi screen.
+    /// Only the config file can turn it off again.
+    pub enabled: bool,
+
+    /// Show each page this many seconds before moving on to the next one. 0 stays on the first page.
+    pub page_seconds: u64,
+
+    pub pages: Vec<KioskPage>,
+}
+
+impl Default for KioskSettings {
+    fn default() -> Self {
+        KioskSettings {
+            enabled: false,
+            page_seconds: 15,
+            pages: vec![KioskPage::Current, KioskPage::Chart, KioskPage::Diagnostics],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    /// The layout version of the file the settings were loaded from, see `SETTINGS_VERSION`.
+    pub version: u32,
+
+    pub source: DataSourceKind,
+
+    /// The sensors on the local network that the app knows about. Readings come from the active one.
+    pub sensors: Vec<SensorSettings>,
+    pub active_sensor: usize,
+
+    /// How often the local sensor is polled for its 2-minute averages.
+    pub poll_interval_seconds: u64,
+
+    pub cloud: CloudSettings,
+    pub replay: ReplaySettings,
+    pub simulator: SimulatorSettings,
+
+    /// Poll the sensor's instantaneous `?live=true` values instead of its 2-minute averages.
+    pub live_mode: bool,
+
+    /// Append every raw `/json` response from the local sensor to this file, so the session can be replayed later.
+    pub recording: Option<PathBuf>,
+
+    /// Defaults to the language of the desktop session.
+    pub language: Language,
+    pub temperature_unit: TemperatureUnit,
+
+    /// Show the heat index, humidex, absolute humidity and comfort zone next to the temperature and humidity.
+    pub comfort_metrics: bool,
+
+    pub aqi_scheme: AqiScheme,
+    pub aqi_display: AqiDisplay,
+
+    /// Show the PM1.0, PM2.5 and PM10 concentrations of both channels below the AQI tiles.
+    pub concentrations: bool,
+
+    pub theme: Theme,
+    pub custom_theme: Palette,
+
+    /// Show the AQI categories in colorblind-safe colors instead of the EPA colors.
+    pub colorblind_safe: bool,
+
+    pub freshness: FreshnessSettings,
+    pub map: MapSettings,
+    pub archive: ArchiveSettings,
+    pub reports: ReportSettings,
+    pub window: WindowSettings,
+    pub kiosk: KioskSettings,
+    pub night: NightSettings,
+
+    pub notifications: NotificationSettings,
+    pub webhooks: Vec<WebhookSettings>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            source: DataSourceKind::default(),
+            sensors: vec![SensorSettings::default()],
+            active_sensor: 0,
+            poll_interval_seconds: 10,
+            cloud: CloudSettings::default(),
+            replay: ReplaySettings::default(),
+            simulator: SimulatorSettings::default(),
+            live_mode: false,
+            recording: None,
+            language: Language::default(),
+            temperature_unit: TemperatureUnit::default(),
+            comfort_metrics: false,
+            aqi_scheme: AqiScheme::default(),
+            aqi_display: AqiDisplay::default(),
+            concentrations: false,
+            theme: Theme::default(),
+            custom_theme: Palette::default(),
+            colorblind_safe: false,
+            freshness: FreshnessSettings::default(),
+            map: MapSettings::default(),
+            archive: ArchiveSettings::default(),
+            reports: ReportSettings::default(),
+            window: WindowSettings::default(),
+            kiosk: KioskSettings::default(),
+            night: NightSettings::default(),
+            notifications: NotificationSettings::default(),
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+
+    /// The file was written by a newer version of the app.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(error) => write!(f, "{}", error),
+            SettingsError::Parse(error) => write!(f, "invalid config: {}", error),
+            SettingsError::Serialize(error) => write!(f, "could not write the config: {}", error),
+            SettingsError::UnsupportedVersion(version) => {
+                write!(f, "the config is version {}, but this build only understands up to version {}", version, SETTINGS_VERSION)
+            }
+        }
+    }
+}
+
+impl Settings {
+    /// `$XDG_CONFIG_HOME/purple-air-gui/config.toml`, or the platform's equivalent.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|config_dir| config_dir.join("purple-air-gui").join("config.toml"))
+    }
+
+    /// Reads the settings from a config file. A missing file gives the default settings.
+    pub fn load(path: &Path) -> Result<Settings, SettingsError> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Settings::default()),
+            Err(error) => return Err(SettingsError::Io(error)),
+        };
+
+        Settings::parse(contents.as_str())
+    }
+
+    pub fn parse(contents: &str) -> Result<Settings, SettingsError> {
+        let mut settings: Settings = toml::from_str(contents).map_err(SettingsError::Parse)?;
+        if settings.version > SETTINGS_VERSION {
+            return Err(SettingsError::UnsupportedVersion(settings.version));
+        }
+
+        // Older layouts are upgraded here when the version is bumped.
+        settings.version = SETTINGS_VERSION;
+        if settings.sensors.is_empty() {
+            settings.sensors.push(SensorSettings::default());
+        }
+        settings.active_sensor = settings.active_sensor.min(settings.sensors.len() - 1);
+
+        Ok(settings)
+    }
+
+    /// Writes the settings to a temporary file next to the config and moves it into place,
+    /// so the file watcher never sees a half-written config.
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        let contents = toml::to_string_pretty(self).map_err(SettingsError::Serialize)?;
+
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).map_err(SettingsError::Io)?;
+        }
+        let temporary = path.with_extension("toml.tmp");
+        std::fs::write(&temporary, contents).map_err(SettingsError::Io)?;
+        std::fs::rename(&temporary, path).map_err(SettingsError::Io)
+    }
+
+    /// The sensor readings come from when the source is `Local`.
+    pub fn sensor(&self) -> &SensorSettings {
+        &self.sensors[self.active_sensor]
+    }
+
+    pub fn sensor_mut(&mut self) -> &mut SensorSettings {
+        &mut self.sensors[self.active_sensor]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_get_their_defaults() {
+        let settings = Settings::parse("live_mode = true\n[kiosk]\npage_seconds = 30\n").unwrap();
+
+        assert!(settings.live_mode);
+        assert_eq!(settings.kiosk.page_seconds, 30);
+        assert!(!settings.kiosk.enabled);
+        assert_eq!(settings.kiosk.pages.len(), 3);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.source, DataSourceKind::Local);
+        assert_eq!(settings.poll_interval_seconds, 10);
+        assert_eq!(settings.aqi_scheme, AqiScheme::UsEpa);
+        assert_eq!(settings.sensors.len(), 1);
+    }
+
+    #[test]
+    fn newer_files_are_refused() {
+        let contents = format!("version = {}\n", SETTINGS_VERSION + 1);
+
+        assert!(matches!(Settings::parse(contents.as_str()), Err(SettingsError::UnsupportedVersion(version)) if version == SETTINGS_VERSION + 1));
+    }
+
+    #[test]
+    fn older_files_are_upgraded() {
+        let settings = Settings::parse("version = 0\n").unwrap();
+
+        assert_eq!(settings.version, SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn an_empty_sensor_list_gets_the_default_sensor() {
+        let settings = Settings::parse("sensors = []\nactive_sensor = 3\n").unwrap();
+
+        assert_eq!(settings.sensors.len(), 1);
+        assert_eq!(settings.sensor().url, SensorSettings::default().url);
+        assert_eq!(settings.active_sensor, 0);
+    }
+
+    #[test]
+    fn the_active_sensor_is_clamped_to_the_list() {
+        let contents = "active_sensor = 5\n\n[[sensors]]\nurl = \"http://10.0.0.2/json\"\n\n[[sensors]]\nurl = \"http://10.0.0.3/json\"\n";
+        let settings = Settings::parse(contents).unwrap();
+
+        assert_eq!(settings.active_sensor, 1);
+        assert_eq!(settings.sensor().url, "http://10.0.0.3/json");
+    }
+
+    #[test]
+    fn saved_settings_load_unchanged() {
+        let directory = std::env::temp_dir().join(format!("purple-air-settings-{}", std::process::id()));
+        let path = directory.join("config.toml");
+
+        let mut settings = Settings::default();
+        settings.sensors.push(SensorSettings {
+            name: "Backyard".to_string(),
+            url: "http://[fd00::2]/json".to_string(),
+            sensor_id: Some("84:f3:eb:00:00:01".to_string()),
+        });
+        settings.active_sensor = 1;
+        settings.source = DataSourceKind::Simulator;
+        settings.aqi_scheme = AqiScheme::Lrapa;
+        settings.recording = Some(PathBuf::from("/tmp/session.jsonl"));
+        settings.language = Language::German;
+        settings.save(&path).unwrap();
+
+        let loaded = Settings::load(&path).unwrap();
+        assert!(!path.with_extension("toml.tmp").exists());
+        std::fs::remove_dir_all(directory).unwrap();
+
+        assert_eq!(toml::to_string(&loaded).unwrap(), toml::to_string(&settings).unwrap());
+        assert_eq!(loaded.sensor().label(), "Backyard");
+    }
+
+    #[test]
+    fn a_missing_file_gives_the_defaults() {
+        let path = std::env::temp_dir().join(format!("purple-air-settings-missing-{}", std::process::id())).join("config.toml");
+
+        let settings = Settings::load(&path).unwrap();
+        assert_eq!(toml::to_string(&settings).unwrap(), toml::to_string(&Settings::default()).unwrap());
+    }
+}
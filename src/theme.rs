@@ -0,0 +1,272 @@
+use crate::aqi::AqiCategory;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A color, written as `"#rrggbb"` in the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub fn from_hex(hex: &str) -> Option<Rgb> {
+        let hex = hex.strip_prefix('#')?;
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+        Some(Rgb(channel(0..2)?, channel(2..4)?, channel(4..6)?))
+    }
+
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+
+    /// The WCAG relative luminance, from 0 for black to 1 for white.
+    pub fn luminance(&self) -> f64 {
+        let linear = |channel: u8| {
+            let channel = channel as f64 / 255.0;
+            if channel <= 0.04045 { channel / 12.92 } else { ((channel + 0.055) / 1.055).powf(2.4) }
+        };
+        0.2126 * linear(self.0) + 0.7152 * linear(self.1) + 0.0722 * linear(self.2)
+    }
+
+    /// The WCAG contrast ratio between two colors, from 1 to 21.
+    pub fn contrast(&self, other: Rgb) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Black or white, whichever is easier to read on this color.
+    pub fn contrasting_text(&self) -> Rgb {
+        let (black, white) = (Rgb(0, 0, 0), Rgb(255, 255, 255));
+        if self.contrast(black) >= self.contrast(white) { black } else { white }
+    }
+
+    /// The same hue at a fraction of the brightness.
+    pub fn darken(&self, factor: f64) -> Rgb {
+        let scale = |channel: u8| (channel as f64 * factor.clamp(0.0, 1.0)).round() as u8;
+        Rgb(scale(self.0), scale(self.1), scale(self.2))
+    }
+}
+
+impl From<[u8; 3]> for Rgb {
+    fn from([red, green, blue]: [u8; 3]) -> Self {
+        Rgb(red, green, blue)
+    }
+}
+
+impl Serialize for Rgb {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.hex().as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rgb {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Rgb::from_hex(hex.as_str()).ok_or_else(|| serde::de::Error::custom(format!("expected a #rrggbb color, got {}", hex)))
+    }
+}
+
+/// Every color the GUI draws with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Palette {
+    pub background: Rgb,
+    pub border: Rgb,
+    pub text: Rgb,
+
+    /// Secondary text, like labels and button outlines.
+    pub muted: Rgb,
+
+    /// Text that needs attention, like live readings and errors.
+    pub accent: Rgb,
+
+    pub temperature: Rgb,
+    pub dew_point: Rgb,
+    pub humidity: Rgb,
+
+    /// The AQI category colors from Good to Hazardous.
+    pub aqi: [Rgb; 6],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::dark()
+    }
+}
+
+impl Palette {
+    pub fn dark() -> Palette {
+        Palette {
+            background: Rgb(35, 37, 52),
+            border: Rgb(25, 27, 42),
+            text: Rgb(255, 255, 255),
+            muted: Rgb(154, 154, 160),
+            accent: Rgb(239, 83, 80),
+            temperature: Rgb(255, 183, 77),
+            dew_point: Rgb(128, 203, 196),
+            humidity: Rgb(129, 212, 250),
+            aqi: AqiCategory::ALL.map(|category| Rgb::from(category.rgb8())),
+        }
+    }
+
+    pub fn light() -> Palette {
+        Palette {
+            background: Rgb(245, 245, 248),
+            border: Rgb(205, 205, 215),
+            text: Rgb(30, 30, 36),
+            muted: Rgb(95, 95, 105),
+            accent: Rgb(198, 40, 40),
+            temperature: Rgb(230, 120, 0),
+            dew_point: Rgb(0, 137, 123),
+            humidity: Rgb(2, 119, 189),
+            aqi: AqiCategory::ALL.map(|category| Rgb::from(category.rgb8())),
+        }
+    }
+
+    pub fn high_contrast() -> Palette {
+        Palette {
+            background: Rgb(0, 0, 0),
+            border: Rgb(255, 255, 255),
+            text: Rgb(255, 255, 255),
+            muted: Rgb(225, 225, 225),
+            accent: Rgb(255, 235, 59),
+            temperature: Rgb(255, 210, 0),
+            dew_point: Rgb(0, 255, 255),
+            humidity: Rgb(120, 190, 255),
+            aqi: AqiCategory::ALL.map(|category| Rgb::from(category.rgb8())),
+        }
+    }
+
+    /// A dim, low-contrast version of `palette` for night mode.
+    pub fn night(palette: &Palette) -> Palette {
+        let dim = |color: Rgb| color.darken(0.35);
+        Palette {
+            background: Rgb(0, 0, 0),
+            border: Rgb(0, 0, 0),
+            text: Rgb(95, 95, 100),
+            muted: Rgb(70, 70, 75),
+            accent: dim(palette.accent),
+            temperature: dim(palette.temperature),
+            dew_point: dim(palette.dew_point),
+            humidity: dim(palette.humidity),
+            aqi: palette.aqi.map(dim),
+        }
+    }
+
+    /// The palette with the measurement colors greyed out, for readings that may be out of date.
+    pub fn stale(&self) -> Palette {
+        let grey = self.muted;
+        Palette {
+            temperature: grey,
+            dew_point: grey,
+            humidity: grey,
+            aqi: [grey.darken(0.6); 6],
+            ..self.clone()
+        }
+    }
+
+    /// The same palette with the AQI categories in colors that stay distinguishable with any kind of color blindness.
+    /// They are the viridis colors, which get darker with every category, so the order also shows in grayscale.
+    pub fn colorblind_safe(mut self) -> Palette {
+        self.aqi = [
+            Rgb(253, 231, 37),
+            Rgb(122, 209, 81),
+            Rgb(34, 168, 132),
+            Rgb(42, 120, 142),
+            Rgb(65, 68, 135),
+            Rgb(68, 1, 84),
+        ];
+        self
+    }
+
+    pub fn category(&self, category: AqiCategory) -> Rgb {
+        self.aqi[category as usize]
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+
+    /// The palette under `[custom_theme]` in the config file.
+    Custom,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Dark, Theme::Light, Theme::HighContrast, Theme::Custom];
+
+    pub fn palette(&self, custom: &Palette) -> Palette {
+        match self {
+            Theme::Dark => Palette::dark(),
+            Theme::Light => Palette::light(),
+            Theme::HighContrast => Palette::high_contrast(),
+            Theme::Custom => custom.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_are_read_and_written_as_hex() {
+        assert_eq!(Rgb::from_hex("#ff8000"), Some(Rgb(255, 128, 0)));
+        assert_eq!(Rgb::from_hex("#FF8000"), Some(Rgb(255, 128, 0)));
+        assert_eq!(Rgb(255, 128, 0).hex(), "#ff8000");
+
+        assert_eq!(Rgb::from_hex("ff8000"), None);
+        assert_eq!(Rgb::from_hex("#ff80"), None);
+        assert_eq!(Rgb::from_hex("#ff80zz"), None);
+        assert_eq!(Rgb::from_hex("#ff80é"), None);
+    }
+
+    #[test]
+    fn a_custom_theme_only_needs_the_colors_it_changes() {
+        let custom: Palette = toml::from_str("background = \"#102030\"\naqi = [\"#000001\", \"#000002\", \"#000003\", \"#000004\", \"#000005\", \"#000006\"]\n").unwrap();
+
+        assert_eq!(custom.background, Rgb(16, 32, 48));
+        assert_eq!(custom.text, Palette::dark().text);
+        assert_eq!(custom.category(AqiCategory::Hazardous), Rgb(0, 0, 6));
+        assert_eq!(Theme::Custom.palette(&custom), custom);
+        assert_eq!(Theme::Light.palette(&custom), Palette::light());
+
+        assert!(toml::from_str::<Palette>("text = \"white\"\n").is_err());
+    }
+
+    #[test]
+    fn the_colorblind_safe_categories_get_darker_in_order() {
+        let palette = Palette::dark().colorblind_safe();
+
+        let luminances = AqiCategory::ALL.map(|category| palette.category(category).luminance());
+        assert!(luminances.windows(2).all(|pair| pair[0] > pair[1]));
+        // Neighbouring categories stay apart in grayscale too.
+        let colors = AqiCategory::ALL.map(|category| palette.category(category));
+        assert!(colors.windows(2).all(|pair| pair[0].contrast(pair[1]) > 1.3));
+
+        // Only the category colors change.
+        assert_eq!(Palette { aqi: Palette::dark().aqi, ..palette.clone() }, Palette::dark());
+        assert_ne!(palette.category(AqiCategory::Good), Palette::dark().category(AqiCategory::Good));
+    }
+
+    #[test]
+    fn tile_text_contrasts_with_the_category_color() {
+        assert_eq!(Rgb(0, 0, 0).contrast(Rgb(255, 255, 255)), 21.0);
+        assert_eq!(Rgb(90, 90, 90).contrast(Rgb(90, 90, 90)), 1.0);
+
+        let dark = Palette::dark();
+        assert_eq!(dark.category(AqiCategory::Moderate).contrasting_text(), Rgb(0, 0, 0));
+        assert_eq!(dark.category(AqiCategory::Hazardous).contrasting_text(), Rgb(255, 255, 255));
+
+        for palette in [dark.clone(), dark.colorblind_safe()] {
+            for category in AqiCategory::ALL {
+                let color = palette.category(category);
+                assert!(color.contrast(color.contrasting_text()) >= 4.5, "{:?} on {}", category, color.hex());
+            }
+        }
+    }
+}
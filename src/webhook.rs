@@ -0,0 +1,382 @@
+use crate::aqi::AqiCategory;
+use crate::i18n::Localizer;
+use crate::notifications::CategoryChange;
+use crate::sensor_data::LocalSensorData;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+
+const DELIVERY_LOG_CAPACITY: usize = 100;
+/// Retries beyond these would keep a delivery going for hours.
+const MAX_RETRIES: u32 = 10;
+const MAX_BACKOFF_SECONDS: u64 = 300;
+
+/// An HTTP endpoint that receives alerts and periodic summaries, e.g. a Slack, Teams or ntfy webhook.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub url: String,
+
+    /// The request body. `{{Name}}` placeholders are replaced with the `LocalSensorData` field of the same JSON name,
+    /// e.g. `{{pm2.5_aqi}}` or `{{current_temp_f}}`, or with one of the computed values
+    /// `{{aqi}}`, `{{category}}`, `{{kind}}`, `{{message}}` and `{{timestamp}}`.
+    pub template: String,
+
+    pub content_type: String,
+
+    /// Post when the PM2.5 AQI category changes.
+    pub send_alerts: bool,
+
+    /// Post a summary of the current reading every N minutes.
+    pub summary_interval_minutes: Option<u64>,
+
+    /// How many times a failed delivery is retried before it is given up.
+    pub max_retries: u32,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        WebhookSettings {
+            url: String::new(),
+            template: r#"{"text": "{{message}}"}"#.to_string(),
+            content_type: "application/json".to_string(),
+            send_alerts: true,
+            summary_interval_minutes: None,
+            max_retries: 3,
+        }
+    }
+}
+
+impl WebhookSettings {
+    /// Whether placeholder values have to be escaped as JSON string content, e.g. for `application/json`.
+    pub fn is_json(&self) -> bool {
+        self.content_type.to_ascii_lowercase().contains("json")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadKind {
+    Alert,
+    Summary,
+}
+
+impl PayloadKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayloadKind::Alert => "alert",
+            PayloadKind::Summary => "summary",
+        }
+    }
+}
+
+/// A rendered payload waiting to be posted.
+#[derive(Debug, Clone)]
+pub struct PendingDelivery {
+    pub webhook: WebhookSettings,
+    pub kind: PayloadKind,
+    pub body: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum DeliveryOutcome {
+    Delivered(u16),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DeliveryRecord {
+    pub time: DateTime<Utc>,
+    pub url: String,
+    pub kind: PayloadKind,
+    pub attempts: u32,
+    pub outcome: DeliveryOutcome,
+}
+
+impl DeliveryRecord {
+    /// One line for the diagnostics page, with the time in the local time zone.
+    pub fn describe(&self, localizer: &Localizer) -> String {
+        let time = localizer.time(&self.time.with_timezone(&chrono::Local));
+        let args = [("time", time.into()), ("kind", localizer.name(&self.kind).into()), ("url", self.url.as_str().into())];
+        match &self.outcome {
+            DeliveryOutcome::Delivered(status) => localizer.format("delivery-delivered", args.into_iter().chain([("status", status.to_string().into())])),
+            DeliveryOutcome::Failed(error) => {
+                localizer.format("delivery-failed", args.into_iter().chain([("attempts", self.attempts.into()), ("error", error.as_str().into())]))
+            }
+        }
+    }
+}
+
+/// The most recent deliveries, newest last.
+#[derive(Default)]
+pub struct DeliveryLog {
+    records: VecDeque<DeliveryRecord>,
+}
+
+impl DeliveryLog {
+    pub fn push(&mut self, record: DeliveryRecord) {
+        if self.records.len() == DELIVERY_LOG_CAPACITY {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// The deliveries, newest first.
+    pub fn recent(&self) -> impl Iterator<Item = &DeliveryRecord> {
+        self.records.iter().rev()
+    }
+}
+
+/// Decides which webhooks are due for a payload on every new reading.
+#[derive(Default)]
+pub struct WebhookDispatcher {
+    /// When the last summary was sent, keyed by webhook URL.
+    last_summaries: Vec<(String, DateTime<Utc>)>,
+}
+
+impl WebhookDispatcher {
+    pub fn prepare(
+        &mut self,
+        webhooks: &[WebhookSettings],
+        sensor_data: &LocalSensorData,
+        change: Option<&CategoryChange>,
+        localizer: &Localizer,
+        now: DateTime<Utc>,
+    ) -> Vec<PendingDelivery> {
+        let mut pending = Vec::new();
+
+        for webhook in webhooks.iter().filter(|webhook| !webhook.url.is_empty()) {
+            if let Some(change) = change && webhook.send_alerts {
+                let message = format!("{}. {}", change.summary(localizer), change.body(localizer));
+                pending.push(PendingDelivery {
+                    webhook: webhook.clone(),
+                    kind: PayloadKind::Alert,
+                    body: render(&webhook.template, webhook.is_json(), sensor_data, PayloadKind::Alert, &message, localizer, now),
+                });
+            }
+
+            if let Some(minutes) = webhook.summary_interval_minutes && self.summary_due(&webhook.url, Duration::minutes(minutes as i64), now) {
+                pending.push(PendingDelivery {
+                    webhook: webhook.clone(),
+                    kind: PayloadKind::Summary,
+                    body: render(
+                        &webhook.template,
+                        webhook.is_json(),
+                        sensor_data,
+                        PayloadKind::Summary,
+                        &summary_message(sensor_data, localizer),
+                        localizer,
+                        now,
+                    ),
+                });
+            }
+        }
+
+        pending
+    }
+
+    fn summary_due(&mut self, url: &str, interval: Duration, now: DateTime<Utc>) -> bool {
+        match self.last_summaries.iter_mut().find(|(last_url, _)| last_url == url) {
+            Some((_, last)) if now - *last < interval => false,
+            Some((_, last)) => {
+                *last = now;
+                true
+            }
+            None => {
+                self.last_summaries.push((url.to_string(), now));
+                true
+            }
+        }
+    }
+}
+
+fn summary_message(sensor_data: &LocalSensorData, localizer: &Localizer) -> String {
+    let mut parts = Vec::new();
+    if let Some(aqi) = sensor_data.pm2_5_aqi {
+        parts.push(localizer.format("webhook-summary-aqi", [
+            ("aqi", aqi.round().into()),
+            ("category", localizer.name(&AqiCategory::from_aqi(aqi)).into()),
+        ]));
+    }
+    if let Some(current_temp_f) = sensor_data.current_temp_f {
+        parts.push(localizer.format("temperature-value", [("value", current_temp_f.into()), ("unit", "fahrenheit".into())]));
+    }
+    if let Some(current_humidity) = sensor_data.current_humidity {
+        parts.push(localizer.format("webhook-summary-humidity", [("value", current_humidity.into())]));
+    }
+
+    let place = format!("{} ({})", sensor_data.geo, sensor_data.place);
+    if parts.is_empty() { place } else { format!("{}: {}", place, parts.join(", ")) }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The value as the content of a JSON string, without the surrounding quotes.
+fn escape_json(value: &str) -> String {
+    let quoted = Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Replaces every `{{Name}}` placeholder in the template. Unknown placeholders are left untouched so typos stay visible.
+/// With `json` the values are escaped, so quotes or line breaks in a message cannot break the payload.
+/// The category is named in the language of `localizer`.
+pub fn render(template: &str, json: bool, sensor_data: &LocalSensorData, kind: PayloadKind, message: &str, localizer: &Localizer, now: DateTime<Utc>) -> String {
+    let fields = serde_json::to_value(sensor_data).unwrap_or(Value::Null);
+
+    let lookup = |name: &str| -> Option<String> {
+        match name {
+            "aqi" => sensor_data.pm2_5_aqi.map(|aqi| (aqi.round() as u64).to_string()),
+            "category" => sensor_data.pm2_5_aqi.map(|aqi| localizer.name(&AqiCategory::from_aqi(aqi))),
+            "kind" => Some(kind.name().to_string()),
+            "message" => Some(message.to_string()),
+            "timestamp" => Some(now.to_rfc3339()),
+            _ => fields.get(name).map(value_to_string),
+        }
+    };
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+
+        let Some(end) = after_open.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let name = after_open[..end].trim();
+        match lookup(name) {
+            Some(value) if json => rendered.push_str(escape_json(&value).as_str()),
+            Some(value) => rendered.push_str(value.as_str()),
+            None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after_open[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    rendered
+}
+
+/// How long to wait before the next attempt, doubling every time up to `MAX_BACKOFF_SECONDS`.
+fn backoff(attempts: u32) -> std::time::Duration {
+    std::time::Duration::from_secs(2u64.saturating_pow(attempts).min(MAX_BACKOFF_SECONDS))
+}
+
+/// Posts the payload, retrying with exponential backoff on connection errors and non-success status codes.
+pub async fn deliver(pending: PendingDelivery) -> DeliveryRecord {
+    let client = reqwest::Client::new();
+    let mut attempts = 0;
+
+    let outcome = loop {
+        attempts += 1;
+
+        let result = client
+            .post(pending.webhook.url.as_str())
+            .header(reqwest::header::CONTENT_TYPE, pending.webhook.content_type.as_str())
+            .body(pending.body.clone())
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await;
+
+        let error = match result {
+            Ok(response) if response.status().is_success() => break DeliveryOutcome::Delivered(response.status().as_u16()),
+            Ok(response) => format!("HTTP {}", response.status().as_u16()),
+            Err(error) => error.to_string(),
+        };
+
+        if attempts > pending.webhook.max_retries.min(MAX_RETRIES) {
+            break DeliveryOutcome::Failed(error);
+        }
+
+        tokio::time::sleep(backoff(attempts)).await;
+    };
+
+    DeliveryRecord {
+        time: Utc::now(),
+        url: pending.webhook.url,
+        kind: pending.kind,
+        attempts,
+        outcome,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use chrono::TimeZone;
+
+    fn sensor_data() -> LocalSensorData {
+        serde_json::from_str(include_str!("../fixtures/pa-ii.json")).unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 29, 22, 45, 0).unwrap()
+    }
+
+    #[test]
+    fn render_replaces_fields_and_computed_values() {
+        let rendered = render(
+            "{{aqi}} {{ kind }} {{Geo}} {{current_temp_f}} {{timestamp}}",
+            false,
+            &sensor_data(),
+            PayloadKind::Summary,
+            "",
+            &Localizer::default(),
+            now(),
+        );
+        assert_eq!(rendered, "31 summary PurpleAir-c8ee 77 2025-06-29T22:45:00+00:00");
+    }
+
+    #[test]
+    fn render_names_the_category_in_the_language() {
+        let render_category = |language| render("{{category}}", false, &sensor_data(), PayloadKind::Alert, "", &Localizer::new(language), now());
+        assert_eq!(render_category(Language::English), "Good");
+        assert_eq!(render_category(Language::German), "Gut");
+    }
+
+    #[test]
+    fn render_keeps_unknown_and_unclosed_placeholders() {
+        let rendered = render("{{nope}} {{aqi}} {{aqi", false, &sensor_data(), PayloadKind::Alert, "", &Localizer::default(), now());
+        assert_eq!(rendered, "{{nope}} 31 {{aqi");
+    }
+
+    #[test]
+    fn render_escapes_json_values() {
+        let message = "Air is \"bad\"\nStay inside \\ close windows";
+        let rendered = render(r#"{"text": "{{message}}"}"#, true, &sensor_data(), PayloadKind::Alert, message, &Localizer::default(), now());
+        let payload: Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(payload["text"], message);
+    }
+
+    #[test]
+    fn render_leaves_plain_text_alone() {
+        let rendered = render("{{message}}", false, &sensor_data(), PayloadKind::Alert, "say \"hi\"", &Localizer::default(), now());
+        assert_eq!(rendered, "say \"hi\"");
+    }
+
+    #[test]
+    fn json_content_types() {
+        let mut webhook = WebhookSettings::default();
+        assert!(webhook.is_json());
+        webhook.content_type = "application/JSON; charset=utf-8".to_string();
+        assert!(webhook.is_json());
+        webhook.content_type = "text/plain".to_string();
+        assert!(!webhook.is_json());
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(backoff(1).as_secs(), 2);
+        assert_eq!(backoff(3).as_secs(), 8);
+        assert_eq!(backoff(9).as_secs(), MAX_BACKOFF_SECONDS);
+        assert_eq!(backoff(u32::MAX).as_secs(), MAX_BACKOFF_SECONDS);
+    }
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::TestServer;
+use purple_air_gui::cloud::{CloudClient, CloudError, CloudSettings};
+
+fn response(last_seen: i64) -> String {
+    format!(
+        r#"{{"api_version": "V1.0.11", "sensor": {{"sensor_index": 1234, "name": "Backyard", "location_type": 1,
+        "latitude": 37.77, "longitude": -122.42, "last_seen": {}, "firmware_version": "7.02", "hardware": "2.0+BME280+PMSX003-B+PMSX003-A",
+        "uptime": 86400, "rssi": -59, "memory": 19008, "humidity": 41.4, "temperature": 77.2, "pressure": 1012.5,
+        "pm2.5_atm_a": 9.0, "pm2.5_atm_b": 35.4, "pm10.0_atm_a": 20.0, "0.3_um_count_a": 672.4, "0.3_um_count_b": 650.0}}}}"#,
+        last_seen
+    )
+}
+
+fn settings(server: &TestServer, refresh_minutes: u64) -> CloudSettings {
+    CloudSettings {
+        api_key: "read-key".to_string(),
+        sensor_index: 1234,
+        api_url: format!("{}/", server.url),
+        refresh_minutes,
+    }
+}
+
+#[tokio::test]
+async fn requests_the_sensor_with_the_key() {
+    let server = TestServer::start(vec![(200, response(1_751_237_054))]).await;
+    let mut client = CloudClient::new(settings(&server, 10));
+
+    client.fetch().await.unwrap().unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    let (path, query) = requests[0].path.split_once('?').unwrap();
+    assert_eq!(path, "/sensors/1234");
+    let fields = query.strip_prefix("fields=").unwrap().replace("%2C", ",");
+    for field in ["name", "last_seen", "pm2.5_atm_a", "pm2.5_atm_b", "0.3_um_count_a", "10.0_um_count_b"] {
+        assert!(fields.split(',').any(|requested| requested == field), "{} is not requested", field);
+    }
+    assert_eq!(requests[0].header("x-api-key"), Some("read-key"));
+}
+
+#[tokio::test]
+async fn maps_the_cloud_fields() {
+    let server = TestServer::start(vec![(200, response(1_751_237_054))]).await;
+    let mut client = CloudClient::new(settings(&server, 10));
+
+    let sensor_data = client.fetch().await.unwrap().unwrap();
+
+    assert_eq!(sensor_data.sensor_id, "1234");
+    assert_eq!(sensor_data.geo, "Backyard");
+    assert_eq!(sensor_data.place, "inside");
+    assert_eq!(sensor_data.date_time.timestamp(), 1_751_237_054);
+    assert_eq!(sensor_data.hardware_version, "2.0");
+    assert_eq!(sensor_data.current_temp_f, Some(77));
+    assert_eq!(sensor_data.current_humidity, Some(41));
+    assert_eq!(sensor_data.pm2_5_atm, Some(9.0));
+    assert_eq!(sensor_data.pm2_5_atm_b, Some(35.4));
+    assert_eq!(sensor_data.pm2_5_aqi.map(f64::round), Some(50.0));
+    assert_eq!(sensor_data.pm2_5_aqi_b.map(f64::round), Some(100.0));
+    assert_eq!(sensor_data.p_0_3_um, Some(672.4));
+    assert_eq!(sensor_data.pm1_0_atm, None);
+}
+
+#[tokio::test]
+async fn does_not_ask_again_before_the_refresh() {
+    let server = TestServer::start(vec![(200, response(1_751_237_054))]).await;
+    let mut client = CloudClient::new(settings(&server, 10));
+
+    assert!(client.fetch().await.unwrap().is_some());
+    assert!(client.fetch().await.unwrap().is_none());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn only_returns_new_readings() {
+    let server = TestServer::start(vec![(200, response(1_751_237_054)), (200, response(1_751_237_054)), (200, response(1_751_237_174))]).await;
+    let mut client = CloudClient::new(settings(&server, 0));
+
+    assert!(client.fetch().await.unwrap().is_some());
+    assert!(client.fetch().await.unwrap().is_none());
+    let sensor_data = client.fetch().await.unwrap().unwrap();
+    assert_eq!(sensor_data.date_time.timestamp(), 1_751_237_174);
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn needs_an_api_key() {
+    let server = TestServer::start(vec![(200, response(1_751_237_054))]).await;
+    let mut client = CloudClient::new(CloudSettings {
+        api_key: String::new(),
+        ..settings(&server, 10)
+    });
+
+    assert!(matches!(client.fetch().await, Err(CloudError::MissingApiKey)));
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn reports_error_statuses() {
+    let server = TestServer::start(vec![(403, r#"{"error": "ApiKeyInvalidError"}"#.to_string()), (200, response(1_751_237_054))]).await;
+    let mut client = CloudClient::new(settings(&server, 10));
+
+    match client.fetch().await {
+        Err(CloudError::Status(status, body)) => {
+            assert_eq!(status, 403);
+            assert!(body.contains("ApiKeyInvalidError"));
+        }
+        other => panic!("expected an HTTP error, got {:?}", other.map(|sensor_data| sensor_data.is_some())),
+    }
+    // A failed request is not cached.
+    assert!(client.fetch().await.unwrap().is_some());
+}
@@ -0,0 +1,133 @@
+//! Runs the `mock_sensor` binary on a free port and reads it through the local data source.
+
+use purple_air_gui::data_source::{DataSource, LocalSource, SourceError};
+use reqwest::StatusCode;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/pa-ii.json");
+
+/// The mock sensor process, killed when dropped.
+struct MockSensor {
+    child: Child,
+    url: String,
+}
+
+impl MockSensor {
+    fn start(args: &[&str]) -> MockSensor {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_mock_sensor"))
+            .args(["--port", "0", "--fixture", FIXTURE, "--seed", "3"])
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        let url = line.trim().rsplit(' ').next().unwrap().to_string();
+        assert!(url.starts_with("http://127.0.0.1:") && !url.starts_with("http://127.0.0.1:0/"), "unexpected output: {}", line);
+
+        // The mock logs every request, keep reading so it never blocks on a full pipe.
+        std::thread::spawn(move || std::io::copy(&mut stdout, &mut std::io::sink()));
+
+        MockSensor { child, url }
+    }
+
+    fn source(&self) -> LocalSource {
+        LocalSource::new(self.url.clone(), Duration::ZERO, false, None)
+    }
+}
+
+impl Drop for MockSensor {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Polls until a failure is followed by a success, checking the health after every reading. Returns the errors.
+async fn poll_until_recovered(source: &mut LocalSource) -> Vec<SourceError> {
+    let mut errors = Vec::new();
+    let mut readings = 0;
+    for _ in 0..50 {
+        match source.next_reading().await {
+            Ok(reading) => {
+                readings += 1;
+                assert_eq!(reading.sensor_data.sensor_id, "84:f3:eb:7b:c8:ee");
+                assert_eq!(source.health().consecutive_failures, 0);
+                assert!(source.health().last_success.is_some());
+                if !errors.is_empty() {
+                    break;
+                }
+            }
+            Err(error) => {
+                errors.push(error);
+                assert!(source.health().consecutive_failures > 0);
+                assert!(source.health().last_error.is_some());
+            }
+        }
+        assert_eq!(source.health().total_readings, readings);
+        assert_eq!(source.health().total_failures, errors.len() as u64);
+    }
+
+    assert!(!errors.is_empty(), "the mock never failed");
+    assert_eq!(source.health().consecutive_failures, 0, "the source never recovered");
+    errors
+}
+
+#[tokio::test]
+async fn reads_the_fixture() {
+    let mock = MockSensor::start(&[]);
+    let mut source = mock.source();
+
+    let reading = source.next_reading().await.unwrap();
+
+    assert_eq!(reading.sensor_data.geo, "PurpleAir-c8ee");
+    assert_eq!(source.health().total_readings, 1);
+    assert_eq!(source.health().total_failures, 0);
+}
+
+#[tokio::test]
+async fn waits_for_a_slow_sensor() {
+    let mock = MockSensor::start(&["--latency-ms", "300"]);
+    let mut source = mock.source();
+
+    let started = Instant::now();
+    source.next_reading().await.unwrap();
+
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    assert_eq!(source.health().total_readings, 1);
+    assert_eq!(source.health().total_failures, 0);
+}
+
+#[tokio::test]
+async fn recovers_from_server_errors() {
+    let mock = MockSensor::start(&["--error-rate", "0.5"]);
+    let mut source = mock.source();
+
+    let errors = poll_until_recovered(&mut source).await;
+
+    assert!(errors.iter().all(|error| matches!(error, SourceError::Status(StatusCode::INTERNAL_SERVER_ERROR))));
+}
+
+#[tokio::test]
+async fn recovers_from_truncated_bodies() {
+    let mock = MockSensor::start(&["--truncate-rate", "0.5"]);
+    let mut source = mock.source();
+
+    let errors = poll_until_recovered(&mut source).await;
+
+    assert!(errors.iter().all(|error| matches!(error, SourceError::Request(_))));
+}
+
+#[tokio::test]
+async fn recovers_from_malformed_json() {
+    let mock = MockSensor::start(&["--malformed-rate", "0.5"]);
+    let mut source = mock.source();
+
+    let errors = poll_until_recovered(&mut source).await;
+
+    assert!(errors.iter().all(|error| matches!(error, SourceError::Parse(_))));
+}
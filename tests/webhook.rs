@@ -0,0 +1,67 @@
+mod common;
+
+use common::TestServer;
+use purple_air_gui::webhook::{self, DeliveryOutcome, PayloadKind, PendingDelivery, WebhookSettings};
+
+fn pending(url: &str, max_retries: u32) -> PendingDelivery {
+    PendingDelivery {
+        webhook: WebhookSettings {
+            url: url.to_string(),
+            max_retries,
+            ..WebhookSettings::default()
+        },
+        kind: PayloadKind::Alert,
+        body: r#"{"text": "Air quality is unhealthy"}"#.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn posts_the_payload() {
+    let server = TestServer::start(vec![(200, String::new())]).await;
+
+    let record = webhook::deliver(pending(&server.url, 3)).await;
+
+    assert!(matches!(record.outcome, DeliveryOutcome::Delivered(200)));
+    assert_eq!(record.attempts, 1);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].header("content-type"), Some("application/json"));
+    assert_eq!(requests[0].body, r#"{"text": "Air quality is unhealthy"}"#);
+}
+
+#[tokio::test]
+async fn retries_until_delivered() {
+    let server = TestServer::start(vec![(500, String::new()), (204, String::new())]).await;
+
+    let record = webhook::deliver(pending(&server.url, 3)).await;
+
+    assert!(matches!(record.outcome, DeliveryOutcome::Delivered(204)));
+    assert_eq!(record.attempts, 2);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn gives_up_after_the_retries() {
+    let server = TestServer::start(vec![(503, String::new())]).await;
+
+    let record = webhook::deliver(pending(&server.url, 1)).await;
+
+    match record.outcome {
+        DeliveryOutcome::Failed(error) => assert_eq!(error, "HTTP 503"),
+        other => panic!("expected a failure, got {:?}", other),
+    }
+    assert_eq!(record.attempts, 2);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn does_not_retry_without_retries() {
+    let server = TestServer::start(vec![(500, String::new())]).await;
+
+    let record = webhook::deliver(pending(&server.url, 0)).await;
+
+    assert!(matches!(record.outcome, DeliveryOutcome::Failed(_)));
+    assert_eq!(record.attempts, 1);
+    assert_eq!(server.requests().len(), 1);
+}